@@ -1,6 +1,13 @@
-use chumsky::prelude::*;
+use std::path::Path;
+
+use crochet_ast::{FileId, SourceFile};
+use crochet_codegen::d_ts::{codegen_d_ts, codegen_d_ts_with_source_map, DtsOutput};
+use crochet_codegen::js::{
+    codegen_js_with_options, codegen_js_with_source_map, JsOptions, JsOutput, JsxEmit,
+};
+use crochet_codegen::source_map::SourceMapEmit;
 use crochet_infer::*;
-use crochet_parser::parser;
+use crochet_parser::parse;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -24,6 +31,8 @@ pub fn foo() {
 pub struct CompileResult {
     js: String,
     dts: String,
+    source_map: Option<String>,
+    dts_map: Option<String>,
 }
 
 #[wasm_bindgen]
@@ -42,17 +51,130 @@ impl CompileResult {
     pub fn dts(&self) -> String {
         self.dts.to_owned()
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn source_map(&self) -> Option<String> {
+        self.source_map.to_owned()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dts_map(&self) -> Option<String> {
+        self.dts_map.to_owned()
+    }
+}
+
+const DEFAULT_SOURCE_NAME: &str = "input.crochet";
+
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct CompileOptions {
+    js: JsOptions,
+    source_name: Option<String>,
+    source_map: Option<SourceMapEmit>,
+    dts_map: Option<SourceMapEmit>,
+}
+
+#[wasm_bindgen]
+impl CompileOptions {
+    // `jsx` must be one of "automatic", "classic" or "preserve".  `pragma` and
+    // `pragma_frag` are only used by "classic" and `import_source` is only
+    // used by "automatic".  Options that aren't provided use React's defaults.
+    //
+    // `source_map` must be one of "inline" or "file" if provided and applies to
+    // both the .js and .d.ts outputs.  "file" links to `${name}.js.map` and
+    // `${name}.d.ts.map`, where `name` is the file name of `source_name`
+    // without its directory or .crochet extension, which should contain
+    // `CompileResult.source_map` and `CompileResult.dts_map` respectively.
+    // `source_name` defaults to "input.crochet".
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        jsx: &str,
+        pragma: Option<String>,
+        pragma_frag: Option<String>,
+        import_source: Option<String>,
+        source_map: Option<String>,
+        source_name: Option<String>,
+    ) -> Result<CompileOptions, JsValue> {
+        let jsx = match jsx {
+            "automatic" => match import_source {
+                Some(import_source) => JsxEmit::Automatic { import_source },
+                None => JsxEmit::default(),
+            },
+            "classic" => JsxEmit::Classic {
+                pragma: pragma.unwrap_or_else(|| String::from("React.createElement")),
+                pragma_frag: pragma_frag.unwrap_or_else(|| String::from("React.Fragment")),
+            },
+            "preserve" => JsxEmit::Preserve,
+            _ => return Err(JsValue::from_str(&format!("Invalid jsx option '{jsx}'"))),
+        };
+
+        // Map files are linked relative to the output so only the file name
+        // is used, e.g. `src/foo.crochet` links to `foo.js.map`.
+        let name = source_name.as_deref().unwrap_or(DEFAULT_SOURCE_NAME);
+        let name = Path::new(name)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(name);
+        let stem = name.strip_suffix(".crochet").unwrap_or(name);
+        let map_emit = |ext: &str| -> Result<Option<SourceMapEmit>, JsValue> {
+            let emit = match source_map.as_deref() {
+                Some("inline") => SourceMapEmit::Inline,
+                Some("file") => SourceMapEmit::File {
+                    map_file: format!("{stem}.{ext}.map"),
+                },
+                Some(source_map) => {
+                    return Err(JsValue::from_str(&format!(
+                        "Invalid source_map option '{source_map}'"
+                    )))
+                }
+                None => return Ok(None),
+            };
+            Ok(Some(emit))
+        };
+
+        Ok(CompileOptions {
+            js: JsOptions { jsx },
+            source_map: map_emit("js")?,
+            dts_map: map_emit("d.ts")?,
+            source_name,
+        })
+    }
 }
 
 #[wasm_bindgen]
-pub fn compile(input: &str) -> CompileResult {
-    let program = parser().parse(input).unwrap();
+pub fn compile(input: &str, options: Option<CompileOptions>) -> CompileResult {
+    let options = options.unwrap_or_default();
+    let source_name = options
+        .source_name
+        .as_deref()
+        .unwrap_or(DEFAULT_SOURCE_NAME);
+    let file = SourceFile::new(FileId(0), source_name, input);
+    let program = parse(&file).unwrap();
 
-    let js = crochet_codegen::js::codegen_js(&program);
+    let (js, source_map) = match &options.source_map {
+        Some(emit) => {
+            let JsOutput { js, source_map } =
+                codegen_js_with_source_map(&program, &file, &options.js, emit);
+            (js, Some(source_map))
+        }
+        None => (codegen_js_with_options(&program, &options.js), None),
+    };
 
     // TODO: return errors as part of CompileResult
     let ctx = infer_prog(&program).unwrap();
-    let dts = crochet_codegen::d_ts::codegen_d_ts(&program, &ctx);
+    let (dts, dts_map) = match &options.dts_map {
+        Some(emit) => {
+            let DtsOutput { d_ts, source_map } =
+                codegen_d_ts_with_source_map(&program, &ctx, &file, emit);
+            (d_ts, Some(source_map))
+        }
+        None => (codegen_d_ts(&program, &ctx), None),
+    };
 
-    CompileResult { js, dts }
+    CompileResult {
+        js,
+        dts,
+        source_map,
+        dts_map,
+    }
 }
@@ -1,13 +1,38 @@
-use chumsky::prelude::*;
-use crochet_parser::parser;
+use crochet_ast::{FileId, Program, SourceFile};
+use crochet_parser::parse;
 
 use crochet_codegen::js::*;
+use crochet_codegen::source_map::*;
+
+fn parse_program(input: &str) -> Program {
+    let file = SourceFile::new(FileId(0), "input.crochet", input);
+    parse(&file).unwrap()
+}
 
 fn compile(input: &str) -> String {
-    let program = parser().parse(input).unwrap();
+    let program = parse_program(input);
     codegen_js(&program)
 }
 
+fn compile_with_jsx(input: &str, jsx: JsxEmit) -> String {
+    let program = parse_program(input);
+    codegen_js_with_options(&program, &JsOptions { jsx })
+}
+
+fn compile_with_source_map(input: &str, emit: SourceMapEmit) -> JsOutput {
+    let file = SourceFile::new(FileId(0), "input.crochet", input);
+    let program = parse(&file).unwrap();
+    codegen_js_with_source_map(&program, &file, &JsOptions::default(), &emit)
+}
+
+// Returns the (line, col) in the source of the token at `line` and `col` in
+// the generated code.
+fn lookup_source_pos(output: &JsOutput, line: u32, col: u32) -> (u32, u32) {
+    let source_map = sourcemap::SourceMap::from_slice(output.source_map.as_bytes()).unwrap();
+    let token = source_map.lookup_token(line, col).unwrap();
+    (token.get_src_line(), token.get_src_col())
+}
+
 #[test]
 fn string_literal() {
     insta::assert_snapshot!(compile("\"hello\""), @r###""hello";"###);
@@ -245,6 +270,24 @@ fn js_print_object() {
     "###);
 }
 
+#[test]
+fn js_print_lambda_returning_object_with_type_assertion() {
+    let src = r#"
+    type Foo = {a: number}
+    let foo = () => {a: 1} as Foo
+    let bar = () => {a: 1} satisfies Foo
+    "#;
+    insta::assert_snapshot!(compile(src), @r###"
+    ;
+    export const foo = ()=>({
+            a: 1
+        });
+    export const bar = ()=>({
+            a: 1
+        });
+    "###);
+}
+
 #[test]
 fn codegen_jsx() {
     insta::assert_snapshot!(compile("<Foo>Hello</Foo>"), @r###"
@@ -296,8 +339,192 @@ fn codegen_jsx() {
     "###);
 }
 
+#[test]
+fn codegen_jsx_fragments_and_attributes() {
+    insta::assert_snapshot!(compile("<>Hello, {world}</>"), @r###"
+    import { jsxs as _jsxs, Fragment as _Fragment } from "react/jsx-runtime";
+    _jsxs(_Fragment, {
+        children: [
+            "Hello, ",
+            world
+        ]
+    });
+    "###);
+    insta::assert_snapshot!(compile("<Foo {...props} bar={baz} />"), @r###"
+    import { jsx as _jsx } from "react/jsx-runtime";
+    _jsx(Foo, {
+        ...props,
+        bar: baz
+    });
+    "###);
+    insta::assert_snapshot!(compile("<input disabled />"), @r###"
+    import { jsx as _jsx } from "react/jsx-runtime";
+    _jsx("input", {
+        disabled: true
+    });
+    "###);
+    insta::assert_snapshot!(compile("<Ctx.Provider value={5}></Ctx.Provider>"), @r###"
+    import { jsx as _jsx } from "react/jsx-runtime";
+    _jsx(Ctx.Provider, {
+        value: 5
+    });
+    "###);
+    insta::assert_snapshot!(compile("<div data-foo=\"bar\" aria-label=\"baz\" />"), @r###"
+    import { jsx as _jsx } from "react/jsx-runtime";
+    _jsx("div", {
+        "data-foo": "bar",
+        "aria-label": "baz"
+    });
+    "###);
+    insta::assert_snapshot!(compile("<Foo><><Bar /></>{...rest}</Foo>"), @r###"
+    import { jsx as _jsx, jsxs as _jsxs, Fragment as _Fragment } from "react/jsx-runtime";
+    _jsxs(Foo, {
+        children: [
+            _jsx(_Fragment, {
+                children: _jsx(Bar, {})
+            }),
+            rest
+        ]
+    });
+    "###);
+}
+
+#[test]
+fn codegen_jsx_preserve() {
+    let src = "<><Foo bar={baz} disabled {...rest}>Hello, {world}!</Foo><input /></>";
+    insta::assert_snapshot!(compile_with_jsx(src, JsxEmit::Preserve), @r###"
+    <><Foo bar={baz} disabled {...rest}>Hello, {world}!</Foo><input /></>;
+    "###);
+}
+
+#[test]
+fn codegen_jsx_classic() {
+    let src = "<><Foo bar={baz} disabled {...rest}>Hello, {world}!</Foo><input /></>";
+    insta::assert_snapshot!(compile_with_jsx(src, JsxEmit::classic()), @r###"
+    React.createElement(React.Fragment, null, React.createElement(Foo, {
+        bar: baz,
+        disabled: true,
+        ...rest
+    }, "Hello, ", world, "!"), React.createElement("input", null));
+    "###);
+
+    let jsx = JsxEmit::Classic {
+        pragma: String::from("h"),
+        pragma_frag: String::from("Fragment"),
+    };
+    insta::assert_snapshot!(compile_with_jsx(src, jsx), @r###"
+    h(Fragment, null, h(Foo, {
+        bar: baz,
+        disabled: true,
+        ...rest
+    }, "Hello, ", world, "!"), h("input", null));
+    "###);
+}
+
+#[test]
+fn codegen_jsx_automatic_with_import_source() {
+    let jsx = JsxEmit::Automatic {
+        import_source: String::from("preact"),
+    };
+    insta::assert_snapshot!(compile_with_jsx("<Foo>{bar}</Foo>", jsx), @r###"
+    import { jsx as _jsx } from "preact/jsx-runtime";
+    _jsx(Foo, {
+        children: bar
+    });
+    "###);
+}
+
 #[test]
 #[ignore]
 fn js_print_member_access() {
     insta::assert_snapshot!(compile("a.b.c"), @"a.b.c;");
 }
+
+#[test]
+fn codegen_source_map_file() {
+    let src = r#"let add = (a, b) => a + b
+let sum = add(5, 10)"#;
+    let output = compile_with_source_map(
+        src,
+        SourceMapEmit::File {
+            map_file: String::from("input.js.map"),
+        },
+    );
+
+    insta::assert_snapshot!(output.js, @r###"
+    export const add = (a, b)=>a + b;
+    export const sum = add(5, 10);
+    //# sourceMappingURL=input.js.map
+    "###);
+    insta::assert_snapshot!(output.source_map, @r###"{"version":3,"sources":["input.crochet"],"sourcesContent":["let add = (a, b) => a + b\nlet sum = add(5, 10)"],"names":[],"mappings":"AAAA,OAAA,MAAA,AAAI,GAAG,GAAG,CAAC,CAAC,EAAE,CAAC,GAAK,CAAC,GAAG,CAAC,CAAA;AACzB,OAAA,MAAA,AAAI,GAAG,GAAG,GAAG,CAAC,CAAC,EAAE,EAAE,CAAC,CAAA"}"###);
+
+    // `add` in `add(5, 10)`
+    assert_eq!(lookup_source_pos(&output, 1, 19), (1, 10));
+}
+
+#[test]
+fn compile_links_source_map_files_by_file_name() {
+    let options = crochet::CompileOptions::new(
+        "automatic",
+        None,
+        None,
+        None,
+        Some(String::from("file")),
+        Some(String::from("src/foo.crochet")),
+    )
+    .unwrap();
+    let result = crochet::compile("let msg = \"hello\"", Some(options));
+
+    assert!(result.js().ends_with("//# sourceMappingURL=foo.js.map\n"));
+    assert!(result.dts().ends_with("//# sourceMappingURL=foo.d.ts.map\n"));
+}
+
+#[test]
+fn codegen_source_map_inline() {
+    let output = compile_with_source_map("let msg = \"hello\"", SourceMapEmit::Inline);
+
+    insta::assert_snapshot!(output.js, @r###"
+    export const msg = "hello";
+    //# sourceMappingURL=data:application/json;charset=utf-8;base64,eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbImlucHV0LmNyb2NoZXQiXSwic291cmNlc0NvbnRlbnQiOlsibGV0IG1zZyA9IFwiaGVsbG9cIiJdLCJuYW1lcyI6W10sIm1hcHBpbmdzIjoiQUFBQSxPQUFBLE1BQUEsQUFBSSxHQUFHLEdBQUcsT0FBTyxDQUFBIn0=
+    "###);
+}
+
+#[test]
+fn codegen_source_map_multibyte_chars() {
+    let src = r#"let msg = "héllo 👋"
+let len = msg.length"#;
+    let output = compile_with_source_map(
+        src,
+        SourceMapEmit::File {
+            map_file: String::from("input.js.map"),
+        },
+    );
+
+    // `msg` in `msg.length`
+    assert_eq!(lookup_source_pos(&output, 1, 19), (1, 10));
+}
+
+#[test]
+fn codegen_source_map_jsx() {
+    let src = r#"let elem = <Foo bar={baz}>hello</Foo>"#;
+    let output = compile_with_source_map(
+        src,
+        SourceMapEmit::File {
+            map_file: String::from("input.js.map"),
+        },
+    );
+
+    insta::assert_snapshot!(output.js, @r###"
+    import { jsx as _jsx } from "react/jsx-runtime";
+    export const elem = _jsx(Foo, {
+        bar: baz,
+        children: "hello"
+    });
+    //# sourceMappingURL=input.js.map
+    "###);
+
+    // `Foo` in `_jsx(Foo, {`
+    assert_eq!(lookup_source_pos(&output, 1, 25), (0, 12));
+    // `baz` in `bar: baz,`
+    assert_eq!(lookup_source_pos(&output, 2, 9), (0, 21));
+}
@@ -1,4 +1,3 @@
-use chumsky::prelude::*;
 use pretty_assertions::assert_eq;
 
 use std::env;
@@ -9,7 +8,8 @@ use std::path::PathBuf;
 use crochet_codegen::d_ts::*;
 use crochet_codegen::js::*;
 use crochet_infer::*;
-use crochet_parser::parser;
+use crochet_ast::{FileId, SourceFile};
+use crochet_parser::parse;
 
 enum Mode {
     Check,
@@ -28,9 +28,10 @@ fn pass(in_path: PathBuf) {
     let mut d_ts_path = in_path.clone();
     d_ts_path.set_extension("d.ts");
 
-    let input = fs::read_to_string(in_path).unwrap();
+    let input = fs::read_to_string(&in_path).unwrap();
+    let file = SourceFile::new(FileId(0), &in_path.to_string_lossy(), &input);
 
-    let program = parser().parse(input).unwrap();
+    let program = parse(&file).unwrap();
     let js_output = codegen_js(&program);
     match mode {
         Mode::Check => {
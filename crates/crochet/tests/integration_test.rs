@@ -1,14 +1,13 @@
-use chumsky::prelude::*;
-
-use crochet_ast::{Program, Statement};
+use crochet_ast::{FileId, Program, SourceFile, Statement};
 use crochet_codegen::*;
 use crochet_infer::*;
-use crochet_parser::parser;
+use crochet_parser::parse;
 
 fn infer(input: &str) -> String {
     let mut ctx = Context::default();
-    let prog = parser().parse(input).unwrap();
-    let stmt = prog.body.get(0).unwrap();
+    let file = SourceFile::new(FileId(0), "input.crochet", input);
+    let prog = parse(&file).unwrap();
+    let stmt = prog.body.first().unwrap();
     let result = match stmt {
         Statement::Expr { expr, .. } => infer_expr(&mut ctx, expr),
         _ => Err(String::from("We can't infer decls yet")),
@@ -17,7 +16,8 @@ fn infer(input: &str) -> String {
 }
 
 fn infer_prog(src: &str) -> (Program, Context) {
-    let result = parser().parse(src);
+    let file = SourceFile::new(FileId(0), "input.crochet", src);
+    let result = parse(&file);
     let prog = match result {
         Ok(prog) => prog,
         Err(err) => {
@@ -682,7 +682,7 @@ fn infer_react_component() {
     let (_, ctx) = infer_prog(src);
 
     let result = format!("{}", ctx.values.get("Foo").unwrap());
-    assert_eq!(result, "(Props) => JSXElement");
+    assert_eq!(result, "(Props) => JSX.Element");
 }
 
 #[test]
@@ -726,7 +726,7 @@ fn infer_mem_access_with_optional_prop() {
 }
 
 #[test]
-#[should_panic = "not yet implemented"]
+#[should_panic = "Property 'b' does not exist on type '{b?: number} | undefined'"]
 fn recursive_mem_access_on_optional_prop_should_fail() {
     let src = r#"
     declare let foo: {a?: {b?: number}}
@@ -888,8 +888,7 @@ fn infer_fn_param_with_type_alias_with_param_2() {
     let (_, ctx) = infer_prog(src);
 
     let result = format!("{}", ctx.values.get("get_bar").unwrap());
-    // TODO: normalize the scheme before inserting it into the context
-    insta::assert_snapshot!(result, @"<t7>(Foo<t7>) => t7");
+    insta::assert_snapshot!(result, @"<t0>(Foo<t0>) => t0");
 }
 
 #[test]
@@ -921,6 +920,36 @@ fn infer_fn_param_with_type_alias_with_param_4() {
     assert_eq!(result, "\"hello\"");
 }
 
+#[test]
+fn codegen_d_ts_type_alias_with_params() {
+    let src = r#"
+    type Foo<T> = {bar: T}
+    type Pair<K extends string, V = number> = {key: K, value: V}
+    namespace Util {
+        type Box<T> = {value: T}
+    }
+    declare let foo: Foo<string>
+    "#;
+    let (program, ctx) = infer_prog(src);
+    let result = codegen_d_ts(&program, &ctx);
+
+    insta::assert_snapshot!(result, @r###"
+    type Foo<T> = {
+        bar: T;
+    };
+    type Pair<K extends string, V = number> = {
+        key: K;
+        value: V;
+    };
+    export declare module Util {
+        export type Box<T> = {
+            value: T;
+        };
+    }
+    export declare const foo: Foo<string>;
+    "###);
+}
+
 #[test]
 fn infer_destructure_all_object_properties() {
     let src = r#"
@@ -1148,7 +1177,7 @@ fn infer_jsx() {
     let (_, ctx) = infer_prog(src);
 
     let elem = format!("{}", ctx.values.get("elem").unwrap());
-    assert_eq!(elem, "JSXElement");
+    assert_eq!(elem, "JSX.Element");
 }
 
 #[test]
@@ -1213,8 +1242,8 @@ fn infer_if_let() {
 
     assert_eq!(format!("{}", ctx.values.get("p").unwrap()), "{x: 5, y: 10}");
     // Ensures we aren't polluting the outside context
-    assert!(ctx.values.get("x").is_none());
-    assert!(ctx.values.get("y").is_none());
+    assert!(!ctx.values.contains_key("x"));
+    assert!(!ctx.values.contains_key("y"));
 }
 
 #[test]
@@ -1233,7 +1262,7 @@ fn infer_if_let_with_is() {
         "string | number"
     );
     // Ensures we aren't polluting the outside context
-    assert!(ctx.values.get("a").is_none());
+    assert!(!ctx.values.contains_key("a"));
 }
 
 #[test]
@@ -1307,6 +1336,7 @@ fn codegen_if_let_with_rename() {
 }
 
 #[test]
+#[should_panic = "Unification failure"]
 fn infer_if_let_with_type_error() {
     let src = r#"
     let p = {x: "hello", y: "world"}
@@ -1581,7 +1611,8 @@ fn codegen_if_let_with_is_class() {
     }
     "#;
 
-    let result = parser().parse(src);
+    let file = SourceFile::new(FileId(0), "input.crochet", src);
+    let result = parse(&file);
     let program = match result {
         Ok(prog) => prog,
         Err(err) => {
@@ -1635,3 +1666,81 @@ fn codegen_array() {
     insta::assert_snapshot!(result, @"export declare const arr: string[];
 ");
 }
+
+// Returns the (line, col) in the source of the token at `line` and `col` in
+// the generated code.
+fn lookup_source_pos(source_map: &str, line: u32, col: u32) -> (u32, u32) {
+    let source_map = sourcemap::SourceMap::from_slice(source_map.as_bytes()).unwrap();
+    let token = source_map.lookup_token(line, col).unwrap();
+    (token.get_src_line(), token.get_src_col())
+}
+
+#[test]
+fn codegen_d_ts_with_declaration_map() {
+    let src = r#"let add = (a: number, b: number) => a + b
+type Point = {x: number, y: number}
+interface Shape {
+    area: () => number
+}
+class Foo {
+    bar: number
+}"#;
+
+    let file = SourceFile::new(FileId(0), "input.crochet", src);
+    let (program, ctx) = infer_prog(src);
+    let emit = SourceMapEmit::File {
+        map_file: String::from("input.d.ts.map"),
+    };
+    let result = codegen_d_ts_with_source_map(&program, &ctx, &file, &emit);
+
+    insta::assert_snapshot!(result.d_ts, @r###"
+    export declare const add: (a: number, b: number) => number;
+    type Point = {
+        x: number;
+        y: number;
+    };
+    export interface Shape {
+        area: () => number;
+    }
+    export declare class Foo {
+        bar: number;
+    }
+    //# sourceMappingURL=input.d.ts.map
+    "###);
+
+    // `add` in `export declare const add`
+    assert_eq!(lookup_source_pos(&result.source_map, 0, 21), (0, 4));
+    // `Point` in `type Point`
+    assert_eq!(lookup_source_pos(&result.source_map, 1, 5), (1, 5));
+    // `Shape` in `export interface Shape`
+    assert_eq!(lookup_source_pos(&result.source_map, 5, 17), (2, 10));
+    // `area` in `area: () => number;`
+    assert_eq!(lookup_source_pos(&result.source_map, 6, 4), (3, 4));
+    // `bar` in `bar: number;`
+    assert_eq!(lookup_source_pos(&result.source_map, 9, 4), (6, 4));
+}
+
+#[test]
+fn codegen_d_ts_with_inline_declaration_map() {
+    let src = "let msg = \"hello\"";
+
+    let file = SourceFile::new(FileId(0), "input.crochet", src);
+    let (program, ctx) = infer_prog(src);
+    let result = codegen_d_ts_with_source_map(&program, &ctx, &file, &SourceMapEmit::Inline);
+
+    insta::assert_snapshot!(result.d_ts, @r###"
+    export declare const msg: "hello";
+    //# sourceMappingURL=data:application/json;charset=utf-8;base64,eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbImlucHV0LmNyb2NoZXQiXSwic291cmNlc0NvbnRlbnQiOlsibGV0IG1zZyA9IFwiaGVsbG9cIiJdLCJuYW1lcyI6W10sIm1hcHBpbmdzIjoiQUFBQSxPQUFBLGNBQUEsQUFBSSxHQUFHLFNBQVUsQ0FBQSJ9
+    "###);
+}
+
+#[test]
+#[should_panic = "Unification failure"]
+fn infer_promise_type_args_are_checked_in_unions() {
+    let src = r#"
+    declare let p: Promise<number>
+    let q: Promise<string> | undefined = p
+    "#;
+
+    infer_prog(src);
+}
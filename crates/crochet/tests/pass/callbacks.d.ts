@@ -0,0 +1,15 @@
+export declare const map: <A, B>(arg0: A[], arg1: (arg0: A) => B) => B[];
+type Person = {
+    name: string;
+    age: number;
+};
+export declare const people: Person[];
+export declare const names: string[];
+export declare const get_age: (person: Person) => number;
+type Identity = <T>(x: T) => T;
+type Mapper<T> = <U>(x: T, f: (arg0: T) => U) => U;
+export declare const identity: Identity;
+export declare const apply: Mapper<Person>;
+export declare const person: Person;
+export declare const age: number;
+export declare const same: 5;
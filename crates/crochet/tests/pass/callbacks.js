@@ -0,0 +1,12 @@
+;
+;
+;
+export const names = map(people, (person)=>person.name);
+export const get_age = (person)=>person.age;
+;
+;
+;
+;
+;
+export const age = apply(person, get_age);
+export const same = identity(5);
@@ -0,0 +1,19 @@
+export declare class Point {
+    x: number;
+    y: number;
+    constructor(x: number, y: number);
+    add(other: Point): Point;
+    sum(): number;
+}
+export declare class Counter {
+    count: number;
+    constructor();
+}
+export declare const p: Point;
+export declare const q: Point;
+export declare const total: number;
+export declare const counter: Counter;
+export declare const get_x: (pt: {
+    x: number;
+}) => number;
+export declare const x: number;
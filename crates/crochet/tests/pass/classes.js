@@ -0,0 +1,23 @@
+export class Point {
+    constructor(x, y){
+        this.x = x;
+        this.y = y;
+    }
+    add(other) {
+        return new Point(this.x + other.x, this.y + other.y);
+    }
+    sum() {
+        return this.x + this.y;
+    }
+}
+export class Counter {
+    constructor(){
+        this.count = 0;
+    }
+}
+export const p = new Point(5, 10);
+export const q = p.add(new Point(1, 2));
+export const total = q.sum();
+export const counter = new Counter();
+export const get_x = (pt)=>pt.x;
+export const x = get_x(p);
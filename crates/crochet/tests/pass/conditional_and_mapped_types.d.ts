@@ -0,0 +1,17 @@
+type Point = {
+    x: number;
+    y: number;
+};
+type Partial<T> = {
+    [K in keyof T]?: T[K];
+};
+type Mutable<T> = {
+    -readonly [K in keyof T]: T[K];
+};
+type Exclude<T, U> = T extends U ? never : T;
+type ReturnType<F> = F extends (arg0: any) => infer R ? R : never;
+export declare const update: Partial<Point>;
+export declare const dir: Exclude<"up" | "down" | "left", "left">;
+export declare const getPoint: () => Point;
+export declare const result: ReturnType<() => Point>;
+export declare const unwrap: <A>(arg0: A) => A extends (infer E)[] ? E : A;
@@ -0,0 +1,12 @@
+;
+;
+;
+;
+;
+export const update = {
+    y: 5
+};
+;
+;
+export const result = getPoint();
+;
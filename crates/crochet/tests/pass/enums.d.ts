@@ -0,0 +1,36 @@
+type Shape = {
+    kind: "Circle";
+    r: number;
+} | {
+    kind: "Square";
+    s: number;
+};
+export declare const Shape: {
+    Circle: (r: number) => Shape;
+    Square: (s: number) => Shape;
+};
+type Option<T> = {
+    kind: "Some";
+    value: T;
+} | {
+    kind: "None";
+};
+export declare const Option: {
+    Some: <T>(value: T) => Option<T>;
+    None: {
+        kind: "None";
+    };
+};
+type Size = {
+    kind: "Box";
+    width: number;
+    height?: number;
+};
+export declare const Size: {
+    Box: (width: number, height?: number) => Size;
+};
+export declare const circle: Shape;
+export declare const square: Shape;
+export declare const some: Option<"hello">;
+export declare const area: (shape: Shape) => number;
+export declare const unwrap_or: <A>(opt: Option<A>, fallback: A) => A;
@@ -0,0 +1,55 @@
+export const Shape = {
+    Circle: (r)=>({
+            kind: "Circle",
+            r
+        }),
+    Square: (s)=>({
+            kind: "Square",
+            s
+        })
+};
+export const Option = {
+    Some: (value)=>({
+            kind: "Some",
+            value
+        }),
+    None: {
+        kind: "None"
+    }
+};
+export const Size = {
+    Box: (width, height)=>({
+            kind: "Box",
+            width,
+            height
+        })
+};
+export const circle = Shape.Circle(5);
+export const square = Shape.Square(4);
+export const some = Option.Some("hello");
+export const area = (shape)=>(()=>{
+        const value = shape;
+        if (value.kind === "Circle") {
+            const { r  } = value;
+            return 3 * r * r;
+        } else {
+            return (()=>{
+                const value = shape;
+                if (value.kind === "Square") {
+                    const { s  } = value;
+                    return s * s;
+                } else {
+                    return 0;
+                }
+            })();
+        }
+    })();
+export const unwrap_or = (opt, fallback)=>(()=>{
+        const value = opt;
+        if (value.kind === "Some") {
+            const { value: x  } = value;
+            return x;
+        } else {
+            return fallback;
+        }
+    })();
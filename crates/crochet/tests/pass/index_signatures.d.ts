@@ -0,0 +1,12 @@
+type Dict = {
+    [key: string]: number;
+};
+export interface Env {
+    [name: string]: string;
+    HOME: string;
+}
+export declare const env: Env;
+export declare const home: string;
+export declare const path: string | undefined;
+export declare const scores: Dict;
+export declare const lookup: (dict: Dict, key: string) => number | undefined;
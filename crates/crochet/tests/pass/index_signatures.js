@@ -0,0 +1,10 @@
+;
+;
+;
+export const home = env.HOME;
+export const path = env.PATH;
+export const scores = {
+    alice: 10,
+    bob: 8
+};
+export const lookup = (dict, key)=>dict[key];
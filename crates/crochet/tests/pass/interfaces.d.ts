@@ -0,0 +1,24 @@
+export interface Point {
+    x: number;
+    y: number;
+}
+export interface Point3D extends Point {
+    z: number;
+}
+export interface Box<T> {
+    value: T;
+    map<U>(fn: (arg0: T) => U): Box<U>;
+}
+export interface Formatter {
+    (value: number) : string;
+    format(value: number): string;
+}
+export interface Formatter {
+    format(value: boolean): string;
+    name?: string;
+}
+export declare const p: Point3D;
+export declare const sum: number;
+export declare const fmt: Formatter;
+export declare const a: string;
+export declare const b: string;
@@ -0,0 +1,14 @@
+;
+;
+;
+;
+;
+export const p = {
+    x: 1,
+    y: 2,
+    z: 3
+};
+export const sum = p.x + p.y + p.z;
+;
+export const a = fmt(5);
+export const b = fmt.format(true);
@@ -0,0 +1,23 @@
+export declare module JSX {
+    export type IntrinsicElements = {
+        div: {
+            id?: string;
+            children?: JSX.Element;
+        };
+        button: {
+            disabled?: boolean;
+            children?: string;
+        };
+    };
+    export interface ElementChildrenAttribute {
+        children: {
+        };
+    }
+}
+type Props = {
+    label: string;
+    disabled?: boolean;
+};
+export declare const Button: (props: Props) => JSX.Element;
+export declare const render: (elem: JSX.Element) => JSX.Element;
+export declare const app: JSX.Element;
@@ -0,0 +1,17 @@
+import { jsx as _jsx, Fragment as _Fragment } from "react/jsx-runtime";
+;
+;
+export const Button = (props)=>_jsx("button", {
+        disabled: props.disabled,
+        children: props.label
+    });
+export const render = (elem)=>_jsx("div", {
+        id: "root",
+        children: elem
+    });
+export const app = render(_jsx(_Fragment, {
+    children: _jsx(Button, {
+        label: "Click me",
+        disabled: true
+    })
+}));
@@ -0,0 +1,8 @@
+export declare const value: string | number;
+export declare const str_or_len: string | number;
+export declare const maybe: number | null;
+export declare const num: number;
+export declare const obj: {
+    x: number;
+} | undefined;
+export declare const x: number;
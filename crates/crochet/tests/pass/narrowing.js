@@ -0,0 +1,24 @@
+;
+export const str_or_len = (()=>{
+    if (typeof value === "number") {
+        return value + 1;
+    } else {
+        return value;
+    }
+})();
+;
+export const num = (()=>{
+    if (maybe !== null) {
+        return maybe * 2;
+    } else {
+        return 0;
+    }
+})();
+;
+export const x = (()=>{
+    if (!obj) {
+        return 0;
+    } else {
+        return obj.x;
+    }
+})();
@@ -0,0 +1,11 @@
+type Config = {
+    readonly name: string;
+    readonly tags: readonly string[];
+};
+export interface State {
+    readonly count: number;
+    history: readonly [number, number];
+}
+export declare const config: Config;
+export declare const tags: readonly string[];
+export declare const pair: readonly [number, string];
@@ -0,0 +1,8 @@
+;
+;
+;
+export const tags = config.tags;
+export const pair = [
+    1,
+    "one"
+];
@@ -0,0 +1,9 @@
+export declare const sql: (arg0: TemplateStringsArray, ...arg1: (string | number)[]) => string;
+export declare const id: 5;
+export declare const query: string;
+export declare const styled: {
+    div: (arg0: TemplateStringsArray, ...arg1: string[]) => number;
+};
+export declare const button: number;
+export declare const raw: (strings: TemplateStringsArray) => readonly string[];
+export declare const hello: readonly string[];
@@ -0,0 +1,7 @@
+;
+export const id = 5;
+export const query = sql`SELECT * FROM users WHERE id = ${id}`;
+;
+export const button = styled.div`color: ${"red"};`;
+export const raw = (strings)=>strings.raw;
+export const hello = raw`hello, world`;
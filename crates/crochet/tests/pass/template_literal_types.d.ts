@@ -0,0 +1,10 @@
+type EventName = `on${string}`;
+type Route = `/users/${number}`;
+type Side = "top" | "bottom";
+type Margin = "margin-top" | "margin-bottom";
+export declare const click: EventName;
+export declare const id: 5;
+export declare const route: Route;
+export declare const margin: Margin;
+type EventType<T> = T extends `on${infer E}` ? E : never;
+export declare const event: EventType<"onclick">;
@@ -0,0 +1,10 @@
+;
+;
+;
+;
+export const click = "onclick";
+export const id = 5;
+export const route = `/users/${id}`;
+;
+;
+export const event = "click";
@@ -0,0 +1,8 @@
+export declare const fail: (arg0: string) => never;
+export declare const input: unknown;
+export declare const anything: any;
+export declare const len: string;
+export declare const num: number;
+export declare const wrapped: {
+    value: unknown;
+};
@@ -0,0 +1,14 @@
+;
+;
+;
+export const len = (()=>{
+    if (typeof input === "string") {
+        return input;
+    } else {
+        return fail("expected a string");
+    }
+})();
+export const num = anything;
+export const wrapped = {
+    value: input
+};
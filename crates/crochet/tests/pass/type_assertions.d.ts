@@ -0,0 +1,12 @@
+export declare const parse: (arg0: string) => unknown;
+export declare const input: string;
+type Point = {
+    x: number;
+    y: number;
+};
+export declare const point: Point;
+export declare const origin: {
+    x: 0;
+    y: 0;
+};
+export declare const sum: number;
@@ -0,0 +1,9 @@
+;
+;
+;
+export const point = parse(input);
+export const origin = {
+    x: 0,
+    y: 0
+};
+export const sum = (point.x + origin.y) * 2;
@@ -0,0 +1,16 @@
+type Point = {
+    x: number;
+    y: string;
+};
+type PointKey = keyof Point;
+type X = Point["x"];
+export declare const origin: {
+    x: 0;
+    y: "zero";
+};
+export declare const key: keyof {
+    x: 0;
+    y: "zero";
+};
+export declare const value: 0 | "zero";
+export declare const getX: (p: Point) => number;
@@ -0,0 +1,10 @@
+;
+;
+;
+export const origin = {
+    x: 0,
+    y: "zero"
+};
+;
+export const value = origin[key];
+export const getX = (p)=>p.x;
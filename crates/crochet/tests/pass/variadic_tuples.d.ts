@@ -0,0 +1,11 @@
+type Args = [string, ...number[]];
+export declare const args: Args;
+export declare const sum: (...arg0: number[]) => number;
+export declare const total: number;
+export declare const last: <A extends unknown[], B>(arg0: [...A, B]) => B;
+export declare const a: true;
+export declare const concat: <A extends unknown[], B extends unknown[]>(arg0: A, arg1: B) => [...A, ...B];
+export declare const c: [1, 2, "three"];
+type Tail<T> = T extends [any, ...infer R] ? R : never;
+export declare const rest: Tail<[number, string, boolean]>;
+export declare const tail: [string, boolean];
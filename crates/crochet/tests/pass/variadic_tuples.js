@@ -0,0 +1,24 @@
+;
+export const args = [
+    "values",
+    1,
+    2
+];
+;
+export const total = sum(1, 2, 3);
+;
+export const a = last([
+    1,
+    "two",
+    true
+]);
+;
+export const c = concat([
+    1,
+    2
+], [
+    "three"
+]);
+;
+;
+export const tail = rest;
@@ -0,0 +1,34 @@
+use crate::expr::{Expr, Lambda};
+use crate::ident::Ident;
+use crate::pattern::Pattern;
+use crate::span::Span;
+use crate::types::TypeAnn;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassMember {
+    Constructor(Constructor),
+    Method(ClassMethod),
+    Prop(ClassProp),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constructor {
+    pub span: Span,
+    pub params: Vec<Pattern>,
+    pub body: Box<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassMethod {
+    pub span: Span,
+    pub key: Ident,
+    pub lambda: Lambda,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassProp {
+    pub span: Span,
+    pub key: Ident,
+    pub optional: bool,
+    pub type_ann: TypeAnn,
+}
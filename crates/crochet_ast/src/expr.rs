@@ -1,11 +1,12 @@
 
 
+use crate::class::ClassMember;
 use crate::ident::Ident;
-use crate::jsx::JSXElement;
+use crate::jsx::{JSXElement, JSXFragment};
 use crate::literal::Lit;
 use crate::pattern::Pattern;
 use crate::span::Span;
-use crate::types::{TypeAnn, TypeParam};
+use crate::types::{TProp, TypeAnn, TypeElem, TypeParam, TypeRef};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Program {
@@ -27,12 +28,48 @@ pub enum Statement {
         type_ann: TypeAnn,    
         type_params: Option<Vec<TypeParam>>,    
     },
+    EnumDecl {
+        span: Span,
+        id: Ident,
+        type_params: Option<Vec<TypeParam>>,
+        variants: Vec<EnumVariant>,
+    },
+    ClassDecl {
+        span: Span,
+        id: Ident,
+        members: Vec<ClassMember>,
+    },
+    InterfaceDecl {
+        span: Span,
+        id: Ident,
+        type_params: Option<Vec<TypeParam>>,
+        extends: Vec<TypeRef>,
+        members: Vec<TypeElem>,
+    },
+    // Namespaces can only contain type and interface declarations.  Their
+    // members are referenced using qualified names, e.g. `JSX.Element`.
+    NamespaceDecl {
+        span: Span,
+        id: Ident,
+        body: Vec<Statement>,
+    },
     Expr {
         span: Span,
         expr: Expr,
     }, // NOTE: does not include Expr::Let
 }
 
+// Each variant desugars to an object type with a `kind` property whose
+// value is the name of the variant, e.g. `Circle(r: number)` becomes
+// `{kind: "Circle", r: number}`.  Variants without any fields are unit
+// variants, e.g. `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumVariant {
+    pub span: Span,
+    pub name: Ident,
+    pub fields: Option<Vec<TProp>>,
+}
+
 // #[derive(Debug, Clone, PartialEq, Eq)]
 // struct Block {
 //     pub span: Span,
@@ -46,6 +83,22 @@ pub struct App {
     pub args: Vec<ExprOrSpread>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct New {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub args: Vec<ExprOrSpread>,
+}
+
+// The left side of an assignment must be a property of `this`, e.g.
+// `this.x = x`.  This is only used to initialize fields in constructors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assign {
+    pub span: Span,
+    pub left: Box<Expr>,
+    pub right: Box<Expr>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Fix {
     pub span: Span,
@@ -85,6 +138,31 @@ pub struct Let {
     pub body: Box<Expr>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unary {
+    pub span: Span,
+    pub op: UnaryOp,
+    pub arg: Box<Expr>,
+}
+
+// `expr as T` tells the checker that `expr` has type `T`.  This is only
+// allowed if the inferred type of `expr` and `T` overlap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct As {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub type_ann: TypeAnn,
+}
+
+// `expr satisfies T` checks that `expr` is assignable to `T` without changing
+// the type that was inferred for `expr`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Satisfies {
+    pub span: Span,
+    pub expr: Box<Expr>,
+    pub type_ann: TypeAnn,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Op {
     pub span: Span,
@@ -192,26 +270,32 @@ pub struct TemplateLiteral {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TaggedTemplateLiteral {
     pub span: Span,
-    pub tag: Ident,
+    pub tag: Box<Expr>,
     pub template: TemplateLiteral,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Expr {
     App(App),
+    Assign(Assign),
     Fix(Fix),
     Ident(Ident),
     IfElse(IfElse),
     JSXElement(JSXElement),
+    JSXFragment(JSXFragment),
     Lambda(Lambda),
     Let(Let),
     LetExpr(LetExpr), // should only be used in `if let` expressions
     Lit(Lit),
     Op(Op),
+    Unary(Unary),
+    As(As),
+    Satisfies(Satisfies),
     Obj(Obj),
     Await(Await),
     Tuple(Tuple),
     Member(Member),
+    New(New),
     Empty(Empty),
     TemplateLiteral(TemplateLiteral),
     TaggedTemplateLiteral(TaggedTemplateLiteral),
@@ -221,18 +305,24 @@ impl Expr {
     pub fn span(&self) -> Span {
         match &self {
             Expr::App(app) => app.span.to_owned(),
+            Expr::Assign(assign) => assign.span.to_owned(),
             Expr::Fix(fix) => fix.span.to_owned(),
             Expr::Ident(ident) => ident.span.to_owned(),
             Expr::IfElse(if_else) => if_else.span.to_owned(),
             Expr::JSXElement(elem) => elem.span.to_owned(),
+            Expr::JSXFragment(frag) => frag.span.to_owned(),
             Expr::Lambda(lam) => lam.span.to_owned(),
             Expr::Let(r#let) => r#let.span.to_owned(),
             Expr::Lit(lit) => lit.span(),
             Expr::Op(op) => op.span.to_owned(),
+            Expr::Unary(unary) => unary.span.to_owned(),
+            Expr::As(r#as) => r#as.span.to_owned(),
+            Expr::Satisfies(satisfies) => satisfies.span.to_owned(),
             Expr::Obj(obj) => obj.span.to_owned(),
             Expr::Await(r#await) => r#await.span.to_owned(),
             Expr::Tuple(tuple) => tuple.span.to_owned(),
             Expr::Member(member) => member.span.to_owned(),
+            Expr::New(new) => new.span.to_owned(),
             Expr::Empty(empty) => empty.span.to_owned(),
             Expr::LetExpr(let_expr) => let_expr.span.to_owned(),
             Expr::TemplateLiteral(tl) => tl.span.to_owned(),
@@ -254,3 +344,9 @@ pub enum BinOp {
     Lt,
     LtEq,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnaryOp {
+    Not,
+    Typeof,
+}
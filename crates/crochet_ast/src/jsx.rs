@@ -15,12 +15,60 @@ pub struct JSXExprContainer {
     pub expr: Expr,
 }
 
+// `{...children}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JSXSpreadChild {
+    pub span: Span,
+    pub expr: Expr,
+}
+
+// `Ctx.Provider` in `<Ctx.Provider>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JSXMemberExpr {
+    pub span: Span,
+    pub obj: Box<JSXElementName>,
+    pub prop: Ident,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JSXElementName {
+    Ident(Ident),
+    Member(JSXMemberExpr),
+}
+
+impl JSXElementName {
+    pub fn span(&self) -> Span {
+        match self {
+            JSXElementName::Ident(ident) => ident.span.to_owned(),
+            JSXElementName::Member(member) => member.span.to_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for JSXElementName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JSXElementName::Ident(Ident { name, .. }) => write!(f, "{name}"),
+            JSXElementName::Member(JSXMemberExpr { obj, prop, .. }) => {
+                write!(f, "{obj}.{}", prop.name)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JSXElement {
     pub span: Span,
     // Other ASTs make have JSXOpeningElement and JSXClosingElement
-    pub name: String,
-    pub attrs: Vec<JSXAttr>,
+    pub name: JSXElementName,
+    pub attrs: Vec<JSXAttrOrSpread>,
+    pub children: Vec<JSXElementChild>,
+}
+
+// `<>...</>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JSXFragment {
+    pub span: Span,
     pub children: Vec<JSXElementChild>,
 }
 
@@ -29,19 +77,34 @@ pub enum JSXElementChild {
     JSXText(JSXText),
     JSXExprContainer(JSXExprContainer),
     JSXElement(Box<JSXElement>),
-    // JSXSpreadChild(JSXSpreadChild),
-    // JSXFragment(JSXFragment),
+    JSXSpreadChild(JSXSpreadChild),
+    JSXFragment(JSXFragment),
 }
 
+// `value` is `None` for boolean attributes, e.g. `<input disabled />`, which
+// are the same as `disabled={true}`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JSXAttr {
     pub span: Span,
     pub ident: Ident,
-    pub value: JSXAttrValue,
+    pub value: Option<JSXAttrValue>,
+}
+
+// `{...props}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JSXSpreadAttr {
+    pub span: Span,
+    pub expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JSXAttrOrSpread {
+    JSXAttr(JSXAttr),
+    JSXSpreadAttr(JSXSpreadAttr),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum JSXAttrValue {
     Lit(Lit),
     JSXExprContainer(JSXExprContainer),
-}
\ No newline at end of file
+}
@@ -1,17 +1,21 @@
+pub mod class;
 pub mod expr;
 pub mod ident;
 pub mod jsx;
 pub mod literal;
 pub mod pattern;
+pub mod source_file;
 pub mod span;
 pub mod types;
 pub mod prim;
 
+pub use class::*;
 pub use expr::*;
 pub use ident::*;
 pub use jsx::*;
 pub use literal::*;
 pub use pattern::*;
+pub use source_file::*;
 pub use span::*;
 pub use types::*;
 pub use prim::*;
@@ -8,6 +8,13 @@ pub enum Primitive {
     Str,
     Undefined,
     Null,
+    // `never` is the bottom type, it has no values and is a subtype of all types
+    Never,
+    // `unknown` is the top type, all types are subtypes of it
+    Unknown,
+    // `any` opts out of type checking, it's both a subtype and a supertype of
+    // all types
+    Any,
 }
 
 impl fmt::Display for Primitive {
@@ -18,6 +25,9 @@ impl fmt::Display for Primitive {
             Primitive::Str => write!(f, "string"),
             Primitive::Null => write!(f, "null"),
             Primitive::Undefined => write!(f, "undefined"),
+            Primitive::Never => write!(f, "never"),
+            Primitive::Unknown => write!(f, "unknown"),
+            Primitive::Any => write!(f, "any"),
         }
     }
 }
@@ -0,0 +1,183 @@
+use std::iter;
+
+// Identifies the file that a `Span` belongs to.  It's up to the caller to
+// give each `SourceFile` a unique id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct FileId(pub u32);
+
+// A zero-based line and column.  Whether `col` counts bytes or UTF-16 code
+// units depends on the method that returned it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+// Converts between byte offsets and line/column positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    // The byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(src: &str) -> Self {
+        let line_starts = iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex { line_starts }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        self.line_starts.get(line).copied()
+    }
+
+    // Returns the line containing `offset` and the number of bytes between
+    // the start of that line and `offset`.
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        LineCol {
+            line,
+            col: offset - self.line_starts[line],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    id: FileId,
+    name: String,
+    src: String,
+    line_index: LineIndex,
+}
+
+impl SourceFile {
+    pub fn new(id: FileId, name: &str, src: &str) -> Self {
+        SourceFile {
+            id,
+            name: name.to_owned(),
+            src: src.to_owned(),
+            line_index: LineIndex::new(src),
+        }
+    }
+
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+
+    // `col` is in bytes, see `line_col_utf16` for editors.
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        self.line_index.line_col(offset)
+    }
+
+    // Editors, e.g. via the Language Server Protocol, and JavaScript count
+    // columns in UTF-16 code units.
+    pub fn line_col_utf16(&self, offset: usize) -> LineCol {
+        let LineCol { line, col } = self.line_col(offset);
+        let line_start = offset - col;
+        LineCol {
+            line,
+            col: self.src[line_start..offset].encode_utf16().count(),
+        }
+    }
+
+    // The inverse of `line_col_utf16`.  Returns `None` if the position is
+    // past the end of its line or splits a char.
+    pub fn offset_utf16(&self, line_col: LineCol) -> Option<usize> {
+        let line_start = self.line_index.line_start(line_col.line)?;
+        // Excludes the newline so that positions after it aren't accepted.
+        let line_end = match self.line_index.line_start(line_col.line + 1) {
+            Some(next_line_start) => next_line_start - 1,
+            None => self.src.len(),
+        };
+
+        let mut col = 0;
+        for (i, c) in self.src[line_start..line_end].char_indices() {
+            if col == line_col.col {
+                return Some(line_start + i);
+            }
+            if col > line_col.col {
+                return None;
+            }
+            col += c.len_utf16();
+        }
+
+        match col == line_col.col {
+            true => Some(line_end),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source_file(src: &str) -> SourceFile {
+        SourceFile::new(FileId(0), "input.crochet", src)
+    }
+
+    #[test]
+    fn line_col() {
+        let file = source_file("let a = 5\nlet b = a\n");
+
+        assert_eq!(file.line_col(0), LineCol { line: 0, col: 0 });
+        assert_eq!(file.line_col(4), LineCol { line: 0, col: 4 });
+        assert_eq!(file.line_col(9), LineCol { line: 0, col: 9 });
+        assert_eq!(file.line_col(10), LineCol { line: 1, col: 0 });
+        assert_eq!(file.line_col(18), LineCol { line: 1, col: 8 });
+        assert_eq!(file.line_col(20), LineCol { line: 2, col: 0 });
+        assert_eq!(file.line_index().line_count(), 3);
+    }
+
+    #[test]
+    fn line_col_utf16() {
+        // "é" is 2 bytes and 1 UTF-16 code unit, "👋" is 4 bytes and 2 UTF-16
+        // code units.
+        let file = source_file("let a = \"é👋\"\nlet b = a");
+
+        let offset = file.src().find("\"\n").unwrap();
+        assert_eq!(file.line_col(offset), LineCol { line: 0, col: 15 });
+        assert_eq!(file.line_col_utf16(offset), LineCol { line: 0, col: 12 });
+
+        let offset = file.src().rfind('a').unwrap();
+        assert_eq!(file.line_col_utf16(offset), LineCol { line: 1, col: 8 });
+    }
+
+    #[test]
+    fn offset_utf16() {
+        let file = source_file("let a = \"é👋\"\nlet b = a");
+
+        let offset = file.src().find("\"\n").unwrap();
+        assert_eq!(
+            file.offset_utf16(LineCol { line: 0, col: 12 }),
+            Some(offset)
+        );
+        assert_eq!(
+            file.offset_utf16(LineCol { line: 1, col: 9 }),
+            Some(file.src().len())
+        );
+
+        // The middle of "👋"
+        assert_eq!(file.offset_utf16(LineCol { line: 0, col: 11 }), None);
+        // Past the end of the line
+        assert_eq!(file.offset_utf16(LineCol { line: 0, col: 14 }), None);
+        assert_eq!(file.offset_utf16(LineCol { line: 2, col: 0 }), None);
+    }
+}
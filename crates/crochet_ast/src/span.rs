@@ -1 +1,61 @@
-pub type Span = std::ops::Range<usize>;
+use std::fmt;
+use std::ops::Range;
+
+use crate::source_file::FileId;
+
+// A range of byte offsets in the file identified by `file`.  Offsets are
+// stored as `u32`s to keep the AST small, which limits files to 4GiB.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub file: FileId,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Span {
+    // Returns a span from the start of `self` to the end of `other`, e.g. the
+    // span of a binary expression from the spans of its operands.
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            file: self.file,
+            start: self.start,
+            end: other.end,
+        }
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start as usize..self.end as usize
+    }
+}
+
+impl chumsky::Span for Span {
+    type Context = FileId;
+    type Offset = usize;
+
+    fn new(file: FileId, range: Range<usize>) -> Self {
+        Span {
+            file,
+            start: range.start as u32,
+            end: range.end as u32,
+        }
+    }
+
+    fn context(&self) -> FileId {
+        self.file
+    }
+
+    fn start(&self) -> usize {
+        self.start as usize
+    }
+
+    fn end(&self) -> usize {
+        self.end as usize
+    }
+}
+
+// Spans are printed as ranges to keep debug output, e.g. snapshots, compact.
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.range())
+    }
+}
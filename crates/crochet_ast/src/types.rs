@@ -1,4 +1,5 @@
 use crate::span::Span;
+use crate::expr::TemplateElem;
 use crate::literal::Lit;
 use crate::ident::Ident;
 use crate::prim::Primitive;
@@ -6,11 +7,21 @@ use crate::prim::Primitive;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LamType {
     pub span: Span,
-    pub params: Vec<TypeAnn>,
+    pub params: Vec<TFnParam>,
     pub ret: Box<TypeAnn>,
     pub type_params: Option<Vec<TypeParam>>,
 }
 
+// A param in a lambda type or signature, e.g. `number` or `x: number`.  Names
+// don't affect the type but are kept so that declarations can be emitted the
+// way they were written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TFnParam {
+    pub span: Span,
+    pub name: Option<Ident>,
+    pub type_ann: Box<TypeAnn>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PrimType {
     pub span: Span,
@@ -34,6 +45,7 @@ pub struct TypeRef {
 pub struct ObjectType {
     pub span: Span,
     pub props: Vec<TProp>,
+    pub index: Option<Box<TIndex>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -41,9 +53,42 @@ pub struct TProp {
     pub span: Span,
     pub name: String,
     pub optional: bool,
+    pub readonly: bool,
+    pub type_ann: Box<TypeAnn>,
+}
+
+// An index signature, e.g. `[key: string]: number`.  The types of all of the
+// named props in the same object type must be assignable to `type_ann`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TIndex {
+    pub span: Span,
+    pub key: Ident,
+    pub key_type: Box<TypeAnn>,
+    pub readonly: bool,
     pub type_ann: Box<TypeAnn>,
 }
 
+// A method signature, e.g. `concat(string): string`.  Unlike props, multiple
+// methods with the same name can appear in an interface, each one being an
+// overload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TMethod {
+    pub span: Span,
+    pub name: String,
+    pub optional: bool,
+    pub lam: LamType,
+}
+
+// The members that can appear in the body of an interface.  Call signatures,
+// e.g. `(number): string`, allow values of the interface to be called.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeElem {
+    Prop(TProp),
+    Method(TMethod),
+    Call(LamType),
+    Index(TIndex),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UnionType {
     pub span: Span,
@@ -68,6 +113,93 @@ pub struct ArrayType {
     pub elem_type: Box<TypeAnn>,
 }
 
+// `...T`, only valid as an element of a tuple type or as the last param of a
+// lambda type.  `T` must be an array or tuple type, or a type param.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestType {
+    pub span: Span,
+    pub type_ann: Box<TypeAnn>,
+}
+
+// `readonly T[]` or `readonly [A, B]`.  Readonly arrays and tuples can't be
+// assigned to mutable ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadonlyType {
+    pub span: Span,
+    pub type_ann: Box<TypeAnn>,
+}
+
+// `keyof T`, the union of the keys of `T`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyOfType {
+    pub span: Span,
+    pub type_ann: Box<TypeAnn>,
+}
+
+// `typeof x`, the type of the value `x`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryType {
+    pub span: Span,
+    pub name: Ident,
+}
+
+// `T["prop"]`, the type of the prop `"prop"` on `T`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexedAccessType {
+    pub span: Span,
+    pub obj_type: Box<TypeAnn>,
+    pub index_type: Box<TypeAnn>,
+}
+
+// `T extends U ? X : Y`.  If `T` is a union, the conditional type is applied
+// to each member of the union and the results are unioned together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConditionalType {
+    pub span: Span,
+    pub check_type: Box<TypeAnn>,
+    pub extends_type: Box<TypeAnn>,
+    pub true_type: Box<TypeAnn>,
+    pub false_type: Box<TypeAnn>,
+}
+
+// `infer T`, declares a type that's inferred when matching against the
+// `extends` clause of a conditional type.  `T` can be used in the true branch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InferType {
+    pub span: Span,
+    pub name: Ident,
+}
+
+// `+` or `-` prefixes on the `readonly` and `?` modifiers of mapped types.  No
+// prefix is the same as `+`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MappedModifier {
+    Add,
+    Remove,
+}
+
+// `{[K in keyof T]?: T[K]}`, creates an object type with a prop for each key
+// in the constraint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MappedType {
+    pub span: Span,
+    pub type_param: Ident,
+    pub constraint: Box<TypeAnn>,
+    pub optional: Option<MappedModifier>,
+    pub readonly: Option<MappedModifier>,
+    pub type_ann: Box<TypeAnn>,
+}
+
+// `` `on${string}` ``, matches strings made up of the literal `quasis`
+// interleaved with values of `types`.  There's always one more quasi than
+// there are types.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateLiteralType {
+    pub span: Span,
+    pub quasis: Vec<TemplateElem>,
+    pub types: Vec<TypeAnn>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TypeAnn {
     Lam(LamType),
@@ -79,6 +211,15 @@ pub enum TypeAnn {
     Intersection(IntersectionType),
     Tuple(TupleType),
     Array(ArrayType),
+    Readonly(ReadonlyType),
+    KeyOf(KeyOfType),
+    Query(QueryType),
+    IndexedAccess(IndexedAccessType),
+    Conditional(ConditionalType),
+    Infer(InferType),
+    Mapped(MappedType),
+    TemplateLiteral(TemplateLiteralType),
+    Rest(RestType),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1,21 +1,68 @@
+use std::collections::HashSet;
 use std::rc::Rc;
 
 use swc_atoms::*;
-use swc_common::{SourceMap, DUMMY_SP};
+use swc_common::{BytePos, LineCol, SourceMap, DUMMY_SP};
 use swc_ecma_ast::*;
 use swc_ecma_codegen::*;
+use swc_ecma_visit::*;
 
 use crochet_ast as ast;
-use crochet_infer::Context;
+use crochet_infer::{lookup_alias, Context};
 use crochet_infer::types::{self, Scheme, Type, Variant};
 
+use crate::source_map::*;
+
+// The output of `codegen_d_ts_with_source_map`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DtsOutput {
+    pub d_ts: String,
+    // A v3 declaration map, this is returned even if it's also inlined in
+    // `d_ts`.
+    pub source_map: String,
+}
+
 pub fn codegen_d_ts(program: &ast::Program, ctx: &Context) -> String {
-    print_d_ts(&build_d_ts(program, ctx))
+    let cm = Rc::new(SourceMap::default());
+    let mut program = build_d_ts(program, ctx);
+    program.visit_mut_with(&mut SpanFixer::dummy());
+
+    print_d_ts(&program, cm, None)
 }
 
-fn print_d_ts(program: &Program) -> String {
-    let mut buf = vec![];
+// Declarations are mapped back to the statements they came from so that
+// "Go to definition" in TypeScript lands in the .crochet file.  `file` must
+// be the file that `program` was parsed from.
+pub fn codegen_d_ts_with_source_map(
+    program: &ast::Program,
+    ctx: &Context,
+    file: &ast::SourceFile,
+    emit: &SourceMapEmit,
+) -> DtsOutput {
     let cm = Rc::new(SourceMap::default());
+    let file = add_source_file(&cm, file);
+    let mut program = build_d_ts(program, ctx);
+    program.visit_mut_with(&mut SpanFixer::new(&file));
+
+    let mut mappings = vec![];
+    let d_ts = print_d_ts(&program, cm.clone(), Some(&mut mappings));
+    let source_map = build_source_map(&cm, &mut mappings);
+
+    DtsOutput {
+        d_ts: format!(
+            "{d_ts}{}\n",
+            source_mapping_url(&source_map, emit)
+        ),
+        source_map,
+    }
+}
+
+fn print_d_ts(
+    program: &Program,
+    cm: Rc<SourceMap>,
+    mappings: Option<&mut Vec<(BytePos, LineCol)>>,
+) -> String {
+    let mut buf = vec![];
 
     let mut emitter = Emitter {
         cfg: swc_ecma_codegen::Config {
@@ -23,7 +70,7 @@ fn print_d_ts(program: &Program) -> String {
         },
         cm: cm.clone(),
         comments: None,
-        wr: text_writer::JsWriter::new(cm, "\n", &mut buf, None),
+        wr: text_writer::JsWriter::new(cm, "\n", &mut buf, mappings),
     };
 
     emitter.emit_program(program).unwrap();
@@ -35,38 +82,61 @@ fn build_d_ts(program: &ast::Program, ctx: &Context) -> Program {
     let body: Vec<ModuleItem> = program
         .body
         .iter()
-        .map(|child| match child {
-            ast::Statement::VarDecl { pattern, init, .. } => {
-                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
-                    span: DUMMY_SP,
+        .flat_map(|child| match child {
+            ast::Statement::VarDecl {
+                span,
+                pattern,
+                init,
+                ..
+            } => {
+                vec![ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    span: build_span(span),
                     decl: Decl::Var(VarDecl {
-                        span: DUMMY_SP,
+                        span: build_span(span),
                         kind: VarDeclKind::Const,
                         declare: true,
                         decls: vec![VarDeclarator {
-                            span: DUMMY_SP,
+                            span: build_span(span),
                             name: build_pattern(pattern, init.as_ref(), ctx),
                             init: None,
                             definite: false,
                         }],
                     }),
-                }))
+                }))]
             }
             ast::Statement::TypeDecl {
+                span,
                 declare,
                 id,
+                type_params,
+                type_ann,
                 ..
-            } => match ctx.types.get(&id.name) {
-                Some(scheme) => ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(TsTypeAliasDecl {
-                    span: DUMMY_SP,
-                    declare: declare.to_owned(),
-                    id: build_ident(id),
-                    type_params: None,
-                    type_ann: Box::from(build_type(&scheme.ty, None, None)),
-                }))),
-                None => panic!("Couldn't find type in ctx.types"),
-            },
-            _ => ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP })),
+            } => {
+                let mut decl =
+                    build_type_alias(span, id, &id.name, type_params, Some(type_ann), ctx);
+                decl.declare = declare.to_owned();
+                vec![ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(decl)))]
+            }
+            ast::Statement::EnumDecl {
+                span,
+                id,
+                type_params,
+                variants,
+            } => build_enum(span, id, type_params, variants, ctx),
+            ast::Statement::ClassDecl { span, id, members } => {
+                vec![build_class(span, id, members, ctx)]
+            }
+            ast::Statement::InterfaceDecl {
+                span,
+                id,
+                type_params,
+                extends,
+                members,
+            } => vec![build_interface(span, id, type_params, extends, members)],
+            ast::Statement::NamespaceDecl { span, id, body } => {
+                vec![build_namespace(span, id, body, ctx)]
+            }
+            _ => vec![ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }))],
         })
         .collect();
 
@@ -77,10 +147,913 @@ fn build_d_ts(program: &ast::Program, ctx: &Context) -> Program {
     })
 }
 
+// The types of a namespace's members are stored in `ctx` using their qualified
+// names, e.g. `JSX.Element`.  Every member is exported from the namespace.
+// NOTE: swc prints namespaces using the equivalent `module` keyword.
+fn build_namespace(
+    span: &ast::Span,
+    id: &ast::Ident,
+    body: &[ast::Statement],
+    ctx: &Context,
+) -> ModuleItem {
+    let body: Vec<ModuleItem> = body
+        .iter()
+        .map(|stmt| match stmt {
+            ast::Statement::TypeDecl {
+                span,
+                id: member_id,
+                type_params,
+                type_ann,
+                ..
+            } => {
+                let name = format!("{}.{}", id.name, member_id.name);
+                let decl =
+                    build_type_alias(span, member_id, &name, type_params, Some(type_ann), ctx);
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    span: build_span(span),
+                    decl: Decl::TsTypeAlias(decl),
+                }))
+            }
+            ast::Statement::InterfaceDecl {
+                span,
+                id,
+                type_params,
+                extends,
+                members,
+            } => build_interface(span, id, type_params, extends, members),
+            _ => panic!("Namespaces can only contain type and interface declarations"),
+        })
+        .collect();
+
+    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        span: build_span(span),
+        decl: Decl::TsModule(TsModuleDecl {
+            span: build_span(span),
+            declare: true,
+            global: false,
+            id: TsModuleName::Ident(build_ident(id)),
+            body: Some(TsNamespaceBody::TsModuleBlock(TsModuleBlock {
+                span: DUMMY_SP,
+                body,
+            })),
+        }),
+    }))
+}
+
+// Qualified names, e.g. `JSX.Element`, are emitted as nested `TsQualifiedName`s.
+fn build_entity_name(name: &str) -> TsEntityName {
+    let mut names = name.split('.').map(|name| Ident {
+        span: DUMMY_SP,
+        sym: JsWord::from(name),
+        optional: false,
+    });
+    let first = TsEntityName::from(names.next().unwrap());
+    names.fold(first, |left, right| {
+        TsEntityName::TsQualifiedName(Box::from(TsQualifiedName { left, right }))
+    })
+}
+
+// Enums are exported as a discriminated union type along with an object
+// containing constructors for each of the enum's variants.
+fn build_enum(
+    span: &ast::Span,
+    id: &ast::Ident,
+    type_params: &Option<Vec<ast::TypeParam>>,
+    variants: &[ast::EnumVariant],
+    ctx: &Context,
+) -> Vec<ModuleItem> {
+    let type_scheme = ctx
+        .types
+        .get(&id.name)
+        .expect("Couldn't find type in ctx.types");
+
+    let type_decl = build_type_alias(span, id, &id.name, type_params, None, ctx);
+    let type_decl = ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(type_decl)));
+
+    // The object type for each variant, in the same order as `variants`.
+    let variant_types = match &type_scheme.ty.variant {
+        Variant::Union(types) => types.to_owned(),
+        _ => vec![type_scheme.ty.to_owned()],
+    };
+
+    // Constructors are emitted as they were declared so that they use the
+    // enum's type params instead of the qualifiers of their inferred type.
+    let enum_type = TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: build_entity_name(&id.name),
+        type_params: type_params
+            .as_ref()
+            .map(|type_params| TsTypeParamInstantiation {
+                span: DUMMY_SP,
+                params: type_params
+                    .iter()
+                    .map(|param| {
+                        Box::from(TsType::TsTypeRef(TsTypeRef {
+                            span: DUMMY_SP,
+                            type_name: TsEntityName::from(build_ident(&param.name)),
+                            type_params: None,
+                        }))
+                    })
+                    .collect(),
+            }),
+    });
+
+    let members: Vec<TsTypeElement> = variants
+        .iter()
+        .zip(variant_types)
+        .map(|(variant, variant_type)| {
+            let ty = match &variant.fields {
+                Some(fields) => {
+                    let params: Vec<TsFnParam> = fields
+                        .iter()
+                        .map(|field| {
+                            TsFnParam::Ident(BindingIdent {
+                                id: Ident {
+                                    span: build_span(&field.span),
+                                    sym: JsWord::from(field.name.to_owned()),
+                                    optional: field.optional,
+                                },
+                                type_ann: Some(TsTypeAnn {
+                                    span: DUMMY_SP,
+                                    type_ann: Box::from(build_type_ann(&field.type_ann)),
+                                }),
+                            })
+                        })
+                        .collect();
+
+                    TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(TsFnType {
+                        span: DUMMY_SP,
+                        params,
+                        type_params: build_type_param_decl(type_params),
+                        type_ann: TsTypeAnn {
+                            span: DUMMY_SP,
+                            type_ann: Box::from(enum_type.clone()),
+                        },
+                    }))
+                }
+                // Unit variants use their own object type instead of the enum's
+                // type since a `const` can't have type params.
+                _ => build_type(&variant_type, None, None),
+            };
+
+            TsTypeElement::TsPropertySignature(TsPropertySignature {
+                span: build_span(&variant.span),
+                readonly: false,
+                key: Box::from(Expr::from(build_ident(&variant.name))),
+                computed: false,
+                optional: false,
+                init: None,
+                params: vec![],
+                type_ann: Some(TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::from(ty),
+                }),
+                type_params: None,
+            })
+        })
+        .collect();
+
+    let ctors_decl = ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        span: build_span(span),
+        decl: Decl::Var(VarDecl {
+            span: build_span(span),
+            kind: VarDeclKind::Const,
+            declare: true,
+            decls: vec![VarDeclarator {
+                span: build_span(span),
+                name: Pat::Ident(BindingIdent {
+                    id: build_ident(id),
+                    type_ann: Some(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::from(TsType::TsTypeLit(TsTypeLit {
+                            span: DUMMY_SP,
+                            members,
+                        })),
+                    }),
+                }),
+                init: None,
+                definite: false,
+            }],
+        }),
+    }));
+
+    vec![type_decl, ctors_decl]
+}
+
+// Fields and methods are looked up in the instance type of the class while
+// the constructor's params come from the type of the class's value.
+fn build_class(
+    span: &ast::Span,
+    id: &ast::Ident,
+    members: &[ast::ClassMember],
+    ctx: &Context,
+) -> ModuleItem {
+    let instance_scheme = ctx
+        .types
+        .get(&id.name)
+        .expect("Couldn't find type in ctx.types");
+    let ctor_scheme = ctx
+        .values
+        .get(&id.name)
+        .expect("Couldn't find value in ctx.values");
+    let props = match &instance_scheme.ty.variant {
+        Variant::Class(types::ClassType { props, .. }) => props,
+        _ => panic!("Expected {} to be a class", id.name),
+    };
+    let get_prop = |name: &str| {
+        props
+            .iter()
+            .find(|prop| prop.name == name)
+            .expect("Couldn't find member in class")
+    };
+
+    let body: Vec<ClassMember> = members
+        .iter()
+        .map(|member| match member {
+            ast::ClassMember::Constructor(ast::Constructor { span, params, .. }) => {
+                let (types, _) = lam_params_and_ret(&ctor_scheme.ty);
+                ClassMember::Constructor(Constructor {
+                    span: build_span(span),
+                    key: PropName::Ident(Ident {
+                        span: DUMMY_SP,
+                        sym: JsWord::from("constructor"),
+                        optional: false,
+                    }),
+                    params: build_params(params, types)
+                        .into_iter()
+                        .map(ParamOrTsParamProp::Param)
+                        .collect(),
+                    body: None,
+                    accessibility: None,
+                    is_optional: false,
+                })
+            }
+            ast::ClassMember::Method(ast::ClassMethod { span, key, lambda }) => {
+                let prop = get_prop(&key.name);
+                let (types, ret) = lam_params_and_ret(&prop.ty);
+                ClassMember::Method(ClassMethod {
+                    span: build_span(span),
+                    key: PropName::Ident(build_ident(key)),
+                    function: Function {
+                        params: build_params(&lambda.params, types),
+                        decorators: vec![],
+                        span: DUMMY_SP,
+                        body: None,
+                        is_generator: false,
+                        is_async: false,
+                        type_params: None,
+                        return_type: Some(TsTypeAnn {
+                            span: DUMMY_SP,
+                            type_ann: Box::from(build_type(ret, None, None)),
+                        }),
+                    },
+                    kind: MethodKind::Method,
+                    is_static: false,
+                    accessibility: None,
+                    is_abstract: false,
+                    is_optional: false,
+                    is_override: false,
+                })
+            }
+            ast::ClassMember::Prop(ast::ClassProp { span, key, .. }) => {
+                let prop = get_prop(&key.name);
+                ClassMember::ClassProp(ClassProp {
+                    span: build_span(span),
+                    key: PropName::Ident(build_ident(key)),
+                    value: None,
+                    type_ann: Some(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::from(build_type(&prop.ty, None, None)),
+                    }),
+                    is_static: false,
+                    decorators: vec![],
+                    accessibility: None,
+                    is_abstract: false,
+                    is_optional: prop.optional,
+                    is_override: false,
+                    readonly: false,
+                    declare: false,
+                    definite: false,
+                })
+            }
+        })
+        .collect();
+
+    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        span: build_span(span),
+        decl: Decl::Class(ClassDecl {
+            ident: build_ident(id),
+            declare: true,
+            class: Class {
+                span: build_span(span),
+                decorators: vec![],
+                body,
+                super_class: None,
+                is_abstract: false,
+                type_params: None,
+                super_type_params: None,
+                implements: vec![],
+            },
+        }),
+    }))
+}
+
+// `name` is the key of the alias' scheme in `ctx.types`.  The scheme's
+// qualifiers are replaced with references to the alias' type params so that
+// the type params keep the names they were given in the source instead of
+// being named after the qualifiers' ids like other type variables.
+//
+// Lambdas nested in an alias don't keep their type params once they've been
+// inferred so aliases containing generic lambdas are emitted as they were
+// written in `type_ann` instead.
+fn build_type_alias(
+    span: &ast::Span,
+    id: &ast::Ident,
+    name: &str,
+    type_params: &Option<Vec<ast::TypeParam>>,
+    type_ann: Option<&ast::TypeAnn>,
+    ctx: &Context,
+) -> TsTypeAliasDecl {
+    if let Some(type_ann) = type_ann.filter(|type_ann| has_generic_lam(type_ann)) {
+        return TsTypeAliasDecl {
+            span: build_span(span),
+            declare: false,
+            id: build_ident(id),
+            type_params: build_type_param_decl(type_params),
+            type_ann: Box::from(build_type_ann(type_ann)),
+        };
+    }
+
+    let type_args = type_params.as_ref().map(|type_params| {
+        type_params
+            .iter()
+            .map(|param| ctx.alias(&param.name.name, None))
+            .collect()
+    });
+    let alias = types::AliasType {
+        name: name.to_owned(),
+        type_params: type_args,
+    };
+    let ty = match lookup_alias(ctx, &alias) {
+        Ok(ty) => ty,
+        Err(_) => panic!("Couldn't find type in ctx.types"),
+    };
+
+    TsTypeAliasDecl {
+        span: build_span(span),
+        declare: false,
+        id: build_ident(id),
+        type_params: build_type_param_decl(type_params),
+        type_ann: Box::from(build_type(&ty, None, None)),
+    }
+}
+
+// Interfaces are emitted as they were written instead of using their inferred
+// type.  This preserves their bases, method signatures and call signatures.
+// Each declaration of an interface is emitted separately since TypeScript
+// merges them the same way we do.
+fn build_interface(
+    span: &ast::Span,
+    id: &ast::Ident,
+    type_params: &Option<Vec<ast::TypeParam>>,
+    extends: &[ast::TypeRef],
+    members: &[ast::TypeElem],
+) -> ModuleItem {
+    let body: Vec<TsTypeElement> = members
+        .iter()
+        .map(|member| match member {
+            ast::TypeElem::Prop(prop) => TsTypeElement::TsPropertySignature(TsPropertySignature {
+                span: build_span(&prop.span),
+                readonly: prop.readonly,
+                key: Box::from(Expr::from(Ident {
+                    span: build_span(&prop.span),
+                    sym: JsWord::from(prop.name.to_owned()),
+                    optional: false,
+                })),
+                computed: false,
+                optional: prop.optional,
+                init: None,
+                params: vec![],
+                type_ann: Some(TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::from(build_type_ann(&prop.type_ann)),
+                }),
+                type_params: None,
+            }),
+            ast::TypeElem::Method(method) => TsTypeElement::TsMethodSignature(TsMethodSignature {
+                span: build_span(&method.span),
+                readonly: false,
+                key: Box::from(Expr::from(Ident {
+                    span: build_span(&method.span),
+                    sym: JsWord::from(method.name.to_owned()),
+                    optional: false,
+                })),
+                computed: false,
+                optional: method.optional,
+                params: build_fn_params(&method.lam.params),
+                type_ann: Some(TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::from(build_type_ann(&method.lam.ret)),
+                }),
+                type_params: build_type_param_decl(&method.lam.type_params),
+            }),
+            ast::TypeElem::Call(lam) => TsTypeElement::TsCallSignatureDecl(TsCallSignatureDecl {
+                span: DUMMY_SP,
+                params: build_fn_params(&lam.params),
+                type_ann: Some(TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::from(build_type_ann(&lam.ret)),
+                }),
+                type_params: build_type_param_decl(&lam.type_params),
+            }),
+            ast::TypeElem::Index(index) => build_index_signature(
+                &index.key.name,
+                build_type_ann(&index.key_type),
+                build_type_ann(&index.type_ann),
+                index.readonly,
+            ),
+        })
+        .collect();
+
+    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        span: build_span(span),
+        decl: Decl::TsInterface(TsInterfaceDecl {
+            span: build_span(span),
+            id: build_ident(id),
+            declare: false,
+            type_params: build_type_param_decl(type_params),
+            extends: extends
+                .iter()
+                .map(|base| TsExprWithTypeArgs {
+                    span: DUMMY_SP,
+                    expr: Box::from(Expr::from(Ident {
+                        span: DUMMY_SP,
+                        sym: JsWord::from(base.name.to_owned()),
+                        optional: false,
+                    })),
+                    type_args: build_type_args(&base.type_params),
+                })
+                .collect(),
+            body: TsInterfaceBody {
+                span: DUMMY_SP,
+                body,
+            },
+        }),
+    }))
+}
+
+// Returns true if `type_ann` contains a lambda with type params.
+fn has_generic_lam(type_ann: &ast::TypeAnn) -> bool {
+    match type_ann {
+        ast::TypeAnn::Lam(ast::LamType {
+            params,
+            ret,
+            type_params,
+            ..
+        }) => {
+            type_params.is_some()
+                || params.iter().any(|param| has_generic_lam(&param.type_ann))
+                || has_generic_lam(ret)
+        }
+        ast::TypeAnn::Object(ast::ObjectType { props, index, .. }) => {
+            props.iter().any(|prop| has_generic_lam(&prop.type_ann))
+                || index
+                    .as_ref()
+                    .is_some_and(|index| has_generic_lam(&index.type_ann))
+        }
+        ast::TypeAnn::TypeRef(ast::TypeRef { type_params, .. }) => type_params
+            .as_ref()
+            .is_some_and(|type_params| type_params.iter().any(has_generic_lam)),
+        ast::TypeAnn::Union(ast::UnionType { types, .. })
+        | ast::TypeAnn::Intersection(ast::IntersectionType { types, .. })
+        | ast::TypeAnn::Tuple(ast::TupleType { types, .. })
+        | ast::TypeAnn::TemplateLiteral(ast::TemplateLiteralType { types, .. }) => {
+            types.iter().any(has_generic_lam)
+        }
+        ast::TypeAnn::Array(ast::ArrayType { elem_type, .. }) => has_generic_lam(elem_type),
+        ast::TypeAnn::Readonly(ast::ReadonlyType { type_ann, .. })
+        | ast::TypeAnn::KeyOf(ast::KeyOfType { type_ann, .. })
+        | ast::TypeAnn::Rest(ast::RestType { type_ann, .. })
+        | ast::TypeAnn::Mapped(ast::MappedType { type_ann, .. }) => has_generic_lam(type_ann),
+        ast::TypeAnn::IndexedAccess(ast::IndexedAccessType {
+            obj_type,
+            index_type,
+            ..
+        }) => has_generic_lam(obj_type) || has_generic_lam(index_type),
+        ast::TypeAnn::Conditional(ast::ConditionalType {
+            check_type,
+            extends_type,
+            true_type,
+            false_type,
+            ..
+        }) => [check_type, extends_type, true_type, false_type]
+            .iter()
+            .any(|type_ann| has_generic_lam(type_ann)),
+        ast::TypeAnn::Lit(_)
+        | ast::TypeAnn::Prim(_)
+        | ast::TypeAnn::Query(_)
+        | ast::TypeAnn::Infer(_) => false,
+    }
+}
+
+fn build_type_param_decl(type_params: &Option<Vec<ast::TypeParam>>) -> Option<TsTypeParamDecl> {
+    type_params.as_ref().map(|type_params| TsTypeParamDecl {
+        span: DUMMY_SP,
+        params: type_params
+            .iter()
+            .map(|param| TsTypeParam {
+                span: DUMMY_SP,
+                name: build_ident(&param.name),
+                is_in: false,
+                is_out: false,
+                constraint: param
+                    .constraint
+                    .as_ref()
+                    .map(|constraint| Box::from(build_type_ann(constraint))),
+                default: param
+                    .default
+                    .as_ref()
+                    .map(|default| Box::from(build_type_ann(default))),
+            })
+            .collect(),
+    })
+}
+
+fn build_type_args(type_args: &Option<Vec<ast::TypeAnn>>) -> Option<TsTypeParamInstantiation> {
+    type_args.as_ref().map(|type_args| TsTypeParamInstantiation {
+        span: DUMMY_SP,
+        params: type_args
+            .iter()
+            .map(|type_ann| Box::from(build_type_ann(type_ann)))
+            .collect(),
+    })
+}
+
+// NOTE: swc_ecma_codegen doesn't emit the `readonly` modifier for index
+// signatures yet, but we set it anyways so that the AST is correct.
+fn build_index_signature(key: &str, key_type: TsType, value: TsType, readonly: bool) -> TsTypeElement {
+    TsTypeElement::TsIndexSignature(TsIndexSignature {
+        span: DUMMY_SP,
+        params: vec![TsFnParam::Ident(BindingIdent {
+            id: Ident {
+                span: DUMMY_SP,
+                sym: JsWord::from(key.to_owned()),
+                optional: false,
+            },
+            type_ann: Some(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::from(key_type),
+            }),
+        })],
+        type_ann: Some(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::from(value),
+        }),
+        readonly,
+        is_static: false,
+    })
+}
+
+// Params without names are named `arg0`, `arg1`, etc.
+fn build_fn_params(params: &[ast::TFnParam]) -> Vec<TsFnParam> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let name = match &param.name {
+                Some(name) => name.name.to_owned(),
+                None => format!("arg{}", i),
+            };
+            build_fn_param(&name, build_type_ann(&param.type_ann))
+        })
+        .collect()
+}
+
+// Rest types are emitted as rest params, e.g. `...arg1: number[]`.
+fn build_fn_param(name: &str, type_ann: TsType) -> TsFnParam {
+    let id = Ident {
+        span: DUMMY_SP,
+        sym: JsWord::from(name.to_owned()),
+        optional: false,
+    };
+    match type_ann {
+        TsType::TsRestType(TsRestType { type_ann, .. }) => TsFnParam::Rest(RestPat {
+            span: DUMMY_SP,
+            dot3_token: DUMMY_SP,
+            arg: Box::from(Pat::Ident(BindingIdent { id, type_ann: None })),
+            type_ann: Some(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann,
+            }),
+        }),
+        _ => TsFnParam::Ident(BindingIdent {
+            id,
+            type_ann: Some(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::from(type_ann),
+            }),
+        }),
+    }
+}
+
+/// Converts a type annotation to a TsType.  Unlike `build_type`, this is used
+/// for declarations which are emitted the same way they were written.
+pub fn build_type_ann(type_ann: &ast::TypeAnn) -> TsType {
+    match type_ann {
+        ast::TypeAnn::Lam(lam) => {
+            TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(TsFnType {
+                span: DUMMY_SP,
+                params: build_fn_params(&lam.params),
+                type_params: build_type_param_decl(&lam.type_params),
+                type_ann: TsTypeAnn {
+                    span: DUMMY_SP,
+                    type_ann: Box::from(build_type_ann(&lam.ret)),
+                },
+            }))
+        }
+        ast::TypeAnn::Lit(ast::LitType { lit, .. }) => {
+            let lit = match lit {
+                ast::Lit::Num(n) => TsLit::Number(Number {
+                    span: DUMMY_SP,
+                    value: n.value.parse().unwrap(),
+                    raw: Some(JsWord::from(n.value.to_owned())),
+                }),
+                ast::Lit::Bool(b) => TsLit::Bool(Bool {
+                    span: DUMMY_SP,
+                    value: b.value,
+                }),
+                ast::Lit::Str(s) => TsLit::Str(Str {
+                    span: DUMMY_SP,
+                    value: JsWord::from(s.value.to_owned()),
+                    raw: None,
+                }),
+                _ => panic!("TODO: model null and undefined as keywords"),
+            };
+            TsType::TsLitType(TsLitType {
+                span: DUMMY_SP,
+                lit,
+            })
+        }
+        ast::TypeAnn::Prim(ast::PrimType { prim, .. }) => {
+            let kind = match prim {
+                ast::Primitive::Num => TsKeywordTypeKind::TsNumberKeyword,
+                ast::Primitive::Bool => TsKeywordTypeKind::TsBooleanKeyword,
+                ast::Primitive::Str => TsKeywordTypeKind::TsStringKeyword,
+                ast::Primitive::Undefined => TsKeywordTypeKind::TsUndefinedKeyword,
+                ast::Primitive::Null => TsKeywordTypeKind::TsNullKeyword,
+                ast::Primitive::Never => TsKeywordTypeKind::TsNeverKeyword,
+                ast::Primitive::Unknown => TsKeywordTypeKind::TsUnknownKeyword,
+                ast::Primitive::Any => TsKeywordTypeKind::TsAnyKeyword,
+            };
+            TsType::TsKeywordType(TsKeywordType {
+                span: DUMMY_SP,
+                kind,
+            })
+        }
+        ast::TypeAnn::Object(ast::ObjectType { props, index, .. }) => TsType::TsTypeLit(TsTypeLit {
+            span: DUMMY_SP,
+            members: index
+                .iter()
+                .map(|index| {
+                    build_index_signature(
+                        &index.key.name,
+                        build_type_ann(&index.key_type),
+                        build_type_ann(&index.type_ann),
+                        index.readonly,
+                    )
+                })
+                .chain(props.iter().map(|prop| {
+                    TsTypeElement::TsPropertySignature(TsPropertySignature {
+                        span: DUMMY_SP,
+                        readonly: prop.readonly,
+                        key: Box::from(Expr::from(Ident {
+                            span: DUMMY_SP,
+                            sym: JsWord::from(prop.name.to_owned()),
+                            optional: false,
+                        })),
+                        computed: false,
+                        optional: prop.optional,
+                        init: None,
+                        params: vec![],
+                        type_ann: Some(TsTypeAnn {
+                            span: DUMMY_SP,
+                            type_ann: Box::from(build_type_ann(&prop.type_ann)),
+                        }),
+                        type_params: None,
+                    })
+                }))
+                .collect(),
+        }),
+        ast::TypeAnn::TypeRef(ast::TypeRef {
+            name, type_params, ..
+        }) => TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: build_entity_name(name),
+            type_params: build_type_args(type_params),
+        }),
+        ast::TypeAnn::Union(ast::UnionType { types, .. }) => {
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
+                span: DUMMY_SP,
+                types: types
+                    .iter()
+                    .map(|type_ann| Box::from(build_type_ann(type_ann)))
+                    .collect(),
+            }))
+        }
+        ast::TypeAnn::Intersection(ast::IntersectionType { types, .. }) => {
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsIntersectionType(
+                TsIntersectionType {
+                    span: DUMMY_SP,
+                    types: types
+                        .iter()
+                        .map(|type_ann| Box::from(build_type_ann(type_ann)))
+                        .collect(),
+                },
+            ))
+        }
+        ast::TypeAnn::Tuple(ast::TupleType { types, .. }) => TsType::TsTupleType(TsTupleType {
+            span: DUMMY_SP,
+            elem_types: types
+                .iter()
+                .map(|type_ann| TsTupleElement {
+                    span: DUMMY_SP,
+                    label: None,
+                    ty: build_type_ann(type_ann),
+                })
+                .collect(),
+        }),
+        ast::TypeAnn::Array(ast::ArrayType { elem_type, .. }) => TsType::TsArrayType(TsArrayType {
+            span: DUMMY_SP,
+            elem_type: Box::from(parenthesize(build_type_ann(elem_type))),
+        }),
+        ast::TypeAnn::Readonly(ast::ReadonlyType { type_ann, .. }) => {
+            TsType::TsTypeOperator(TsTypeOperator {
+                span: DUMMY_SP,
+                op: TsTypeOperatorOp::ReadOnly,
+                type_ann: Box::from(build_type_ann(type_ann)),
+            })
+        }
+        ast::TypeAnn::KeyOf(ast::KeyOfType { type_ann, .. }) => {
+            TsType::TsTypeOperator(TsTypeOperator {
+                span: DUMMY_SP,
+                op: TsTypeOperatorOp::KeyOf,
+                type_ann: Box::from(build_type_ann(type_ann)),
+            })
+        }
+        ast::TypeAnn::Query(ast::QueryType { name, .. }) => TsType::TsTypeQuery(TsTypeQuery {
+            span: DUMMY_SP,
+            expr_name: TsTypeQueryExpr::TsEntityName(TsEntityName::from(build_ident(name))),
+            type_args: None,
+        }),
+        ast::TypeAnn::IndexedAccess(ast::IndexedAccessType {
+            obj_type,
+            index_type,
+            ..
+        }) => TsType::TsIndexedAccessType(TsIndexedAccessType {
+            span: DUMMY_SP,
+            readonly: false,
+            obj_type: Box::from(parenthesize(build_type_ann(obj_type))),
+            index_type: Box::from(build_type_ann(index_type)),
+        }),
+        ast::TypeAnn::Conditional(ast::ConditionalType {
+            check_type,
+            extends_type,
+            true_type,
+            false_type,
+            ..
+        }) => TsType::TsConditionalType(TsConditionalType {
+            span: DUMMY_SP,
+            check_type: Box::from(build_type_ann(check_type)),
+            extends_type: Box::from(build_type_ann(extends_type)),
+            true_type: Box::from(build_type_ann(true_type)),
+            false_type: Box::from(build_type_ann(false_type)),
+        }),
+        ast::TypeAnn::Infer(ast::InferType { name, .. }) => TsType::TsInferType(TsInferType {
+            span: DUMMY_SP,
+            type_param: build_ts_type_param(&name.name, None),
+        }),
+        ast::TypeAnn::Mapped(ast::MappedType {
+            type_param,
+            constraint,
+            optional,
+            readonly,
+            type_ann,
+            ..
+        }) => TsType::TsMappedType(TsMappedType {
+            span: DUMMY_SP,
+            readonly: readonly.as_ref().map(build_mapped_modifier),
+            type_param: build_ts_type_param(&type_param.name, Some(build_type_ann(constraint))),
+            name_type: None,
+            optional: optional.as_ref().map(build_mapped_modifier),
+            type_ann: Some(Box::from(build_type_ann(type_ann))),
+        }),
+        ast::TypeAnn::Rest(ast::RestType { type_ann, .. }) => TsType::TsRestType(TsRestType {
+            span: DUMMY_SP,
+            type_ann: Box::from(build_type_ann(type_ann)),
+        }),
+        ast::TypeAnn::TemplateLiteral(ast::TemplateLiteralType { quasis, types, .. }) => {
+            let quasis = quasis
+                .iter()
+                .map(|quasi| match &quasi.cooked {
+                    ast::Lit::Str(ast::Str { value, .. }) => value.to_owned(),
+                    _ => panic!("quasi.cooked must be a string"),
+                })
+                .collect();
+            build_tpl_lit_type(quasis, types.iter().map(build_type_ann).collect())
+        }
+    }
+}
+
+// Wraps types that bind less tightly than array and indexed access types in
+// parens, e.g. `(A | B)[]` and `(infer E)[]`.  swc doesn't do this for us.
+fn parenthesize(t: TsType) -> TsType {
+    match t {
+        TsType::TsUnionOrIntersectionType(_)
+        | TsType::TsFnOrConstructorType(_)
+        | TsType::TsConditionalType(_)
+        | TsType::TsInferType(_)
+        | TsType::TsTypeOperator(_) => TsType::TsParenthesizedType(TsParenthesizedType {
+            span: DUMMY_SP,
+            type_ann: Box::from(t),
+        }),
+        _ => t,
+    }
+}
+
+fn build_ts_type_param(name: &str, constraint: Option<TsType>) -> TsTypeParam {
+    TsTypeParam {
+        span: DUMMY_SP,
+        name: Ident {
+            span: DUMMY_SP,
+            sym: JsWord::from(name.to_owned()),
+            optional: false,
+        },
+        is_in: false,
+        is_out: false,
+        constraint: constraint.map(Box::from),
+        default: None,
+    }
+}
+
+fn build_tpl_lit_type(quasis: Vec<String>, types: Vec<TsType>) -> TsType {
+    let tail = quasis.len() - 1;
+    TsType::TsLitType(TsLitType {
+        span: DUMMY_SP,
+        lit: TsLit::Tpl(TsTplLitType {
+            span: DUMMY_SP,
+            types: types.into_iter().map(Box::from).collect(),
+            quasis: quasis
+                .into_iter()
+                .enumerate()
+                .map(|(i, quasi)| TplElement {
+                    span: DUMMY_SP,
+                    tail: i == tail,
+                    cooked: Some(JsWord::from(quasi.to_owned())),
+                    raw: JsWord::from(quasi),
+                })
+                .collect(),
+        }),
+    })
+}
+
+fn build_mapped_modifier(modifier: &ast::MappedModifier) -> TruePlusMinus {
+    match modifier {
+        ast::MappedModifier::Add => TruePlusMinus::True,
+        ast::MappedModifier::Remove => TruePlusMinus::Minus,
+    }
+}
+
+fn lam_params_and_ret(ty: &Type) -> (&[Type], &Type) {
+    match &ty.variant {
+        Variant::Lam(types::LamType { params, ret, .. }) => (params, ret),
+        _ => panic!("Expected a lambda type"),
+    }
+}
+
+// Builds params with type annotations for class constructors and methods.
+fn build_params(patterns: &[ast::Pattern], types: &[Type]) -> Vec<Param> {
+    patterns
+        .iter()
+        .zip(types)
+        .map(|(pattern, ty)| {
+            let type_ann = Some(TsTypeAnn {
+                span: DUMMY_SP,
+                type_ann: Box::from(build_type(ty, None, None)),
+            });
+            let pat = match build_pattern_rec(pattern) {
+                Pat::Ident(id) => Pat::Ident(BindingIdent { type_ann, ..id }),
+                Pat::Rest(rest) => Pat::Rest(RestPat { type_ann, ..rest }),
+                pat => pat,
+            };
+            Param::from(pat)
+        })
+        .collect()
+}
+
 // TODO: create a trait for this and then provide multiple implementations
 pub fn build_ident(id: &ast::Ident) -> Ident {
     Ident {
-        span: DUMMY_SP,
+        span: build_span(&id.span),
         sym: JsWord::from(id.name.to_owned()),
         optional: false,
     }
@@ -132,6 +1105,9 @@ pub fn build_type_params(scheme: &Scheme) -> Option<TsTypeParamDecl> {
         .chars()
         .collect();
 
+    let mut spread_ids = HashSet::new();
+    collect_spread_type_vars(&scheme.ty, &mut spread_ids);
+
     if !scheme.qualifiers.is_empty() {
         Some(TsTypeParamDecl {
             span: DUMMY_SP,
@@ -139,6 +1115,18 @@ pub fn build_type_params(scheme: &Scheme) -> Option<TsTypeParamDecl> {
                 .qualifiers
                 .iter()
                 .map(|id| {
+                    // TypeScript only allows type params to be spread if
+                    // they're constrained to array types.
+                    let constraint = match spread_ids.contains(id) {
+                        true => Some(Box::from(TsType::TsArrayType(TsArrayType {
+                            span: DUMMY_SP,
+                            elem_type: Box::from(TsType::TsKeywordType(TsKeywordType {
+                                span: DUMMY_SP,
+                                kind: TsKeywordTypeKind::TsUnknownKeyword,
+                            })),
+                        }))),
+                        false => None,
+                    };
                     let id = chars.get(id.to_owned() as usize).unwrap();
 
                     TsTypeParam {
@@ -150,7 +1138,7 @@ pub fn build_type_params(scheme: &Scheme) -> Option<TsTypeParamDecl> {
                         },
                         is_in: false,
                         is_out: false,
-                        constraint: None,
+                        constraint,
                         default: None,
                     }
                 })
@@ -161,6 +1149,33 @@ pub fn build_type_params(scheme: &Scheme) -> Option<TsTypeParamDecl> {
     }
 }
 
+// Collects the ids of type variables that are spread in tuples or rest params,
+// e.g. `T` in `[...T, U]`.
+fn collect_spread_type_vars(t: &Type, ids: &mut HashSet<i32>) {
+    match &t.variant {
+        Variant::Rest(arg) => match &arg.variant {
+            Variant::Var => {
+                ids.insert(arg.id);
+            }
+            _ => collect_spread_type_vars(arg, ids),
+        },
+        Variant::Lam(types::LamType { params, ret, .. }) => {
+            params
+                .iter()
+                .for_each(|param| collect_spread_type_vars(param, ids));
+            collect_spread_type_vars(ret, ids);
+        }
+        Variant::Union(types) | Variant::Intersection(types) | Variant::Tuple(types) => {
+            types.iter().for_each(|t| collect_spread_type_vars(t, ids))
+        }
+        Variant::Object(props) => props
+            .iter()
+            .for_each(|prop| collect_spread_type_vars(&prop.ty, ids)),
+        Variant::Array(t) | Variant::Readonly(t) => collect_spread_type_vars(t, ids),
+        _ => (),
+    }
+}
+
 /// Converts an internal Type to a TsType for eventual export to .d.ts.
 ///
 /// `expr` should be the original expression that `ty` was inferred
@@ -196,6 +1211,9 @@ pub fn build_type(
                 crochet_infer::types::Primitive::Str => TsKeywordTypeKind::TsStringKeyword,
                 crochet_infer::types::Primitive::Undefined => TsKeywordTypeKind::TsUndefinedKeyword,
                 crochet_infer::types::Primitive::Null => TsKeywordTypeKind::TsNullKeyword,
+                crochet_infer::types::Primitive::Never => TsKeywordTypeKind::TsNeverKeyword,
+                crochet_infer::types::Primitive::Unknown => TsKeywordTypeKind::TsUnknownKeyword,
+                crochet_infer::types::Primitive::Any => TsKeywordTypeKind::TsAnyKeyword,
             };
 
             TsType::TsKeywordType(TsKeywordType {
@@ -247,6 +1265,12 @@ pub fn build_type(
                             .iter()
                             .zip(expr_params)
                             .map(|(inferred_type, pattern)| {
+                                // Rest params already have the `...` so we
+                                // use the type of the array they're bound to.
+                                let inferred_type = match &inferred_type.variant {
+                                    Variant::Rest(arg) => arg.as_ref(),
+                                    _ => inferred_type,
+                                };
                                 let type_ann = Some(TsTypeAnn {
                                     span: DUMMY_SP,
                                     type_ann: Box::from(build_type(inferred_type, None, None)),
@@ -299,19 +1323,7 @@ pub fn build_type(
                         .iter()
                         .enumerate()
                         .map(|(i, arg)| {
-                            let type_ann = Some(TsTypeAnn {
-                                span: DUMMY_SP,
-                                type_ann: Box::from(build_type(arg, None, None)),
-                            });
-
-                            TsFnParam::Ident(BindingIdent {
-                                id: Ident {
-                                    span: DUMMY_SP,
-                                    sym: JsWord::from(format!("arg{}", i)),
-                                    optional: false,
-                                },
-                                type_ann,
-                            })
+                            build_fn_param(&format!("arg{}", i), build_type(arg, None, None))
                         })
                         .collect();
 
@@ -353,7 +1365,7 @@ pub fn build_type(
                 .map(|prop| {
                     TsTypeElement::TsPropertySignature(TsPropertySignature {
                         span: DUMMY_SP,
-                        readonly: false,
+                        readonly: prop.readonly,
                         key: Box::from(Expr::from(Ident {
                             span: DUMMY_SP,
                             sym: JsWord::from(prop.name.to_owned()),
@@ -377,15 +1389,24 @@ pub fn build_type(
                 members,
             })
         }
+        Variant::Index(types::IndexType {
+            key,
+            value,
+            readonly,
+        }) => TsType::TsTypeLit(TsTypeLit {
+            span: DUMMY_SP,
+            members: vec![build_index_signature(
+                "key",
+                build_type(key, None, None),
+                build_type(value, None, None),
+                *readonly,
+            )],
+        }),
         Variant::Alias(types::AliasType {
             name, type_params, ..
         }) => TsType::TsTypeRef(TsTypeRef {
             span: DUMMY_SP,
-            type_name: TsEntityName::from(Ident {
-                span: DUMMY_SP,
-                sym: JsWord::from(name.to_owned()),
-                optional: false,
-            }),
+            type_name: build_entity_name(name),
             type_params: type_params.clone().map(|params| TsTypeParamInstantiation {
                 span: DUMMY_SP,
                 params: params
@@ -407,9 +1428,112 @@ pub fn build_type(
         }),
         Variant::Array(t) => TsType::TsArrayType(TsArrayType {
             span: DUMMY_SP,
-            elem_type: Box::from(build_type(t, None, None)),
+            elem_type: Box::from(parenthesize(build_type(t, None, None))),
+        }),
+        Variant::Readonly(t) => TsType::TsTypeOperator(TsTypeOperator {
+            span: DUMMY_SP,
+            op: TsTypeOperatorOp::ReadOnly,
+            type_ann: Box::from(build_type(t, None, None)),
+        }),
+        Variant::Class(types::ClassType {
+            name, type_args, ..
+        }) => TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: build_entity_name(name),
+            type_params: match type_args.is_empty() {
+                true => None,
+                false => Some(TsTypeParamInstantiation {
+                    span: DUMMY_SP,
+                    params: type_args
+                        .iter()
+                        .map(|ty| Box::from(build_type(ty, None, None)))
+                        .collect(),
+                }),
+            },
+        }),
+        Variant::Rest(arg) => TsType::TsRestType(TsRestType {
+            span: DUMMY_SP,
+            type_ann: Box::from(build_type(arg, None, None)),
+        }),
+        Variant::Member(types::MemberType { obj, prop }) => {
+            TsType::TsIndexedAccessType(TsIndexedAccessType {
+                span: DUMMY_SP,
+                readonly: false,
+                obj_type: Box::from(parenthesize(build_type(obj, None, None))),
+                index_type: Box::from(TsType::TsLitType(TsLitType {
+                    span: DUMMY_SP,
+                    lit: TsLit::Str(Str {
+                        span: DUMMY_SP,
+                        value: JsWord::from(prop.to_owned()),
+                        raw: None,
+                    }),
+                })),
+            })
+        }
+        Variant::KeyOf(t) => TsType::TsTypeOperator(TsTypeOperator {
+            span: DUMMY_SP,
+            op: TsTypeOperatorOp::KeyOf,
+            type_ann: Box::from(build_type(t, None, None)),
+        }),
+        Variant::Query(name) => TsType::TsTypeQuery(TsTypeQuery {
+            span: DUMMY_SP,
+            expr_name: TsTypeQueryExpr::TsEntityName(TsEntityName::from(Ident {
+                span: DUMMY_SP,
+                sym: JsWord::from(name.to_owned()),
+                optional: false,
+            })),
+            type_args: None,
+        }),
+        Variant::IndexedAccess(types::IndexedAccessType { obj, index }) => {
+            TsType::TsIndexedAccessType(TsIndexedAccessType {
+                span: DUMMY_SP,
+                readonly: false,
+                obj_type: Box::from(parenthesize(build_type(obj, None, None))),
+                index_type: Box::from(build_type(index, None, None)),
+            })
+        }
+        Variant::Conditional(types::ConditionalType {
+            check,
+            extends,
+            true_type,
+            false_type,
+        }) => TsType::TsConditionalType(TsConditionalType {
+            span: DUMMY_SP,
+            check_type: Box::from(build_type(check, None, None)),
+            extends_type: Box::from(build_type(extends, None, None)),
+            true_type: Box::from(build_type(true_type, None, None)),
+            false_type: Box::from(build_type(false_type, None, None)),
+        }),
+        Variant::Mapped(types::MappedType {
+            key,
+            constraint,
+            value,
+            optional,
+            readonly,
+        }) => TsType::TsMappedType(TsMappedType {
+            span: DUMMY_SP,
+            readonly: readonly.as_ref().map(build_mapped_modifier),
+            type_param: build_ts_type_param(&key.to_string(), Some(build_type(constraint, None, None))),
+            name_type: None,
+            optional: optional.as_ref().map(build_mapped_modifier),
+            type_ann: Some(Box::from(build_type(value, None, None))),
+        }),
+        Variant::Infer(name) => TsType::TsInferType(TsInferType {
+            span: DUMMY_SP,
+            type_param: build_ts_type_param(name, None),
+        }),
+        Variant::Bound(name) => TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: TsEntityName::from(Ident {
+                span: DUMMY_SP,
+                sym: JsWord::from(name.to_owned()),
+                optional: false,
+            }),
+            type_params: None,
         }),
-        Variant::Rest(_) => todo!(),
-        Variant::Member(_) => todo!(),
+        Variant::TemplateLiteral(types::TemplateLiteralType { quasis, types }) => build_tpl_lit_type(
+            quasis.to_owned(),
+            types.iter().map(|t| build_type(t, None, None)).collect(),
+        ),
     }
 }
@@ -3,7 +3,8 @@ use std::rc::Rc;
 use swc_atoms::*;
 use swc_common::comments::SingleThreadedComments;
 use swc_common::hygiene::Mark;
-use swc_common::source_map::{Globals, SourceMap, DUMMY_SP, GLOBALS};
+use swc_common::source_map::{BytePos, Globals, SourceMap, DUMMY_SP, GLOBALS};
+use swc_common::LineCol;
 use swc_ecma_ast::*;
 use swc_ecma_codegen::*;
 use swc_ecma_transforms_react::{react, Options, Runtime};
@@ -11,29 +12,129 @@ use swc_ecma_visit::*;
 
 use crochet_ast::{self as ast, is_refutable};
 
+use crate::source_map::*;
+
+// How JSX is emitted.  `Classic` and `Automatic` lower JSX to function calls
+// the same way as Babel's React preset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsxEmit {
+    // Leaves JSX as is so that it can be lowered by another tool.
+    Preserve,
+    // `<Foo />` becomes `React.createElement(Foo, null)` where `pragma` is
+    // the function to call and `pragma_frag` is used for fragments.
+    Classic { pragma: String, pragma_frag: String },
+    // `<Foo />` becomes `_jsx(Foo, {})` where `_jsx` is imported from
+    // `${import_source}/jsx-runtime`.
+    Automatic { import_source: String },
+}
+
+impl Default for JsxEmit {
+    fn default() -> Self {
+        JsxEmit::Automatic {
+            import_source: String::from("react"),
+        }
+    }
+}
+
+impl JsxEmit {
+    pub fn classic() -> Self {
+        JsxEmit::Classic {
+            pragma: String::from("React.createElement"),
+            pragma_frag: String::from("React.Fragment"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsOptions {
+    pub jsx: JsxEmit,
+}
+
+// The output of `codegen_js_with_source_map`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsOutput {
+    pub js: String,
+    // A v3 source map, this is returned even if it's also inlined in `js`.
+    pub source_map: String,
+}
+
 pub fn codegen_js(program: &ast::Program) -> String {
-    let program = build_js(program);
+    codegen_js_with_options(program, &JsOptions::default())
+}
 
+pub fn codegen_js_with_options(program: &ast::Program, options: &JsOptions) -> String {
     let cm = Rc::new(SourceMap::default());
-    let comments: Option<SingleThreadedComments> = None;
-    let options = Options {
-        runtime: Some(Runtime::Automatic),
-        ..Default::default()
+    let mut program = build_js(program);
+    program.visit_mut_with(&mut SpanFixer::dummy());
+
+    print_js(&transform_jsx(program, cm.clone(), options), cm, None)
+}
+
+// `file` must be the file that `program` was parsed from.
+pub fn codegen_js_with_source_map(
+    program: &ast::Program,
+    file: &ast::SourceFile,
+    options: &JsOptions,
+    emit: &SourceMapEmit,
+) -> JsOutput {
+    let cm = Rc::new(SourceMap::default());
+    let file = add_source_file(&cm, file);
+    let mut program = build_js(program);
+    program.visit_mut_with(&mut SpanFixer::new(&file));
+
+    let mut mappings = vec![];
+    let program = transform_jsx(program, cm.clone(), options);
+    let js = print_js(&program, cm.clone(), Some(&mut mappings));
+    let source_map = build_source_map(&cm, &mut mappings);
+
+    JsOutput {
+        js: format!(
+            "{js}{}\n",
+            source_mapping_url(&source_map, emit)
+        ),
+        source_map,
+    }
+}
+
+fn transform_jsx(program: Program, cm: Rc<SourceMap>, options: &JsOptions) -> Program {
+    let options = match &options.jsx {
+        JsxEmit::Preserve => return program,
+        JsxEmit::Classic {
+            pragma,
+            pragma_frag,
+        } => Options {
+            runtime: Some(Runtime::Classic),
+            pragma: Some(pragma.to_owned()),
+            pragma_frag: Some(pragma_frag.to_owned()),
+            // Spread attributes are emitted as object spreads instead of
+            // using the `_extends` helper.
+            use_spread: Some(true),
+            ..Default::default()
+        },
+        JsxEmit::Automatic { import_source } => Options {
+            runtime: Some(Runtime::Automatic),
+            import_source: Some(import_source.to_owned()),
+            ..Default::default()
+        },
     };
 
+    let comments: Option<SingleThreadedComments> = None;
+
     let globals = Globals::default();
     // The call to Mark::new() must be wrapped in a GLOBALS.set() closure
     GLOBALS.set(&globals, || {
         let top_level_mark = Mark::new();
         let mut v = react(cm, comments, options, top_level_mark);
-        let program = program.fold_with(&mut v);
-        print_js(&program)
+        program.fold_with(&mut v)
     })
 }
 
-fn print_js(program: &Program) -> String {
+fn print_js(
+    program: &Program,
+    cm: Rc<SourceMap>,
+    mappings: Option<&mut Vec<(BytePos, LineCol)>>,
+) -> String {
     let mut buf = vec![];
-    let cm = Rc::new(SourceMap::default());
 
     let mut emitter = Emitter {
         cfg: swc_ecma_codegen::Config {
@@ -41,7 +142,7 @@ fn print_js(program: &Program) -> String {
         },
         cm: cm.clone(),
         comments: None,
-        wr: text_writer::JsWriter::new(cm, "\n", &mut buf, None),
+        wr: text_writer::JsWriter::new(cm, "\n", &mut buf, mappings),
     };
 
     emitter.emit_program(program).unwrap();
@@ -55,10 +156,10 @@ fn build_js(program: &ast::Program) -> Program {
         .iter()
         .map(|child| match child {
             ast::Statement::VarDecl {
+                span,
                 pattern,
                 init,
                 declare,
-                ..
             } => match declare {
                 true => ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP })),
                 false => {
@@ -66,13 +167,13 @@ fn build_js(program: &ast::Program) -> Program {
                     // using `declare` should have an initial value.
                     let init = init.as_ref().unwrap();
                     ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
-                        span: DUMMY_SP,
+                        span: build_span(span),
                         decl: Decl::Var(VarDecl {
-                            span: DUMMY_SP,
+                            span: build_span(span),
                             kind: VarDeclKind::Const,
                             declare: false,
                             decls: vec![VarDeclarator {
-                                span: DUMMY_SP,
+                                span: build_span(span),
                                 name: build_pattern(pattern),
                                 init: Some(Box::from(build_expr(init))),
                                 definite: false,
@@ -81,11 +182,39 @@ fn build_js(program: &ast::Program) -> Program {
                     }))
                 }
             },
-            ast::Statement::TypeDecl { .. } => {
+            ast::Statement::TypeDecl { .. }
+            | ast::Statement::InterfaceDecl { .. }
+            | ast::Statement::NamespaceDecl { .. } => {
                 ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }))
             }
-            ast::Statement::Expr { expr, .. } => ModuleItem::Stmt(Stmt::Expr(ExprStmt {
-                span: DUMMY_SP,
+            ast::Statement::EnumDecl {
+                span, id, variants, ..
+            } => {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    span: build_span(span),
+                    decl: Decl::Var(VarDecl {
+                        span: build_span(span),
+                        kind: VarDeclKind::Const,
+                        declare: false,
+                        decls: vec![VarDeclarator {
+                            span: build_span(span),
+                            name: Pat::Ident(BindingIdent::from(build_ident(id))),
+                            init: Some(Box::from(build_expr(&enum_ctors(span, variants)))),
+                            definite: false,
+                        }],
+                    }),
+                }))
+            }
+            ast::Statement::ClassDecl { span, id, members } => ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: build_span(span),
+                decl: Decl::Class(ClassDecl {
+                    ident: build_ident(id),
+                    declare: false,
+                    class: build_class(span, members),
+                }),
+            })),
+            ast::Statement::Expr { span, expr } => ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+                span: build_span(span),
                 expr: Box::from(build_expr(expr)),
             })),
         })
@@ -98,19 +227,162 @@ fn build_js(program: &ast::Program) -> Program {
     })
 }
 
+// Desugars the variants of an enum into an object containing a constructor
+// for each variant, e.g. `enum Option<T> { Some(value: T), None }` becomes
+// `{Some: (value) => ({kind: "Some", value}), None: {kind: "None"}}`.
+fn enum_ctors(span: &ast::Span, variants: &[ast::EnumVariant]) -> ast::Expr {
+    let props = variants
+        .iter()
+        .map(|variant| {
+            let span = variant.span.to_owned();
+            let fields = variant.fields.clone().unwrap_or_default();
+
+            let mut props = vec![ast::PropOrSpread::Prop(Box::from(ast::Prop::KeyValue(
+                ast::KeyValueProp {
+                    span: span.clone(),
+                    name: String::from("kind"),
+                    value: ast::Expr::Lit(ast::Lit::str(variant.name.name.to_owned(), span.clone())),
+                },
+            )))];
+            for field in &fields {
+                props.push(ast::PropOrSpread::Prop(Box::from(ast::Prop::Shorthand(
+                    ast::Ident {
+                        span: field.span.to_owned(),
+                        name: field.name.to_owned(),
+                    },
+                ))));
+            }
+            let obj = ast::Expr::Obj(ast::Obj {
+                span: span.clone(),
+                props,
+            });
+
+            let value = match &variant.fields {
+                Some(_) => ast::Expr::Lambda(ast::Lambda {
+                    span: span.clone(),
+                    params: fields
+                        .iter()
+                        .map(|field| {
+                            ast::Pattern::Ident(ast::BindingIdent {
+                                span: field.span.to_owned(),
+                                id: ast::Ident {
+                                    span: field.span.to_owned(),
+                                    name: field.name.to_owned(),
+                                },
+                                type_ann: None,
+                            })
+                        })
+                        .collect(),
+                    body: Box::from(obj),
+                    is_async: false,
+                    return_type: None,
+                    type_params: None,
+                }),
+                None => obj,
+            };
+
+            ast::PropOrSpread::Prop(Box::from(ast::Prop::KeyValue(ast::KeyValueProp {
+                span,
+                name: variant.name.name.to_owned(),
+                value,
+            })))
+        })
+        .collect();
+
+    ast::Expr::Obj(ast::Obj {
+        span: span.to_owned(),
+        props,
+    })
+}
+
+fn build_class(span: &ast::Span, members: &[ast::ClassMember]) -> Class {
+    let body: Vec<ClassMember> = members
+        .iter()
+        .filter_map(|member| match member {
+            ast::ClassMember::Constructor(ast::Constructor { span, params, body }) => {
+                let mut block = build_return_block(body);
+                // Constructors don't return anything so the value of the
+                // last expression in the body is used as a statement instead.
+                if let Some(Stmt::Return(ReturnStmt { arg: Some(arg), .. })) = block.stmts.pop() {
+                    if !matches!(arg.as_ref(), Expr::Ident(Ident { sym, .. }) if sym == "undefined") {
+                        block.stmts.push(Stmt::Expr(ExprStmt {
+                            span: DUMMY_SP,
+                            expr: arg,
+                        }));
+                    }
+                }
+
+                Some(ClassMember::Constructor(Constructor {
+                    span: build_span(span),
+                    key: PropName::Ident(Ident {
+                        span: DUMMY_SP,
+                        sym: JsWord::from("constructor"),
+                        optional: false,
+                    }),
+                    params: params
+                        .iter()
+                        .map(|param| ParamOrTsParamProp::Param(Param::from(build_pattern(param))))
+                        .collect(),
+                    body: Some(block),
+                    accessibility: None,
+                    is_optional: false,
+                }))
+            }
+            ast::ClassMember::Method(ast::ClassMethod { span, key, lambda }) => {
+                Some(ClassMember::Method(ClassMethod {
+                    span: build_span(span),
+                    key: PropName::Ident(build_ident(key)),
+                    function: Function {
+                        params: lambda
+                            .params
+                            .iter()
+                            .map(|param| Param::from(build_pattern(param)))
+                            .collect(),
+                        decorators: vec![],
+                        span: build_span(&lambda.span),
+                        body: Some(build_return_block(&lambda.body)),
+                        is_generator: false,
+                        is_async: lambda.is_async,
+                        type_params: None,
+                        return_type: None,
+                    },
+                    kind: MethodKind::Method,
+                    is_static: false,
+                    accessibility: None,
+                    is_abstract: false,
+                    is_optional: false,
+                    is_override: false,
+                }))
+            }
+            // Fields are initialized in the constructor.
+            ast::ClassMember::Prop(_) => None,
+        })
+        .collect();
+
+    Class {
+        span: build_span(span),
+        decorators: vec![],
+        body,
+        super_class: None,
+        is_abstract: false,
+        type_params: None,
+        super_type_params: None,
+        implements: vec![],
+    }
+}
+
 pub fn build_pattern(pattern: &ast::Pattern) -> Pat {
     match pattern {
         ast::Pattern::Ident(ast::BindingIdent { id, .. }) => Pat::Ident(BindingIdent {
-            id: Ident {
-                span: DUMMY_SP,
-                sym: JsWord::from(id.name.to_owned()),
-                optional: false,
-            },
+            id: build_ident(id),
             type_ann: None,
         }),
         ast::Pattern::Rest(_) => todo!(),
         ast::Pattern::Object(ast::ObjectPat {
-            props, optional, ..
+            span,
+            props,
+            optional,
+            ..
         }) => {
             let props: Vec<ObjectPatProp> = props
                 .iter()
@@ -119,13 +391,13 @@ pub fn build_pattern(pattern: &ast::Pattern) -> Pat {
                         // Prevents literals from appearing in patterns during destructuring.
                         ast::Pattern::Lit(_) => None,
                         _ => Some(ObjectPatProp::KeyValue(KeyValuePatProp {
-                            key: PropName::Ident(Ident::from(&kvp.key)),
+                            key: PropName::Ident(build_ident(&kvp.key)),
                             value: Box::from(build_pattern(kvp.value.as_ref())),
                         })),
                     },
                     ast::ObjectPatProp::Assign(ap) => Some(ObjectPatProp::Assign(AssignPatProp {
-                        span: DUMMY_SP,
-                        key: Ident::from(&ap.key),
+                        span: build_span(&ap.span),
+                        key: build_ident(&ap.key),
                         value: ap
                             .value
                             .clone()
@@ -136,14 +408,17 @@ pub fn build_pattern(pattern: &ast::Pattern) -> Pat {
                 .collect();
 
             Pat::Object(ObjectPat {
-                span: DUMMY_SP,
+                span: build_span(span),
                 optional: optional.to_owned(),
                 type_ann: None, // because we're generating .js
                 props,
             })
         }
         ast::Pattern::Array(ast::ArrayPat {
-            elems, optional, ..
+            span,
+            elems,
+            optional,
+            ..
         }) => {
             let elems: Vec<Option<Pat>> = elems
                 .iter()
@@ -160,7 +435,7 @@ pub fn build_pattern(pattern: &ast::Pattern) -> Pat {
             // TODO: If all elems are None, we can drop the array pattern.
 
             Pat::Array(ArrayPat {
-                span: DUMMY_SP,
+                span: build_span(span),
                 elems,
                 optional: optional.to_owned(),
                 type_ann: None, // because we're generating .js.
@@ -168,11 +443,7 @@ pub fn build_pattern(pattern: &ast::Pattern) -> Pat {
         }
         ast::Pattern::Lit(_) => todo!(),
         ast::Pattern::Is(ast::IsPat { id, .. }) => Pat::Ident(BindingIdent {
-            id: Ident {
-                span: DUMMY_SP,
-                sym: JsWord::from(id.name.to_owned()),
-                optional: false,
-            },
+            id: build_ident(id),
             type_ann: None,
         }),
     }
@@ -197,7 +468,7 @@ pub fn build_return_block(body: &ast::Expr) -> BlockStmt {
 
 pub fn build_expr(expr: &ast::Expr) -> Expr {
     match expr {
-        ast::Expr::App(ast::App { lam, args, .. }) => {
+        ast::Expr::App(ast::App { span, lam, args }) => {
             let callee = Callee::Expr(Box::from(build_expr(lam.as_ref())));
             let args: Vec<ExprOrSpread> = args
                 .iter()
@@ -208,18 +479,37 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
                 .collect();
 
             Expr::Call(CallExpr {
-                span: DUMMY_SP,
+                span: build_span(span),
                 callee,
                 args,
                 type_args: None,
             })
         }
-        ast::Expr::Ident(ident) => Expr::from(Ident {
-            span: DUMMY_SP,
-            sym: JsWord::from(ident.name.to_owned()),
-            optional: false,
+        ast::Expr::New(ast::New { span, expr, args }) => {
+            let args: Vec<ExprOrSpread> = args
+                .iter()
+                .map(|arg| ExprOrSpread {
+                    spread: None,
+                    expr: Box::from(build_expr(arg.expr.as_ref())),
+                })
+                .collect();
+
+            Expr::New(NewExpr {
+                span: build_span(span),
+                callee: Box::from(build_expr(expr.as_ref())),
+                args: Some(args),
+                type_args: None,
+            })
+        }
+        ast::Expr::Assign(ast::Assign { span, left, right }) => Expr::Assign(AssignExpr {
+            span: build_span(span),
+            op: AssignOp::Assign,
+            left: PatOrExpr::Expr(Box::from(build_expr(left.as_ref()))),
+            right: Box::from(build_expr(right.as_ref())),
         }),
+        ast::Expr::Ident(ident) => Expr::from(build_ident(ident)),
         ast::Expr::Lambda(ast::Lambda {
+            span,
             params: args,
             body,
             is_async,
@@ -228,8 +518,8 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
             let params: Vec<Pat> = args
                 .iter()
                 .map(|pat| {
-                    let name = match pat {
-                        ast::Pattern::Ident(ast::BindingIdent { id, .. }) => id.name.to_owned(),
+                    let id = match pat {
+                        ast::Pattern::Ident(ast::BindingIdent { id, .. }) => id,
                         ast::Pattern::Rest(_) => todo!(),
                         ast::Pattern::Object(_) => todo!(),
                         ast::Pattern::Array(_) => todo!(),
@@ -237,27 +527,30 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
                         ast::Pattern::Is(_) => todo!(),
                     };
                     Pat::Ident(BindingIdent {
-                        id: Ident {
-                            span: DUMMY_SP,
-                            sym: JsWord::from(name),
-                            optional: false,
-                        },
+                        id: build_ident(id),
                         type_ann: None,
                     })
                 })
                 .collect();
 
-            let body: BlockStmtOrExpr = match &body.as_ref() {
+            let body: BlockStmtOrExpr = match strip_type_assertions(body) {
                 // TODO: Avoid wrapping in an IIFE when it isn't necessary.
                 ast::Expr::Let(r#let) => BlockStmtOrExpr::BlockStmt(BlockStmt {
                     span: DUMMY_SP,
                     stmts: let_to_children(r#let),
                 }),
+                // Object literals must be wrapped in parens otherwise they'll be
+                // parsed as a block.  This includes object literals in type
+                // assertions since those are erased.
+                ast::Expr::Obj(_) => BlockStmtOrExpr::Expr(Box::from(Expr::Paren(ParenExpr {
+                    span: DUMMY_SP,
+                    expr: Box::from(build_expr(body)),
+                }))),
                 _ => BlockStmtOrExpr::Expr(Box::from(build_expr(body))),
             };
 
             Expr::Arrow(ArrowExpr {
-                span: DUMMY_SP,
+                span: build_span(span),
                 params,
                 body,
                 is_async: is_async.to_owned(),
@@ -293,9 +586,41 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
                 type_args: None,
             })
         }
-        ast::Expr::Lit(lit) => Expr::from(lit),
+        ast::Expr::Lit(lit) => match lit {
+            // `undefined` is an identifier in JS rather than a literal.
+            ast::Lit::Undefined(_) => Expr::from(lit),
+            _ => Expr::Lit(build_lit(lit)),
+        },
+        ast::Expr::Unary(ast::Unary { span, op, arg }) => {
+            let op = match op {
+                ast::UnaryOp::Not => UnaryOp::Bang,
+                ast::UnaryOp::Typeof => UnaryOp::TypeOf,
+            };
+
+            let arg = Box::from(build_expr(arg));
+
+            let wrap_arg = matches!(arg.as_ref(), Expr::Bin(_));
+
+            Expr::Unary(UnaryExpr {
+                span: build_span(span),
+                op,
+                arg: match wrap_arg {
+                    true => Box::from(Expr::Paren(ParenExpr {
+                        span: DUMMY_SP,
+                        expr: arg,
+                    })),
+                    false => arg,
+                },
+            })
+        }
+        // Type assertions only affect type checking so they're stripped.
+        ast::Expr::As(ast::As { expr, .. })
+        | ast::Expr::Satisfies(ast::Satisfies { expr, .. }) => build_expr(expr),
         ast::Expr::Op(ast::Op {
-            op, left, right, ..
+            span,
+            op,
+            left,
+            right,
         }) => {
             let op = match op {
                 ast::BinOp::Add => BinaryOp::Add,
@@ -329,7 +654,7 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
             };
 
             Expr::Bin(BinExpr {
-                span: DUMMY_SP,
+                span: build_span(span),
                 op,
                 left: if wrap_left {
                     Box::from(Expr::Paren(ParenExpr {
@@ -405,7 +730,9 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
                                 span: DUMMY_SP,
                                 test: Box::from(cond),
                                 cons: Box::from(Stmt::Block(cons)),
-                                alt: None, // TODO: handle chaining of if-let with else
+                                alt: alternate.as_ref().map(|alternate| {
+                                    Box::from(Stmt::Block(build_return_block(alternate)))
+                                }),
                             }),
                         ];
 
@@ -451,22 +778,18 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
                 }
             }
         }
-        ast::Expr::Obj(ast::Obj { props, .. }) => {
+        ast::Expr::Obj(ast::Obj { span, props }) => {
             let props: Vec<PropOrSpread> = props
                 .iter()
                 .map(|prop| match prop {
                     ast::PropOrSpread::Prop(prop) => match prop.as_ref() {
-                        ast::Prop::Shorthand(ast::ident::Ident { name, .. }) => {
-                            PropOrSpread::Prop(Box::from(Prop::Shorthand(Ident {
-                                span: DUMMY_SP,
-                                sym: JsWord::from(name.clone()),
-                                optional: false,
-                            })))
+                        ast::Prop::Shorthand(ident) => {
+                            PropOrSpread::Prop(Box::from(Prop::Shorthand(build_ident(ident))))
                         }
-                        ast::Prop::KeyValue(ast::KeyValueProp { name, value, .. }) => {
+                        ast::Prop::KeyValue(ast::KeyValueProp { span, name, value }) => {
                             PropOrSpread::Prop(Box::from(Prop::KeyValue(KeyValueProp {
                                 key: PropName::from(Ident {
-                                    span: DUMMY_SP,
+                                    span: build_span(span),
                                     sym: JsWord::from(name.clone()),
                                     optional: false,
                                 }),
@@ -479,17 +802,18 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
                 .collect();
 
             Expr::Object(ObjectLit {
-                span: DUMMY_SP,
+                span: build_span(span),
                 props,
             })
         }
-        ast::Expr::Await(ast::Await { expr, .. }) => Expr::Await(AwaitExpr {
-            span: DUMMY_SP,
+        ast::Expr::Await(ast::Await { span, expr }) => Expr::Await(AwaitExpr {
+            span: build_span(span),
             arg: Box::from(build_expr(expr.as_ref())),
         }),
         ast::Expr::JSXElement(elem) => Expr::JSXElement(Box::from(build_jsx_element(elem))),
-        ast::Expr::Tuple(ast::Tuple { elems, .. }) => Expr::Array(ArrayLit {
-            span: DUMMY_SP,
+        ast::Expr::JSXFragment(frag) => Expr::JSXFragment(build_jsx_fragment(frag)),
+        ast::Expr::Tuple(ast::Tuple { span, elems }) => Expr::Array(ArrayLit {
+            span: build_span(span),
             elems: elems
                 .iter()
                 .map(|ast::ExprOrSpread { spread, expr }| {
@@ -500,65 +824,86 @@ pub fn build_expr(expr: &ast::Expr) -> Expr {
                 })
                 .collect(),
         }),
-        ast::Expr::Member(ast::Member { obj, prop, .. }) => {
+        ast::Expr::Member(ast::Member { span, obj, prop }) => {
             let prop = match prop {
-                ast::MemberProp::Ident(ident) => MemberProp::Ident(Ident {
-                    span: DUMMY_SP,
-                    sym: JsWord::from(ident.name.to_owned()),
-                    optional: false,
-                }),
-                ast::MemberProp::Computed(ast::ComputedPropName { expr, .. }) => {
+                ast::MemberProp::Ident(ident) => MemberProp::Ident(build_ident(ident)),
+                ast::MemberProp::Computed(ast::ComputedPropName { span, expr }) => {
                     MemberProp::Computed(ComputedPropName {
-                        span: DUMMY_SP,
+                        span: build_span(span),
                         expr: Box::from(build_expr(expr)),
                     })
                 }
             };
             Expr::Member(MemberExpr {
-                span: DUMMY_SP,
+                span: build_span(span),
                 obj: Box::from(build_expr(obj)),
                 prop,
             })
         }
-        ast::Expr::Empty(_) => Expr::from(Ident {
-            span: DUMMY_SP,
+        ast::Expr::Empty(ast::Empty { span }) => Expr::from(Ident {
+            span: build_span(span),
             sym: JsWord::from("undefined"),
             optional: false,
         }),
         ast::Expr::LetExpr(_) => {
             panic!("LetExpr should always be handled by the IfElse branch")
         }
-        ast::Expr::TemplateLiteral(ast::TemplateLiteral { exprs, quasis, .. }) => {
-            Expr::Tpl(Tpl {
-                span: DUMMY_SP,
-                exprs: exprs
-                    .iter()
-                    .map(|expr| Box::from(build_expr(expr)))
-                    .collect(),
-                quasis: quasis
-                    .iter()
-                    .map(|quasi| {
-                        let cooked = match &quasi.cooked {
-                            ast::Lit::Str(ast::Str { value, .. }) => value,
-                            _ => panic!("quasi.cooked must be a string"),
-                        };
-                        let raw = match &quasi.raw {
-                            ast::Lit::Str(ast::Str { value, .. }) => value,
-                            _ => panic!("quasi.raw must be a string"),
-                        };
-                        TplElement {
-                            span: DUMMY_SP,
-                            cooked: Some(JsWord::from(cooked.to_owned())),
-                            raw: JsWord::from(raw.to_owned()),
-                            tail: false, // TODO: set this to `true` if it's the last quasi
-                        }
-                    })
-                    .collect(),
+        ast::Expr::TemplateLiteral(template) => Expr::Tpl(build_template_literal(template)),
+        ast::Expr::TaggedTemplateLiteral(ast::TaggedTemplateLiteral {
+            span,
+            tag,
+            template,
+        }) => Expr::TaggedTpl(TaggedTpl {
+            span: build_span(span),
+            tag: Box::from(build_expr(tag)),
+            type_params: None,
+            tpl: build_template_literal(template),
+        }),
+    }
+}
+
+// Returns the expression inside of any `as` and `satisfies` type assertions
+// since they're erased when generating JavaScript.
+fn strip_type_assertions(expr: &ast::Expr) -> &ast::Expr {
+    match expr {
+        ast::Expr::As(ast::As { expr, .. })
+        | ast::Expr::Satisfies(ast::Satisfies { expr, .. }) => strip_type_assertions(expr),
+        _ => expr,
+    }
+}
+
+fn build_template_literal(template: &ast::TemplateLiteral) -> Tpl {
+    let ast::TemplateLiteral {
+        span,
+        exprs,
+        quasis,
+    } = template;
+    Tpl {
+        span: build_span(span),
+        exprs: exprs
+            .iter()
+            .map(|expr| Box::from(build_expr(expr)))
+            .collect(),
+        quasis: quasis
+            .iter()
+            .enumerate()
+            .map(|(i, quasi)| {
+                let cooked = match &quasi.cooked {
+                    ast::Lit::Str(ast::Str { value, .. }) => value,
+                    _ => panic!("quasi.cooked must be a string"),
+                };
+                let raw = match &quasi.raw {
+                    ast::Lit::Str(ast::Str { value, .. }) => value,
+                    _ => panic!("quasi.raw must be a string"),
+                };
+                TplElement {
+                    span: build_span(&quasi.span),
+                    cooked: Some(JsWord::from(cooked.to_owned())),
+                    raw: JsWord::from(raw.to_owned()),
+                    tail: i == quasis.len() - 1,
+                }
             })
-        }
-        ast::Expr::TaggedTemplateLiteral(_) => {
-            todo!()
-        }
+            .collect(),
     }
 }
 
@@ -587,98 +932,163 @@ fn build_iife(body: BlockStmtOrExpr) -> Expr {
 }
 
 pub fn build_jsx_element(elem: &ast::JSXElement) -> JSXElement {
-    let name = JSXElementName::Ident(Ident {
-        span: DUMMY_SP,
-        sym: JsWord::from(elem.name.to_owned()),
-        optional: false,
-    });
+    let name = build_jsx_element_name(&elem.name);
+    let self_closing = elem.children.is_empty();
 
     let elem = JSXElement {
-        span: DUMMY_SP,
+        span: build_span(&elem.span),
         opening: JSXOpeningElement {
-            span: DUMMY_SP,
+            span: build_span(&elem.span),
             name: name.to_owned(),
             attrs: elem
                 .attrs
                 .iter()
-                .map(|ast::JSXAttr { value, ident, .. }| {
-                    let value = Some(match value {
-                        ast::JSXAttrValue::Lit(lit) => JSXAttrValue::Lit(build_lit(lit)),
-                        ast::JSXAttrValue::JSXExprContainer(ast::JSXExprContainer {
-                            expr, ..
-                        }) => JSXAttrValue::JSXExprContainer(JSXExprContainer {
-                            span: DUMMY_SP,
-                            expr: JSXExpr::Expr(Box::from(build_expr(expr))),
-                        }),
-                    });
+                .map(|attr| match attr {
+                    ast::JSXAttrOrSpread::JSXAttr(ast::JSXAttr { span, ident, value }) => {
+                        // Boolean attributes, e.g. `<input disabled />`, have no value.
+                        let value = value.as_ref().map(|value| match value {
+                            ast::JSXAttrValue::Lit(lit) => JSXAttrValue::Lit(build_lit(lit)),
+                            ast::JSXAttrValue::JSXExprContainer(ast::JSXExprContainer {
+                                span,
+                                expr,
+                            }) => JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                                span: build_span(span),
+                                expr: JSXExpr::Expr(Box::from(build_expr(expr))),
+                            }),
+                        });
 
-                    JSXAttrOrSpread::JSXAttr(JSXAttr {
-                        span: DUMMY_SP,
-                        name: JSXAttrName::Ident(Ident {
-                            span: DUMMY_SP,
-                            sym: JsWord::from(ident.name.to_owned()),
-                            optional: false,
-                        }),
-                        value,
-                    })
+                        JSXAttrOrSpread::JSXAttr(JSXAttr {
+                            span: build_span(span),
+                            name: JSXAttrName::Ident(build_ident(ident)),
+                            value,
+                        })
+                    }
+                    ast::JSXAttrOrSpread::JSXSpreadAttr(ast::JSXSpreadAttr { span, expr }) => {
+                        JSXAttrOrSpread::SpreadElement(SpreadElement {
+                            dot3_token: build_span(span),
+                            expr: Box::from(build_expr(expr)),
+                        })
+                    }
                 })
                 .collect(),
-            self_closing: false,
+            self_closing,
             type_args: None,
         },
-        children: elem
-            .children
-            .iter()
-            .map(|child| {
-                let result: JSXElementChild = match child {
-                    ast::JSXElementChild::JSXText(ast::JSXText { value, .. }) => {
-                        JSXElementChild::JSXText(JSXText {
-                            span: DUMMY_SP,
-                            value: JsWord::from(value.to_owned()),
-                            raw: JsWord::from(value.to_owned()),
-                        })
-                    }
-                    ast::JSXElementChild::JSXExprContainer(ast::JSXExprContainer {
-                        expr, ..
-                    }) => JSXElementChild::JSXExprContainer(JSXExprContainer {
-                        span: DUMMY_SP,
-                        expr: JSXExpr::Expr(Box::from(build_expr(expr))),
-                    }),
-                    ast::JSXElementChild::JSXElement(elem) => {
-                        JSXElementChild::JSXElement(Box::from(build_jsx_element(elem)))
-                    }
-                };
-                result
-            })
-            .collect(),
-        closing: Some(JSXClosingElement {
-            span: DUMMY_SP,
-            name,
-        }),
+        children: build_jsx_children(&elem.children),
+        closing: match self_closing {
+            true => None,
+            false => Some(JSXClosingElement {
+                span: DUMMY_SP,
+                name,
+            }),
+        },
     };
 
     elem
 }
 
+fn build_jsx_fragment(frag: &ast::JSXFragment) -> JSXFragment {
+    JSXFragment {
+        span: build_span(&frag.span),
+        opening: JSXOpeningFragment { span: DUMMY_SP },
+        children: build_jsx_children(&frag.children),
+        closing: JSXClosingFragment { span: DUMMY_SP },
+    }
+}
+
+fn build_jsx_element_name(name: &ast::JSXElementName) -> JSXElementName {
+    match name {
+        ast::JSXElementName::Ident(ident) => JSXElementName::Ident(build_ident(ident)),
+        ast::JSXElementName::Member(member) => {
+            JSXElementName::JSXMemberExpr(build_jsx_member_expr(member))
+        }
+    }
+}
+
+fn build_jsx_member_expr(member: &ast::JSXMemberExpr) -> JSXMemberExpr {
+    let ast::JSXMemberExpr { obj, prop, .. } = member;
+    let obj = match obj.as_ref() {
+        ast::JSXElementName::Ident(ident) => JSXObject::Ident(build_ident(ident)),
+        ast::JSXElementName::Member(member) => {
+            JSXObject::JSXMemberExpr(Box::from(build_jsx_member_expr(member)))
+        }
+    };
+
+    JSXMemberExpr {
+        obj,
+        prop: build_ident(prop),
+    }
+}
+
+fn build_jsx_children(children: &[ast::JSXElementChild]) -> Vec<JSXElementChild> {
+    children
+        .iter()
+        .map(|child| {
+            let result: JSXElementChild = match child {
+                ast::JSXElementChild::JSXText(ast::JSXText { span, value }) => {
+                    JSXElementChild::JSXText(JSXText {
+                        span: build_span(span),
+                        value: JsWord::from(value.to_owned()),
+                        raw: JsWord::from(value.to_owned()),
+                    })
+                }
+                ast::JSXElementChild::JSXExprContainer(ast::JSXExprContainer { span, expr }) => {
+                    JSXElementChild::JSXExprContainer(JSXExprContainer {
+                        span: build_span(span),
+                        expr: JSXExpr::Expr(Box::from(build_expr(expr))),
+                    })
+                }
+                // The automatic JSX runtime doesn't support spread children,
+                // but React flattens nested arrays of children so we can pass
+                // the array through as is.
+                ast::JSXElementChild::JSXSpreadChild(ast::JSXSpreadChild { span, expr }) => {
+                    JSXElementChild::JSXExprContainer(JSXExprContainer {
+                        span: build_span(span),
+                        expr: JSXExpr::Expr(Box::from(build_expr(expr))),
+                    })
+                }
+                ast::JSXElementChild::JSXElement(elem) => {
+                    JSXElementChild::JSXElement(Box::from(build_jsx_element(elem)))
+                }
+                ast::JSXElementChild::JSXFragment(frag) => {
+                    JSXElementChild::JSXFragment(build_jsx_fragment(frag))
+                }
+            };
+            result
+        })
+        .collect()
+}
+
+fn build_ident(ident: &ast::Ident) -> Ident {
+    Ident {
+        span: build_span(&ident.span),
+        sym: JsWord::from(ident.name.to_owned()),
+        optional: false,
+    }
+}
+
 pub fn build_lit(lit: &ast::Lit) -> Lit {
     match lit {
         ast::Lit::Num(n) => Lit::Num(Number {
-            span: DUMMY_SP,
+            span: build_span(&n.span),
             value: n.value.parse().unwrap(),
-            raw: None,
+            // Keeps numbers as they were written, e.g. `1e3`.
+            raw: Some(JsWord::from(n.value.to_owned())),
         }),
         ast::Lit::Bool(b) => Lit::Bool(Bool {
-            span: DUMMY_SP,
+            span: build_span(&b.span),
             value: b.value,
         }),
         ast::Lit::Str(s) => Lit::Str(Str {
-            span: DUMMY_SP,
+            span: build_span(&s.span),
             value: JsWord::from(s.value.to_owned()),
             raw: None,
             // Some would include the quotes around the string
             // Some(JsWord::from(s.value.to_owned())),
         }),
-        ast::Lit::Null(_) => Lit::Null(Null { span: DUMMY_SP }),
+        ast::Lit::Null(n) => Lit::Null(Null {
+            span: build_span(&n.span),
+        }),
         ast::Lit::Undefined(_) => todo!(),
     }
 }
@@ -1,5 +1,10 @@
 pub mod d_ts;
 pub mod js;
+pub mod source_map;
 
+// Both modules export a `build_pattern`, callers must use `d_ts::build_pattern`
+// or `js::build_pattern`.
+#[allow(ambiguous_glob_reexports)]
 pub use d_ts::*;
 pub use js::*;
+pub use source_map::SourceMapEmit;
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use swc_common::source_map::{
+    BytePos, FileName, SourceFile, SourceMap, SourceMapGenConfig, Span, SyntaxContext, DUMMY_SP,
+};
+use swc_common::sync::Lrc;
+use swc_common::LineCol;
+use swc_ecma_visit::VisitMut;
+
+use crochet_ast as ast;
+
+// Where the source map for generated code ends up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceMapEmit {
+    // Appends the source map to the generated code as a base64 data URL.
+    Inline,
+    // Links to `map_file` with a `sourceMappingURL` comment.  It's up to the
+    // caller to write the source map to that file.
+    File { map_file: String },
+}
+
+// swc's `BytePos`s are offsets into a `SourceMap` which can contain several
+// files.  Builders don't know about the `SourceMap` so they store offsets into
+// the .crochet file, shifted by one since `BytePos(0)` is reserved for
+// `DUMMY_SP`, and `SpanFixer` moves them into place once the program is built.
+pub fn build_span(span: &ast::Span) -> Span {
+    Span::new(
+        BytePos(span.start + 1),
+        BytePos(span.end + 1),
+        SyntaxContext::empty(),
+    )
+}
+
+// Adds `file` to `cm` so that spans can be mapped back to it.
+pub fn add_source_file(cm: &SourceMap, file: &ast::SourceFile) -> Lrc<SourceFile> {
+    cm.new_source_file(
+        FileName::Real(PathBuf::from(file.name())),
+        file.src().to_owned(),
+    )
+}
+
+pub struct SpanFixer {
+    // `None` if there's no file to point into.
+    start_pos: Option<BytePos>,
+}
+
+impl SpanFixer {
+    // Moves spans created by `build_span` to their positions within `file`.
+    pub fn new(file: &SourceFile) -> Self {
+        SpanFixer {
+            start_pos: Some(file.start_pos),
+        }
+    }
+
+    // Replaces spans created by `build_span` with `DUMMY_SP` for when there's
+    // no source to point into.
+    pub fn dummy() -> Self {
+        SpanFixer { start_pos: None }
+    }
+}
+
+impl VisitMut for SpanFixer {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        if span.is_dummy() {
+            return;
+        }
+
+        *span = match self.start_pos {
+            Some(start_pos) => Span::new(
+                start_pos + span.lo - BytePos(1),
+                start_pos + span.hi - BytePos(1),
+                span.ctxt,
+            ),
+            None => DUMMY_SP,
+        };
+    }
+}
+
+struct SourceMapConfig;
+
+impl SourceMapGenConfig for SourceMapConfig {
+    fn file_name_to_source(&self, f: &FileName) -> String {
+        f.to_string()
+    }
+
+    // Including the source lets the map be used without access to the
+    // original .crochet file, e.g. in the browser.
+    fn inline_sources_content(&self, _f: &FileName) -> bool {
+        true
+    }
+}
+
+// Builds a v3 source map from the mappings collected while printing.
+pub fn build_source_map(cm: &SourceMap, mappings: &mut Vec<(BytePos, LineCol)>) -> String {
+    let source_map = cm.build_source_map_with_config(mappings, None, SourceMapConfig);
+
+    let mut buf = vec![];
+    source_map.to_writer(&mut buf).unwrap();
+
+    String::from_utf8_lossy(&buf).to_string()
+}
+
+// Returns the comment that links generated code to its source map.
+pub fn source_mapping_url(source_map: &str, emit: &SourceMapEmit) -> String {
+    match emit {
+        SourceMapEmit::Inline => format!(
+            "//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}",
+            base64::encode(source_map)
+        ),
+        SourceMapEmit::File { map_file } => format!("//# sourceMappingURL={map_file}"),
+    }
+}
@@ -1,5 +1,5 @@
-use std::cell::Cell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 
 use crochet_ast::literal::Lit as AstLit;
 
@@ -13,6 +13,10 @@ pub type Env = HashMap<String, Scheme>;
 #[derive(Clone, Debug)]
 pub struct State {
     pub count: Cell<i32>,
+    // Pairs of types that `unify` is in the middle of checking.  Recursive
+    // aliases are checked coinductively: if we encounter the same pair again
+    // while expanding aliases we assume that it holds.
+    pub assumptions: RefCell<HashSet<(Type, Type)>>,
 }
 
 #[derive(Clone, Debug)]
@@ -30,6 +34,7 @@ impl Default for Context {
             types: HashMap::new(),
             state: State {
                 count: Cell::from(0),
+                assumptions: RefCell::from(HashSet::new()),
             },
             is_async: false,
         }
@@ -146,10 +151,23 @@ impl Context {
         TProp {
             name: name.to_owned(),
             optional,
+            readonly: false,
             ty,
         }
     }
 
+    pub fn index(&self, key: Type, value: Type, readonly: bool) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::Index(IndexType {
+                key: Box::from(key),
+                value: Box::from(value),
+                readonly,
+            }),
+        }
+    }
+
     pub fn alias(&self, name: &str, type_params: Option<Vec<Type>>) -> Type {
         Type {
             id: self.fresh_id(),
@@ -177,6 +195,14 @@ impl Context {
         }
     }
 
+    pub fn readonly(&self, t: Type) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::Readonly(Box::from(t)),
+        }
+    }
+
     pub fn rest(&self, arg: Type) -> Type {
         Type {
             id: self.fresh_id(),
@@ -195,6 +221,93 @@ impl Context {
             }),
         }
     }
+
+    pub fn keyof(&self, t: Type) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::KeyOf(Box::from(t)),
+        }
+    }
+
+    pub fn query(&self, name: &str) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::Query(name.to_owned()),
+        }
+    }
+
+    pub fn indexed_access(&self, obj: Type, index: Type) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::IndexedAccess(IndexedAccessType {
+                obj: Box::from(obj),
+                index: Box::from(index),
+            }),
+        }
+    }
+
+    pub fn conditional(
+        &self,
+        check: Type,
+        extends: Type,
+        true_type: Type,
+        false_type: Type,
+    ) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::Conditional(ConditionalType {
+                check: Box::from(check),
+                extends: Box::from(extends),
+                true_type: Box::from(true_type),
+                false_type: Box::from(false_type),
+            }),
+        }
+    }
+
+    pub fn mapped(
+        &self,
+        key: Type,
+        constraint: Type,
+        value: Type,
+        optional: Option<MappedModifier>,
+        readonly: Option<MappedModifier>,
+    ) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::Mapped(MappedType {
+                key: Box::from(key),
+                constraint: Box::from(constraint),
+                value: Box::from(value),
+                optional,
+                readonly,
+            }),
+        }
+    }
+
+    pub fn template_literal(&self, quasis: Vec<String>, types: Vec<Type>) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::TemplateLiteral(TemplateLiteralType { quasis, types }),
+        }
+    }
+
+    pub fn class(&self, name: &str, props: Vec<TProp>) -> Type {
+        Type {
+            id: self.fresh_id(),
+            frozen: false,
+            variant: Variant::Class(ClassType {
+                name: name.to_owned(),
+                props,
+                type_args: vec![],
+            }),
+        }
+    }
 }
 
 pub fn lookup_alias(ctx: &Context, alias: &AliasType) -> Result<Type, String> {
@@ -206,13 +319,22 @@ pub fn lookup_alias(ctx: &Context, alias: &AliasType) -> Result<Type, String> {
             let subs: Subst = match &alias.type_params {
                 Some(type_params) => {
                     if scheme.qualifiers.len() != type_params.len() {
-                        return Err(String::from("mismatch between number of qualifiers in scheme and number of type params"));
+                        return Err(format!(
+                            "Type '{}' expects {} type argument(s) but was given {}",
+                            alias.name,
+                            scheme.qualifiers.len(),
+                            type_params.len()
+                        ));
                     }
                     ids.zip(type_params.iter().cloned()).collect()
                 },
                 None => {
                     if !scheme.qualifiers.is_empty() {
-                        return Err(String::from("mismatch between number of qualifiers in scheme and number of type params"));
+                        return Err(format!(
+                            "Type '{}' expects {} type argument(s) but was given 0",
+                            alias.name,
+                            scheme.qualifiers.len()
+                        ));
                     }
                     ids.zip(scheme.qualifiers.iter().map(|_| ctx.fresh_var())).collect()
                 },
@@ -220,6 +342,75 @@ pub fn lookup_alias(ctx: &Context, alias: &AliasType) -> Result<Type, String> {
 
             Ok(scheme.ty.apply(&subs))
         },
-        None => Err(String::from("Can't find alias '{name}' in context")),
+        None => Err(format!("Can't find alias '{}' in context", alias.name)),
+    }
+}
+
+// Returns an error if expanding the alias `name` requires expanding `name`
+// again before reaching an object, tuple, array, or lambda type.  Those types
+// defer the expansion of the aliases they contain which is what allows
+// recursive types like `type List<T> = {head: T, tail: List<T> | null}`.
+// Generic aliases are expanded with their type args so that cycles through
+// them are found too, e.g. `type B = Id<B>` where `type Id<T> = T`.
+pub fn check_alias_cycle(ctx: &Context, name: &str) -> Result<(), String> {
+    check_alias_cycle_rec(ctx, name, &mut vec![])
+}
+
+// `checking` contains the aliases whose cycle checks are in progress.
+fn check_alias_cycle_rec(
+    ctx: &Context,
+    root: &str,
+    checking: &mut Vec<String>,
+) -> Result<(), String> {
+    fn check(
+        ty: &Type,
+        ctx: &Context,
+        root: &str,
+        seen: &mut Vec<String>,
+        checking: &mut Vec<String>,
+    ) -> Result<(), String> {
+        match &ty.variant {
+            Variant::Alias(alias) => {
+                let name = &alias.name;
+                if name == root {
+                    return Err(format!("Type alias '{root}' circularly references itself"));
+                }
+                // Aliases that haven't been declared yet can't be part of a
+                // cycle.  The cycle will be detected once they are.
+                if !ctx.types.contains_key(name) {
+                    return Ok(());
+                }
+                // An alias can be reached more than once without a cycle when
+                // it's passed itself as a type arg, e.g. `Id<Id<number>>`, as
+                // long as the alias isn't part of a cycle itself.
+                if seen.contains(name) {
+                    if checking.contains(name) {
+                        return Ok(());
+                    }
+                    check_alias_cycle_rec(ctx, name, checking)?;
+                }
+                seen.push(name.to_owned());
+                check(&lookup_alias(ctx, alias)?, ctx, root, seen, checking)?;
+                seen.pop();
+                Ok(())
+            }
+            Variant::Union(types) | Variant::Intersection(types) => {
+                for t in types {
+                    check(t, ctx, root, seen, checking)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    match ctx.types.get(root) {
+        Some(scheme) => {
+            checking.push(root.to_owned());
+            let result = check(&scheme.ty, ctx, root, &mut vec![], checking);
+            checking.pop();
+            result
+        }
+        None => Ok(()),
     }
 }
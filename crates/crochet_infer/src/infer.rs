@@ -1,34 +1,104 @@
 use crochet_ast::*;
 
-use super::context::{Context, Env};
+use super::context::{check_alias_cycle, Context, Env};
+use super::infer_class::infer_class;
 use super::infer_expr::infer_expr as infer_expr_rec;
+use super::infer_interface::infer_interfaces;
 use super::infer_pattern::*;
 use super::infer_type_ann::*;
 use super::substitutable::{Subst, Substitutable};
-use super::types::{freeze_scheme, Scheme, Type, TProp};
+use super::types::{freeze_scheme, ClassType, Scheme, Type, Variant};
 use super::util::*;
 
 pub fn infer_prog(prog: &Program) -> Result<Context, String> {
+    let prog = &flatten_namespaces(prog);
     let mut ctx: Context = Context::default();
-    // TODO: replace with Class type once it exists
-    // We use {_name: "Promise"} to differentiate it from other
-    // object types.
-    let promise_scheme = Scheme::from(ctx.object(vec![TProp {
-        name: String::from("_name"),
-        optional: false,
-        ty: ctx.lit(Lit::str(String::from("Promise"), 0..0)),
-    }]));
+    // The qualifier is kept as a type arg of the class so that expanding
+    // `Promise<T>` doesn't lose `T`.
+    // TODO: add the methods for Promise and JSX.Element
+    let value_type = ctx.fresh_var();
+    let promise_scheme = Scheme {
+        qualifiers: vec![value_type.id],
+        ty: Type {
+            id: ctx.fresh_id(),
+            frozen: false,
+            variant: Variant::Class(ClassType {
+                name: String::from("Promise"),
+                props: vec![],
+                type_args: vec![value_type],
+            }),
+        },
+    };
     ctx.types.insert(String::from("Promise"), promise_scheme);
-    // TODO: replace with Class type once it exists
-    // We use {_name: "JSXElement"} to differentiate it from other
-    // object types.
-    let jsx_element_scheme = Scheme::from(ctx.object(vec![TProp {
-        name: String::from("_name"),
-        optional: false,
-        ty: ctx.lit(Lit::str(String::from("JSXElement"), 0..0)),
-    }]));
+    // The default type of JSX expressions.  It can be replaced by declaring
+    // `Element` in a `JSX` namespace.
+    let jsx_element_scheme = Scheme::from(ctx.class("JSX.Element", vec![]));
     ctx.types
-        .insert(String::from("JSXElement"), jsx_element_scheme);
+        .insert(String::from("JSX.Element"), jsx_element_scheme);
+    // The type of the strings passed as the first arg to the tag function of
+    // a tagged template literal.
+    let mut raw = ctx.prop("raw", ctx.readonly(ctx.array(ctx.prim(Primitive::Str))), false);
+    raw.readonly = true;
+    let strings_scheme = Scheme::from(ctx.class("TemplateStringsArray", vec![raw]));
+    ctx.types
+        .insert(String::from("TemplateStringsArray"), strings_scheme);
+
+    // Type declarations are hoisted so that type annotations can reference
+    // types that are declared later in the program.  A placeholder scheme
+    // with the correct number of qualifiers is added for each declaration
+    // first so that references to it can be arity checked while inferring
+    // the type annotations of all of the declarations.
+    for stmt in &prog.body {
+        if let Statement::TypeDecl {
+            id, type_params, ..
+        }
+        | Statement::EnumDecl {
+            id, type_params, ..
+        }
+        | Statement::InterfaceDecl {
+            id, type_params, ..
+        } = stmt
+        {
+            let qualifiers = match type_params {
+                Some(type_params) => type_params.iter().map(|_| ctx.fresh_id()).collect(),
+                None => vec![],
+            };
+            let scheme = Scheme {
+                qualifiers,
+                ty: ctx.fresh_var(),
+            };
+            ctx.types.insert(id.name.to_owned(), scheme);
+        }
+        if let Statement::ClassDecl { id, .. } = stmt {
+            let scheme = Scheme::from(ctx.fresh_var());
+            ctx.types.insert(id.name.to_owned(), scheme);
+        }
+    }
+    for stmt in &prog.body {
+        if let Statement::TypeDecl {
+            id,
+            type_ann,
+            type_params,
+            ..
+        } = stmt
+        {
+            let scheme = infer_scheme_with_type_params(type_ann, type_params, &ctx)?;
+            ctx.types.insert(id.name.to_owned(), freeze_scheme(scheme));
+        }
+        if let Statement::EnumDecl {
+            id,
+            type_params,
+            variants,
+            ..
+        } = stmt
+        {
+            let type_ann = enum_type_ann(id, variants)?;
+            let scheme = infer_scheme_with_type_params(&type_ann, type_params, &ctx)?;
+            ctx.types
+                .insert(id.name.to_owned(), freeze_scheme(number_qualifiers(&scheme)));
+        }
+    }
+    infer_interfaces(&mut ctx, prog)?;
 
     // TODO: figure out how report multiple errors
     for stmt in &prog.body {
@@ -45,7 +115,8 @@ pub fn infer_prog(prog: &Program) -> Result<Context, String> {
                             Pattern::Ident(BindingIdent { id, type_ann, .. }) => {
                                 match type_ann {
                                     Some(type_ann) => {
-                                        let scheme = infer_scheme(type_ann, &ctx);
+                                        let scheme = infer_scheme(type_ann, &ctx)?;
+                                        let scheme = normalize(&scheme, &ctx);
                                         ctx.values
                                             .insert(id.name.to_owned(), freeze_scheme(scheme));
                                     }
@@ -77,14 +148,33 @@ pub fn infer_prog(prog: &Program) -> Result<Context, String> {
                     }
                 };
             }
-            Statement::TypeDecl {
+            Statement::TypeDecl { id, .. } => {
+                // Type declarations have already been inferred above.
+                check_alias_cycle(&ctx, &id.name)?;
+            }
+            Statement::EnumDecl {
                 id,
-                type_ann,
                 type_params,
+                variants,
                 ..
             } => {
-                let scheme = infer_scheme_with_type_params(type_ann, type_params, &ctx);
-                ctx.types.insert(id.name.to_owned(), freeze_scheme(scheme));
+                // The enum's type has already been inferred above, but its
+                // constructors are values so they're added in program order.
+                let type_ann = enum_ctors_type_ann(id, type_params, variants);
+                let scheme = infer_scheme_with_type_params(&type_ann, type_params, &ctx)?;
+                ctx.values
+                    .insert(id.name.to_owned(), freeze_scheme(number_qualifiers(&scheme)));
+            }
+            Statement::InterfaceDecl { .. } => {
+                // Interfaces have already been inferred above.
+            }
+            Statement::NamespaceDecl { .. } => {
+                // Namespaces have already been flattened above.
+            }
+            Statement::ClassDecl { id, members, .. } => {
+                // Unlike other type declarations, classes are inferred in
+                // program order since their methods can reference values.
+                infer_class(&mut ctx, id, members)?;
             }
             Statement::Expr { expr, .. } => {
                 // We ignore the type that was inferred, we only care that
@@ -97,6 +187,172 @@ pub fn infer_prog(prog: &Program) -> Result<Context, String> {
     Ok(ctx)
 }
 
+// Replaces each namespace with its members, which are renamed to their
+// qualified names, e.g. `Element` in `namespace JSX` becomes `JSX.Element`.
+fn flatten_namespaces(prog: &Program) -> Program {
+    let body = prog
+        .body
+        .iter()
+        .flat_map(|stmt| match stmt {
+            Statement::NamespaceDecl { id: ns, body, .. } => body
+                .iter()
+                .map(|stmt| {
+                    let mut stmt = stmt.to_owned();
+                    if let Statement::TypeDecl { id, .. } | Statement::InterfaceDecl { id, .. } =
+                        &mut stmt
+                    {
+                        id.name = format!("{}.{}", ns.name, id.name);
+                    }
+                    stmt
+                })
+                .collect(),
+            _ => vec![stmt.to_owned()],
+        })
+        .collect();
+
+    Program { body }
+}
+
+// The name of the property used to discriminate between the variants of an
+// enum.
+const ENUM_TAG: &str = "kind";
+
+// Desugars an enum into a union of object types, one for each variant, e.g.
+// `enum Shape { Circle(r: number), Square(s: number) }` becomes
+// `{kind: "Circle", r: number} | {kind: "Square", s: number}`.
+fn enum_type_ann(id: &Ident, variants: &[EnumVariant]) -> Result<TypeAnn, String> {
+    let mut types: Vec<TypeAnn> = vec![];
+    for (i, variant) in variants.iter().enumerate() {
+        let name = &variant.name.name;
+        if variants[..i].iter().any(|v| &v.name.name == name) {
+            return Err(format!("Duplicate variant '{name}' in enum '{}'", id.name));
+        }
+
+        let span = variant.name.span.to_owned();
+        let mut props = vec![TProp {
+            span: span.clone(),
+            name: String::from(ENUM_TAG),
+            optional: false,
+            readonly: false,
+            type_ann: Box::from(TypeAnn::Lit(LitType {
+                span: span.clone(),
+                lit: Lit::str(name.to_owned(), span),
+            })),
+        }];
+        for field in variant.fields.iter().flatten() {
+            if field.name == ENUM_TAG {
+                return Err(format!(
+                    "Variant '{name}' can't have a field named '{ENUM_TAG}'"
+                ));
+            }
+            props.push(field.to_owned());
+        }
+
+        types.push(TypeAnn::Object(ObjectType {
+            span: variant.span.to_owned(),
+            props,
+            index: None,
+        }));
+    }
+
+    match types.len() {
+        1 => Ok(types.remove(0)),
+        _ => Ok(TypeAnn::Union(UnionType {
+            span: id.span.to_owned(),
+            types,
+        })),
+    }
+}
+
+// Desugars the constructors for the variants of an enum into an object type.
+// Variants with fields are functions which return the enum type while unit
+// variants are values of the enum type, e.g. the constructors for
+// `enum Option<T> { Some(value: T), None }` have the following type:
+// `{Some: (T) => Option<T>, None: Option<T>}`.
+fn enum_ctors_type_ann(
+    id: &Ident,
+    type_params: &Option<Vec<TypeParam>>,
+    variants: &[EnumVariant],
+) -> TypeAnn {
+    let enum_type_ann = TypeAnn::TypeRef(TypeRef {
+        span: id.span.to_owned(),
+        name: id.name.to_owned(),
+        type_params: type_params.as_ref().map(|type_params| {
+            type_params
+                .iter()
+                .map(|param| {
+                    TypeAnn::TypeRef(TypeRef {
+                        span: param.span.to_owned(),
+                        name: param.name.name.to_owned(),
+                        type_params: None,
+                    })
+                })
+                .collect()
+        }),
+    });
+
+    let props = variants
+        .iter()
+        .map(|variant| {
+            let type_ann = match &variant.fields {
+                Some(fields) => TypeAnn::Lam(LamType {
+                    span: variant.span.to_owned(),
+                    params: fields
+                        .iter()
+                        .map(|field| TFnParam {
+                            span: field.span.to_owned(),
+                            name: None,
+                            type_ann: field.type_ann.to_owned(),
+                        })
+                        .collect(),
+                    ret: Box::from(enum_type_ann.clone()),
+                    type_params: None,
+                }),
+                None => enum_type_ann.clone(),
+            };
+            TProp {
+                span: variant.span.to_owned(),
+                name: variant.name.name.to_owned(),
+                optional: false,
+                readonly: false,
+                type_ann: Box::from(type_ann),
+            }
+        })
+        .collect();
+
+    TypeAnn::Object(ObjectType {
+        span: id.span.to_owned(),
+        props,
+        index: None,
+    })
+}
+
+// Renames the qualifiers of `scheme` to 0, 1, 2, etc. in the same order as
+// the type params they were created from.  Unlike `normalize`, this keeps
+// qualifiers that don't appear in the scheme's type.
+fn number_qualifiers(scheme: &Scheme) -> Scheme {
+    let s: Subst = scheme
+        .qualifiers
+        .iter()
+        .enumerate()
+        .map(|(index, id)| {
+            (
+                id.to_owned(),
+                Type {
+                    id: index as i32,
+                    frozen: false,
+                    variant: Variant::Var,
+                },
+            )
+        })
+        .collect();
+
+    Scheme {
+        qualifiers: (0..scheme.qualifiers.len()).map(|x| x as i32).collect(),
+        ty: scheme.ty.apply(&s),
+    }
+}
+
 pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<Scheme, String> {
     let (s, t) = infer_expr_rec(ctx, expr)?;
     Ok(close_over(&s, &t, ctx))
@@ -0,0 +1,118 @@
+use crochet_ast::*;
+
+use super::context::Context;
+use super::infer_expr::infer_expr;
+use super::infer_type_ann::*;
+use super::substitutable::{Subst, Substitutable};
+use super::types::{self, freeze_scheme, Scheme, Type, Variant};
+use super::unify::unify;
+use super::util::*;
+
+// Infers the instance type of the class along with the type of its constructor.
+// The instance type is added to `ctx.types` and the constructor is added to
+// `ctx.values`, both using the name of the class.
+pub fn infer_class(ctx: &mut Context, id: &Ident, members: &[ClassMember]) -> Result<(), String> {
+    let name = &id.name;
+    let mut props: Vec<types::TProp> = vec![];
+    let mut methods: Vec<(&ClassMethod, Type)> = vec![];
+    let mut ctor: Option<&Constructor> = None;
+
+    for member in members {
+        let key = match member {
+            ClassMember::Constructor(_) => String::from("constructor"),
+            ClassMember::Method(method) => method.key.name.to_owned(),
+            ClassMember::Prop(prop) => prop.key.name.to_owned(),
+        };
+        let is_duplicate = match member {
+            ClassMember::Constructor(_) => ctor.is_some(),
+            _ => props.iter().any(|prop| prop.name == key),
+        };
+        if is_duplicate {
+            return Err(format!("Duplicate member '{key}' in class '{name}'"));
+        }
+
+        match member {
+            ClassMember::Constructor(constructor) => ctor = Some(constructor),
+            ClassMember::Method(method) => {
+                // Methods are given a type variable until they're inferred below
+                // so that they can call each other.
+                let tv = ctx.fresh_var();
+                props.push(types::TProp {
+                    name: key,
+                    optional: false,
+                    readonly: false,
+                    ty: tv.clone(),
+                });
+                methods.push((method, tv));
+            }
+            ClassMember::Prop(prop) => props.push(types::TProp {
+                name: key,
+                optional: prop.optional,
+                readonly: false,
+                ty: infer_type_ann(&prop.type_ann, ctx)?,
+            }),
+        }
+    }
+
+    // The class and its constructor are added to the context before the
+    // methods are inferred so that methods can reference the class.
+    let this_t = ctx.class(name, props.clone());
+    ctx.types
+        .insert(name.to_owned(), Scheme::from(this_t.clone()));
+
+    let ctor_t = match ctor {
+        Some(Constructor { span, params, body }) => {
+            let lambda = Expr::Lambda(Lambda {
+                span: span.to_owned(),
+                params: params.to_owned(),
+                body: body.to_owned(),
+                is_async: false,
+                return_type: None,
+                type_params: None,
+            });
+            let (s, t) = infer_with_this(ctx, &this_t, &lambda)?;
+            match t.apply(&s).variant {
+                Variant::Lam(types::LamType { params, .. }) => {
+                    ctx.lam(params, Box::from(ctx.alias(name, None)))
+                }
+                _ => panic!("Expected the constructor to be a lambda"),
+            }
+        }
+        None => ctx.lam(vec![], Box::from(ctx.alias(name, None))),
+    };
+    ctx.values
+        .insert(name.to_owned(), Scheme::from(ctor_t.clone()));
+
+    let mut ss: Vec<Subst> = vec![];
+    for (method, tv) in &methods {
+        let s = compose_many_subs(&ss);
+        let this_t = ctx.class(name, props.apply(&s));
+        let (s1, t1) = infer_with_this(ctx, &this_t, &Expr::Lambda(method.lambda.to_owned()))?;
+        let s2 = unify(&t1, &tv.apply(&s1).apply(&s), ctx)?;
+        ss.push(s1);
+        ss.push(s2);
+    }
+    let s = compose_many_subs(&ss);
+    let instance_t = ctx.class(name, props.apply(&s));
+
+    ctx.types
+        .insert(name.to_owned(), freeze_scheme(Scheme::from(instance_t)));
+    let ctor_scheme = normalize(&Scheme::from(ctor_t), ctx);
+    ctx.values.insert(name.to_owned(), freeze_scheme(ctor_scheme));
+
+    Ok(())
+}
+
+fn infer_with_this(ctx: &Context, this_t: &Type, expr: &Expr) -> Result<(Subst, Type), String> {
+    let mut new_ctx = ctx.clone();
+    new_ctx
+        .values
+        .insert(String::from("this"), Scheme::from(this_t));
+
+    let result = infer_expr(&mut new_ctx, expr);
+
+    // Copies over the count from new_ctx so that it's unique across Contexts.
+    ctx.state.count.set(new_ctx.state.count.get());
+
+    result
+}
@@ -5,6 +5,7 @@ use crochet_ast::*;
 use super::context::{lookup_alias, Context};
 use super::infer_pattern::*;
 use super::infer_type_ann::*;
+use super::narrow::{infer_refined, narrow};
 use super::substitutable::{Subst, Substitutable};
 use super::types::{self, Type, Variant};
 use super::unify::unify;
@@ -13,46 +14,32 @@ use super::util::*;
 pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), String> {
     let result = match expr {
         Expr::App(App { lam, args, .. }) => {
-            let mut ss: Vec<Subst> = vec![];
-            let mut arg_types: Vec<Type> = vec![];
-
-            let (s1, lam_type) = infer_expr(ctx, lam)?;
-            ss.push(s1);
-
-            for arg in args {
-                let (arg_s, arg_t) = infer_expr(ctx, arg.expr.as_ref())?;
-                ss.push(arg_s);
-                if arg.spread.is_some() {
-                    match arg_t.variant {
-                        Variant::Tuple(types) => arg_types.extend(types.to_owned()),
-                        _ => arg_types.push(ctx.rest(arg_t))
-                    }
-                } else {
-                    arg_types.push(arg_t);
+            if let Expr::Ident(Ident { name, .. }) = lam.as_ref() {
+                if is_class(name, ctx) {
+                    return Err(format!(
+                        "Class constructor '{name}' cannot be invoked without 'new'"
+                    ));
                 }
             }
+            infer_app(ctx, lam, args)
+        }
+        Expr::New(New { expr, args, .. }) => match expr.as_ref() {
+            Expr::Ident(Ident { name, .. }) if is_class(name, ctx) => infer_app(ctx, expr, args),
+            _ => Err(String::from("Only classes can be instantiated using 'new'")),
+        },
+        Expr::Assign(Assign { left, right, .. }) => {
+            match left.as_ref() {
+                Expr::Member(Member { obj, .. })
+                    if matches!(obj.as_ref(), Expr::Ident(Ident { name, .. }) if name == "this") => {}
+                _ => return Err(String::from("Only properties of 'this' can be assigned to")),
+            }
 
-            let ret_type = ctx.fresh_var();
-            // Are we missing an `apply()` call here?
-            // Maybe, I could see us needing an apply to handle generic functions properly
-            // s3       <- unify (apply s2 t1) (TArr t2 tv)
-            let call_type = Type {
-                id: ctx.fresh_id(),
-                frozen: false,
-                variant: Variant::Lam(types::LamType {
-                    params: arg_types,
-                    ret: Box::from(ret_type.clone()),
-                    is_call: true,
-                }),
-            };
-            let s3 = unify(&call_type, &lam_type, ctx)?;
-
-            ss.push(s3);
-
-            let s = compose_many_subs(&ss);
-            let t = ret_type.apply(&s);
+            let (s1, t1) = infer_expr(ctx, left)?;
+            let (s2, t2) = infer_expr(ctx, right)?;
+            let s3 = unify(&t2, &t1, ctx)?;
 
-            // return (s3 `compose` s2 `compose` s1, apply s3 tv)
+            let s = compose_many_subs(&[s1, s2, s3]);
+            let t = t2.apply(&s);
             Ok((s, t))
         }
         Expr::Fix(Fix { expr, .. }) => {
@@ -85,9 +72,10 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
                     }
                     _ => {
                         let (s1, t1) = infer_expr(ctx, cond)?;
-                        let (s2, t2) = infer_expr(ctx, consequent)?;
-                        let (s3, t3) = infer_expr(ctx, alternate)?;
-                        let s4 = unify(&t1, &ctx.prim(Primitive::Bool), ctx)?;
+                        let (cons_refinements, alt_refinements) = narrow(cond, ctx)?;
+                        let (s2, t2) = infer_refined(ctx, &cons_refinements, consequent)?;
+                        let (s3, t3) = infer_refined(ctx, &alt_refinements, alternate)?;
+                        let s4 = unify_cond(&t1, ctx)?;
 
                         let s = compose_many_subs(&[s1, s2, s3, s4]);
                         let t = union_types(&t2, &t3, ctx);
@@ -111,8 +99,9 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
                 }
                 _ => {
                     let (s1, t1) = infer_expr(ctx, cond)?;
-                    let (s2, t2) = infer_expr(ctx, consequent)?;
-                    let s3 = unify(&t1, &ctx.prim(Primitive::Bool), ctx)?;
+                    let (cons_refinements, _) = narrow(cond, ctx)?;
+                    let (s2, t2) = infer_refined(ctx, &cons_refinements, consequent)?;
+                    let s3 = unify_cond(&t1, ctx)?;
                     let s4 = match unify(&t2, &ctx.prim(Primitive::Undefined), ctx) {
                         Ok(s) => Ok(s),
                         Err(_) => Err(String::from(
@@ -126,135 +115,9 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
                 }
             },
         },
-        Expr::JSXElement(JSXElement {
-            name,
-            attrs,
-            children: _,
-            ..
-        }) => {
-            let first_char = name.chars().next().unwrap();
-            // JSXElement's starting with an uppercase char are user defined.
-            if first_char.is_uppercase() {
-                match ctx.values.get(name) {
-                    Some(scheme) => {
-                        let ct = ctx.instantiate(scheme);
-                        match &ct.variant {
-                            Variant::Lam(_) => {
-                                let mut ss: Vec<_> = vec![];
-                                let mut props: Vec<_> = vec![];
-                                for attr in attrs {
-                                    let (s, t) = match &attr.value {
-                                        JSXAttrValue::Lit(lit) => {
-                                            infer_expr(ctx, &Expr::Lit(lit.to_owned()))?
-                                        }
-                                        JSXAttrValue::JSXExprContainer(JSXExprContainer {
-                                            expr,
-                                            ..
-                                        }) => infer_expr(ctx, expr)?,
-                                    };
-                                    ss.push(s);
-
-                                    let prop = types::TProp {
-                                        name: attr.ident.name.to_owned(),
-                                        optional: false,
-                                        ty: t,
-                                    };
-                                    props.push(prop);
-                                }
-
-                                let ret_type = ctx.alias("JSXElement", None);
-
-                                let call_type = Type {
-                                    id: ctx.fresh_id(),
-                                    frozen: false,
-                                    variant: Variant::Lam(types::LamType {
-                                        params: vec![ctx.object(props)],
-                                        ret: Box::from(ret_type.clone()),
-                                        is_call: true,
-                                    }),
-                                };
-
-                                let s1 = compose_many_subs(&ss);
-                                let s2 = unify(&call_type, &ct, ctx)?;
-
-                                let s = compose_subs(&s2, &s1);
-
-                                return Ok((s, ret_type));
-                            }
-                            _ => return Err(String::from("Component must be a function")),
-                        }
-                    }
-                    None => return Err(format!("Component '{name}' is not in scope")),
-                }
-            }
-
-            let s = Subst::default();
-            // TODO: check props on JSXInstrinsics
-            let t = ctx.alias("JSXElement", None);
-
-            Ok((s, t))
-        }
-        Expr::Lambda(Lambda {
-            params,
-            body,
-            is_async,
-            return_type: rt_type_ann,
-            type_params,
-            ..
-        }) => {
-            let mut new_ctx = ctx.clone();
-            new_ctx.is_async = is_async.to_owned();
-
-            let type_params_map: HashMap<String, Type> = match type_params {
-                Some(params) => params
-                    .iter()
-                    .map(|param| (param.name.name.to_owned(), new_ctx.fresh_var()))
-                    .collect(),
-                None => HashMap::default(),
-            };
-
-            let params: Result<Vec<(Subst, Type)>, String> = params
-                .iter()
-                .map(|param| {
-                    let (ps, pa, pt) = infer_pattern(param, &new_ctx, &type_params_map)?;
-
-                    // Inserts any new variables introduced by infer_pattern() into
-                    // the current context.
-                    for (name, scheme) in pa {
-                        new_ctx.values.insert(name, scheme);
-                    }
-
-                    Ok((ps, pt))
-                })
-                .collect();
-
-            let (ss, ts): (Vec<_>, Vec<_>) = params?.iter().cloned().unzip();
-
-            let (rs, rt) = infer_expr(&mut new_ctx, body)?;
-
-            // Copies over the count from new_ctx so that it's unique across Contexts.
-            ctx.state.count.set(new_ctx.state.count.get());
-
-            let rt = if *is_async && !is_promise(&rt) {
-                ctx.alias("Promise", Some(vec![rt]))
-            } else {
-                rt
-            };
-
-            let s = match rt_type_ann {
-                Some(rt_type_ann) => unify(
-                    &rt,
-                    &infer_type_ann_with_params(rt_type_ann, ctx, &type_params_map),
-                    ctx,
-                )?,
-                None => Subst::default(),
-            };
-            let t = ctx.lam(ts, Box::from(rt));
-            let s = compose_subs(&s, &compose_subs(&rs, &compose_many_subs(&ss)));
-            let t = t.apply(&s);
-
-            Ok((s, t))
-        }
+        Expr::JSXElement(elem) => infer_jsx_element(ctx, elem),
+        Expr::JSXFragment(frag) => infer_jsx_fragment(ctx, frag),
+        Expr::Lambda(lambda) => infer_lambda(ctx, lambda, None),
         Expr::Let(Let {
             pattern,
             init,
@@ -265,7 +128,12 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
                 Some(pat) => infer_let(pat, init, body, ctx, &PatternUsage::Assign),
                 None => {
                     // TODO: warn about unused values
-                    infer_expr(ctx, body)
+                    let (s1, _) = infer_expr(ctx, init)?;
+                    let (s2, t2) = infer_expr(ctx, body)?;
+
+                    let s = compose_subs(&s2, &s1);
+                    let t = t2.apply(&s);
+                    Ok((s, t))
                 }
             }
         }
@@ -280,12 +148,17 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
         Expr::Op(Op {
             op, left, right, ..
         }) => {
-            // TODO: check what `op` is and handle comparison operators
-            // differently from arithmetic operators
             let (s1, t1) = infer_expr(ctx, left)?;
             let (s2, t2) = infer_expr(ctx, right)?;
-            let s3 = unify(&t1, &ctx.prim(Primitive::Num), ctx)?;
-            let s4 = unify(&t2, &ctx.prim(Primitive::Num), ctx)?;
+            // Values of any type can be compared for equality.
+            // TODO: report an error if the types of `left` and `right` don't overlap
+            let (s3, s4) = match op {
+                BinOp::EqEq | BinOp::NotEq => (Subst::default(), Subst::default()),
+                _ => (
+                    unify(&t1, &ctx.prim(Primitive::Num), ctx)?,
+                    unify(&t2, &ctx.prim(Primitive::Num), ctx)?,
+                ),
+            };
             let t = match op {
                 BinOp::Add => ctx.prim(Primitive::Num),
                 BinOp::Sub => ctx.prim(Primitive::Num),
@@ -300,46 +173,43 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
             };
             Ok((compose_many_subs(&[s1, s2, s3, s4]), t))
         }
-        Expr::Obj(Obj { props, .. }) => {
-            let mut ss: Vec<Subst> = vec![];
-            let mut ps: Vec<types::TProp> = vec![];
-            let mut spread_types: Vec<_> = vec![];
-            for p in props {
-                match p {
-                    PropOrSpread::Prop(p) => {
-                        match p.as_ref() {
-                            Prop::Shorthand(Ident { name, .. }) => {
-                                let t = ctx.lookup_value(name)?;
-                                ps.push(ctx.prop(name, t, false));
-                            }
-                            Prop::KeyValue(KeyValueProp { name, value, .. }) => {
-                                let (s, t) = infer_expr(ctx, value)?;
-                                ss.push(s);
-                                // TODO: check if the inferred type is T | undefined and use that
-                                // determine the value of optional
-                                ps.push(ctx.prop(name, t, false));
-                            }
-                        }
-                    }
-                    PropOrSpread::Spread(SpreadElement { expr, .. }) => {
-                        let (s, t) = infer_expr(ctx, expr)?;
-                        ss.push(s);
-                        spread_types.push(t);
-                    }
-                }
-            }
+        Expr::Unary(Unary { op, arg, .. }) => {
+            let (s, _) = infer_expr(ctx, arg)?;
+            let t = match op {
+                UnaryOp::Not => ctx.prim(Primitive::Bool),
+                UnaryOp::Typeof => ctx.prim(Primitive::Str),
+            };
+            Ok((s, t))
+        }
+        Expr::As(As { expr, type_ann, .. }) => {
+            let (s1, t1) = infer_expr(ctx, expr)?;
+            let t2 = infer_type_ann(type_ann, ctx)?;
 
-            let s = compose_many_subs(&ss);
-            if spread_types.is_empty() {
-                let t = ctx.object(ps);
-                Ok((s, t))
-            } else {
-                let mut all_types = spread_types;
-                all_types.push(ctx.object(ps));
-                let t = simplify_intersection(&all_types, ctx);
-                Ok((s, t))
-            }
+            // Assertions can widen or narrow the type of an expression, but
+            // not change it to a type that it doesn't overlap with.
+            let s2 = match unify(&t1, &t2, ctx) {
+                Ok(s) => s,
+                Err(_) => unify(&t2, &t1, ctx).map_err(|_| {
+                    format!("Conversion of type '{t1}' to type '{t2}' may be a mistake because neither type sufficiently overlaps with the other")
+                })?,
+            };
+
+            let s = compose_subs(&s2, &s1);
+            let t = t2.apply(&s);
+            Ok((s, t))
         }
+        Expr::Satisfies(Satisfies { expr, type_ann, .. }) => {
+            let (s1, t1) = infer_expr(ctx, expr)?;
+            let t2 = infer_type_ann(type_ann, ctx)?;
+
+            let s2 = unify(&t1, &t2, ctx)
+                .map_err(|_| format!("Type '{t1}' does not satisfy the expected type '{t2}'"))?;
+
+            let s = compose_subs(&s2, &s1);
+            let t = t1.apply(&s);
+            Ok((s, t))
+        }
+        Expr::Obj(Obj { props, .. }) => infer_obj(ctx, props, None),
         Expr::Await(Await { expr, .. }) => {
             if !ctx.is_async {
                 return Err(String::from("Can't use `await` inside non-async lambda"));
@@ -386,9 +256,23 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
             let t = ctx.tuple(ts);
             Ok((s, t))
         }
+        Expr::Member(Member {
+            obj,
+            prop: MemberProp::Computed(ComputedPropName { expr: key, .. }),
+            ..
+        }) => {
+            let (obj_s, obj_t) = infer_expr(ctx, obj)?;
+            let (key_s, key_t) = infer_expr(ctx, key)?;
+            let (prop_s, prop_t) = infer_computed_property_type(&obj_t, &key_t, ctx)?;
+
+            let s = compose_many_subs(&[obj_s, key_s, prop_s]);
+            let t = unwrap_member_type(&prop_t, ctx);
+
+            Ok((s, t))
+        }
         Expr::Member(Member { obj, prop, .. }) => {
             let (obj_s, obj_t) = infer_expr(ctx, obj)?;
-            let (prop_s, prop_t) = infer_property_type(&obj_t, prop, ctx)?;
+            let (prop_s, prop_t) = infer_property_type(&obj_t, &prop.name(), ctx)?;
 
             let s = compose_subs(&prop_s, &obj_s);
             let t = unwrap_member_type(&prop_t, ctx);
@@ -400,23 +284,53 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
             let s = Subst::default();
             Ok((s, t))
         }
-        Expr::TemplateLiteral(TemplateLiteral { exprs, quasis: _, .. }) => {
-            let t = ctx.prim(Primitive::Str);
+        Expr::TemplateLiteral(TemplateLiteral { exprs, quasis, .. }) => {
             let result: Result<Vec<(Subst, Type)>, String> = exprs.iter().map(|expr| {
                 infer_expr(ctx, expr)
             }).collect();
-            // We ignore the types of expressions if there are any because any expression
-            // in JavaScript has a string representation.
-            let (ss, _): (Vec<_>, Vec<_>) = result?.iter().cloned().unzip();
+            // Any expression in JavaScript has a string representation so the
+            // expressions can have any type.  If they're all literals though
+            // then we know exactly which string the template literal produces.
+            let (ss, ts): (Vec<_>, Vec<_>) = result?.iter().cloned().unzip();
             let s = compose_many_subs(&ss);
+            let lits: Option<Vec<_>> = ts
+                .iter()
+                .map(|t| match &t.variant {
+                    Variant::Lit(lit) => Some(lit_to_string(lit)),
+                    _ => None,
+                })
+                .collect();
+            let t = match lits {
+                Some(lits) => {
+                    let mut value = String::new();
+                    for (i, quasi) in quasis.iter().enumerate() {
+                        match &quasi.cooked {
+                            Lit::Str(Str { value: quasi, .. }) => value.push_str(quasi),
+                            _ => panic!("quasi.cooked must be a string"),
+                        }
+                        if let Some(lit) = lits.get(i) {
+                            value.push_str(lit);
+                        }
+                    }
+                    ctx.lit_type(types::Lit::Str(value))
+                }
+                None => ctx.prim(Primitive::Str),
+            };
             Ok((s, t))
         }
-        Expr::TaggedTemplateLiteral(_) => {
-            // TODO: treat this like a call/application
-            // NOTE: requires:
-            // - arrays
-            // - rest params
-            todo!()
+        // Tagged template literals are calls to the tag function where the
+        // first arg contains the strings and the rest are the expressions.
+        Expr::TaggedTemplateLiteral(TaggedTemplateLiteral { tag, template, .. }) => {
+            let strings_t = ctx.lookup_type("TemplateStringsArray")?;
+            let args: Vec<_> = template
+                .exprs
+                .iter()
+                .map(|expr| ExprOrSpread {
+                    spread: None,
+                    expr: Box::from(expr.to_owned()),
+                })
+                .collect();
+            infer_call(ctx, tag, vec![strings_t], &args)
         }
     };
 
@@ -430,6 +344,482 @@ pub fn infer_expr(ctx: &mut Context, expr: &Expr) -> Result<(Subst, Type), Strin
     Ok((s, t))
 }
 
+// Checks that `expr` is assignable to the `expected` type.  Unlike
+// `infer_expr`, the expected type is pushed down into lambdas and object
+// literals so that the params of callbacks don't need type annotations, e.g.
+// `map(arr, (x) => x.name)`.
+pub fn check_expr(
+    ctx: &mut Context,
+    expr: &Expr,
+    expected: &Type,
+) -> Result<(Subst, Type), String> {
+    let (s1, t1) = match (expr, &expand_expected(expected, ctx)?.variant) {
+        (Expr::Lambda(lambda), Variant::Lam(lam)) => infer_lambda(ctx, lambda, Some(lam))?,
+        // Lambdas can only be checked against a union containing a single
+        // lambda type, e.g. `((number) => string) | string`.
+        (Expr::Lambda(lambda), Variant::Union(types)) => {
+            let types: Result<Vec<_>, String> =
+                types.iter().map(|t| expand_expected(t, ctx)).collect();
+            let lams: Vec<_> = types?
+                .into_iter()
+                .filter_map(|t| match t.variant {
+                    Variant::Lam(lam) => Some(lam),
+                    _ => None,
+                })
+                .collect();
+            match lams.as_slice() {
+                [lam] => infer_lambda(ctx, lambda, Some(lam))?,
+                _ => infer_expr(ctx, expr)?,
+            }
+        }
+        (Expr::Obj(Obj { props, .. }), Variant::Object(tprops)) => {
+            infer_obj(ctx, props, Some(tprops))?
+        }
+        // Template literals are only given template literal types when that's
+        // what's expected, otherwise they're strings, e.g. `` `on${name}` `` is
+        // `` `on${string}` `` when `name` is a string.
+        (
+            Expr::TemplateLiteral(TemplateLiteral { exprs, quasis, .. }),
+            Variant::TemplateLiteral(_),
+        ) => {
+            let result: Result<Vec<(Subst, Type)>, String> =
+                exprs.iter().map(|expr| infer_expr(ctx, expr)).collect();
+            let (ss, ts): (Vec<_>, Vec<_>) = result?.into_iter().unzip();
+            let quasis = quasis
+                .iter()
+                .map(|quasi| match &quasi.cooked {
+                    Lit::Str(Str { value, .. }) => value.to_owned(),
+                    _ => panic!("quasi.cooked must be a string"),
+                })
+                .collect();
+            (compose_many_subs(&ss), template_literal_type(quasis, ts, ctx))
+        }
+        _ => infer_expr(ctx, expr)?,
+    };
+
+    let s2 = unify(&t1, &expected.apply(&s1), ctx)?;
+
+    let s = compose_subs(&s2, &s1);
+    let t = t1.apply(&s);
+    Ok((s, t))
+}
+
+// Expands aliases in the expected type.  Optional values, e.g. `T | undefined`,
+// are treated as `T` since that's the only type that lambdas and object
+// literals could be checked against.
+fn expand_expected(expected: &Type, ctx: &Context) -> Result<Type, String> {
+    match &expected.variant {
+        Variant::Alias(alias) => expand_expected(&lookup_alias(ctx, alias)?, ctx),
+        Variant::Union(types) => {
+            let types: Vec<&Type> = types
+                .iter()
+                .filter(|t| {
+                    !matches!(
+                        t.variant,
+                        Variant::Prim(Primitive::Undefined | Primitive::Null)
+                            | Variant::Lit(types::Lit::Undefined | types::Lit::Null)
+                    )
+                })
+                .collect();
+            match types.as_slice() {
+                [t] => expand_expected(t, ctx),
+                _ => Ok(expected.to_owned()),
+            }
+        }
+        _ => Ok(expected.to_owned()),
+    }
+}
+
+// Infers the type of an object literal.  If there's an `expected` type then
+// the values of its props are checked against the types of those props.
+fn infer_obj(
+    ctx: &mut Context,
+    props: &[PropOrSpread],
+    expected: Option<&[types::TProp]>,
+) -> Result<(Subst, Type), String> {
+    let mut ss: Vec<Subst> = vec![];
+    let mut ps: Vec<types::TProp> = vec![];
+    let mut spread_types: Vec<_> = vec![];
+    for p in props {
+        match p {
+            PropOrSpread::Prop(p) => {
+                match p.as_ref() {
+                    Prop::Shorthand(Ident { name, .. }) => {
+                        let t = ctx.lookup_value(name)?;
+                        ps.push(ctx.prop(name, t, false));
+                    }
+                    Prop::KeyValue(KeyValueProp { name, value, .. }) => {
+                        let expected_prop = expected
+                            .and_then(|expected| expected.iter().find(|prop| &prop.name == name));
+                        let (s, t) = match expected_prop {
+                            Some(prop) => check_expr(ctx, value, &prop.get_type(ctx))?,
+                            None => infer_expr(ctx, value)?,
+                        };
+                        ss.push(s);
+                        // TODO: check if the inferred type is T | undefined and use that
+                        // determine the value of optional
+                        ps.push(ctx.prop(name, t, false));
+                    }
+                }
+            }
+            PropOrSpread::Spread(SpreadElement { expr, .. }) => {
+                let (s, t) = infer_expr(ctx, expr)?;
+                ss.push(s);
+                spread_types.push(t);
+            }
+        }
+    }
+
+    let s = compose_many_subs(&ss);
+    if spread_types.is_empty() {
+        let t = ctx.object(ps);
+        Ok((s, t))
+    } else {
+        let mut all_types = spread_types;
+        all_types.push(ctx.object(ps));
+        let t = simplify_intersection(&all_types, ctx);
+        Ok((s, t))
+    }
+}
+
+fn infer_jsx_element(ctx: &mut Context, elem: &JSXElement) -> Result<(Subst, Type), String> {
+    let JSXElement {
+        name,
+        attrs,
+        children,
+        ..
+    } = elem;
+
+    // JSXElement's starting with an uppercase char or using a member expression
+    // as their name, e.g. `<Ctx.Provider>`, are user defined.
+    let (s1, ct) = match name {
+        JSXElementName::Ident(Ident { name, .. }) => {
+            let first_char = name.chars().next().unwrap();
+            if !first_char.is_uppercase() {
+                return infer_jsx_intrinsic_element(ctx, name, attrs, children);
+            }
+            match ctx.values.get(name) {
+                Some(scheme) => (Subst::default(), ctx.instantiate(scheme)),
+                None => return Err(format!("Component '{name}' is not in scope")),
+            }
+        }
+        JSXElementName::Member(_) => infer_expr(ctx, &jsx_element_name_to_expr(name))?,
+    };
+
+    match &ct.variant {
+        Variant::Lam(_) => {
+            let children_attr = jsx_children_attr(ctx)?.unwrap_or_else(|| String::from("children"));
+            let (s2, props_type) = infer_jsx_props(ctx, attrs, children, &children_attr)?;
+            let ret_type = ctx.alias("JSX.Element", None);
+
+            let call_type = Type {
+                id: ctx.fresh_id(),
+                frozen: false,
+                variant: Variant::Lam(types::LamType {
+                    params: vec![props_type],
+                    ret: Box::from(ret_type.clone()),
+                    is_call: true,
+                }),
+            };
+
+            let s3 = unify(&call_type, &ct, ctx)?;
+
+            let s = compose_many_subs(&[s1, s2, s3]);
+
+            Ok((s, ret_type))
+        }
+        _ => Err(String::from("Component must be a function")),
+    }
+}
+
+// Intrinsic elements, e.g. `<div>`, are checked against the props for their
+// tag in `JSX.IntrinsicElements` if it's been declared.  Like TypeScript,
+// their children are only passed as a prop if `JSX.ElementChildrenAttribute`
+// has been declared.
+fn infer_jsx_intrinsic_element(
+    ctx: &mut Context,
+    tag: &str,
+    attrs: &[JSXAttrOrSpread],
+    children: &[JSXElementChild],
+) -> Result<(Subst, Type), String> {
+    let children_attr = jsx_children_attr(ctx)?;
+    let (s1, attrs_type) = match &children_attr {
+        Some(children_attr) => infer_jsx_props(ctx, attrs, children, children_attr)?,
+        None => {
+            let (s1, _) = infer_jsx_children(ctx, children)?;
+            let (s2, t) = infer_jsx_props(ctx, attrs, &[], "children")?;
+            (compose_subs(&s2, &s1), t)
+        }
+    };
+
+    let s = match ctx.types.get("JSX.IntrinsicElements") {
+        Some(scheme) => {
+            let elems = ctx.instantiate(scheme);
+            let props_type = expand_type(&ctx.mem(elems, tag), ctx)
+                .map_err(|_| format!("'{tag}' is not a known intrinsic element"))?;
+
+            // Attributes with hyphens in their names, e.g. `data-*` and
+            // `aria-*`, are allowed even if the element doesn't declare them.
+            let mut names: Vec<&str> = attrs
+                .iter()
+                .filter_map(|attr| match attr {
+                    JSXAttrOrSpread::JSXAttr(JSXAttr { ident, .. }) => Some(ident.name.as_str()),
+                    JSXAttrOrSpread::JSXSpreadAttr(_) => None,
+                })
+                .filter(|name| !name.contains('-'))
+                .collect();
+            if let Some(children_attr) = &children_attr {
+                if !children.is_empty() {
+                    names.push(children_attr);
+                }
+            }
+            for name in names {
+                if expand_type(&ctx.mem(props_type.clone(), name), ctx).is_err() {
+                    return Err(format!(
+                        "Property '{name}' does not exist on intrinsic element '{tag}'"
+                    ));
+                }
+            }
+
+            let s2 = unify(&attrs_type, &props_type, ctx)?;
+            compose_subs(&s2, &s1)
+        }
+        None => s1,
+    };
+
+    let t = ctx.alias("JSX.Element", None);
+
+    Ok((s, t))
+}
+
+// TypeScript passes children using the name of the only property in
+// `JSX.ElementChildrenAttribute`.
+fn jsx_children_attr(ctx: &Context) -> Result<Option<String>, String> {
+    match ctx.types.get("JSX.ElementChildrenAttribute") {
+        Some(scheme) => match &ctx.instantiate(scheme).variant {
+            Variant::Object(props) if props.len() == 1 => Ok(Some(props[0].name.to_owned())),
+            _ => Err(String::from(
+                "'JSX.ElementChildrenAttribute' must have exactly one property",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+fn infer_jsx_fragment(ctx: &mut Context, frag: &JSXFragment) -> Result<(Subst, Type), String> {
+    let (s, _) = infer_jsx_children(ctx, &frag.children)?;
+    let t = ctx.alias("JSX.Element", None);
+
+    Ok((s, t))
+}
+
+// Builds the props object passed to a component.  Boolean attributes, e.g.
+// `<Foo disabled />`, are `true`, spread attributes are merged in the same
+// way as spreads in object literals, and any children are passed as the
+// `children_attr` prop.
+fn infer_jsx_props(
+    ctx: &mut Context,
+    attrs: &[JSXAttrOrSpread],
+    children: &[JSXElementChild],
+    children_attr: &str,
+) -> Result<(Subst, Type), String> {
+    let mut ss: Vec<Subst> = vec![];
+    let mut props: Vec<types::TProp> = vec![];
+    let mut spread_types: Vec<_> = vec![];
+    for attr in attrs {
+        match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr { ident, value, .. }) => {
+                let (s, t) = match value {
+                    Some(JSXAttrValue::Lit(lit)) => infer_expr(ctx, &Expr::Lit(lit.to_owned()))?,
+                    Some(JSXAttrValue::JSXExprContainer(JSXExprContainer { expr, .. })) => {
+                        infer_expr(ctx, expr)?
+                    }
+                    None => (Subst::default(), ctx.lit_type(types::Lit::Bool(true))),
+                };
+                ss.push(s);
+                props.push(ctx.prop(&ident.name, t, false));
+            }
+            JSXAttrOrSpread::JSXSpreadAttr(JSXSpreadAttr { expr, .. }) => {
+                let (s, t) = infer_expr(ctx, expr)?;
+                ss.push(s);
+                spread_types.push(t);
+            }
+        }
+    }
+
+    let (s, children_type) = infer_jsx_children(ctx, children)?;
+    ss.push(s);
+    if let Some(t) = children_type {
+        if props.iter().any(|prop| prop.name == children_attr) {
+            return Err(format!(
+                "'{children_attr}' are specified twice, as an attribute and as JSX children"
+            ));
+        }
+        props.push(ctx.prop(children_attr, t, false));
+    }
+
+    let s = compose_many_subs(&ss);
+    if spread_types.is_empty() {
+        let t = ctx.object(props);
+        Ok((s, t))
+    } else {
+        let mut all_types = spread_types;
+        all_types.push(ctx.object(props));
+        let t = simplify_intersection(&all_types, ctx);
+        Ok((s, t))
+    }
+}
+
+// Infers the type of the `children` prop.  A single child is passed as is,
+// multiple children are passed as a tuple, and spread children, e.g.
+// `{...items}`, are spread into that tuple.  Returns `None` if there are no
+// children.
+fn infer_jsx_children(
+    ctx: &mut Context,
+    children: &[JSXElementChild],
+) -> Result<(Subst, Option<Type>), String> {
+    let mut ss: Vec<Subst> = vec![];
+    let mut ts: Vec<Type> = vec![];
+    for child in children {
+        let (s, t) = match child {
+            JSXElementChild::JSXText(JSXText { value, .. }) => {
+                // Whitespace containing a line break is removed when JSX is
+                // compiled so it isn't passed as a child.
+                if value.trim().is_empty() && value.contains('\n') {
+                    continue;
+                }
+                (Subst::default(), ctx.prim(Primitive::Str))
+            }
+            JSXElementChild::JSXExprContainer(JSXExprContainer { expr, .. }) => {
+                infer_expr(ctx, expr)?
+            }
+            JSXElementChild::JSXSpreadChild(JSXSpreadChild { expr, .. }) => {
+                let (s, t) = infer_expr(ctx, expr)?;
+                (s, ctx.rest(t))
+            }
+            JSXElementChild::JSXElement(elem) => infer_jsx_element(ctx, elem)?,
+            JSXElementChild::JSXFragment(frag) => infer_jsx_fragment(ctx, frag)?,
+        };
+        ss.push(s);
+        ts.push(t);
+    }
+
+    let s = compose_many_subs(&ss);
+    let t = match ts.as_slice() {
+        [] => None,
+        [t] => match &t.variant {
+            Variant::Rest(arg) => Some(arg.as_ref().to_owned()),
+            _ => Some(t.to_owned()),
+        },
+        _ => Some(ctx.tuple(ts)),
+    };
+
+    Ok((s, t))
+}
+
+fn jsx_element_name_to_expr(name: &JSXElementName) -> Expr {
+    match name {
+        JSXElementName::Ident(ident) => Expr::Ident(ident.to_owned()),
+        JSXElementName::Member(JSXMemberExpr { span, obj, prop }) => Expr::Member(Member {
+            span: span.to_owned(),
+            obj: Box::from(jsx_element_name_to_expr(obj)),
+            prop: MemberProp::Ident(prop.to_owned()),
+        }),
+    }
+}
+
+// Infers the type of a lambda.  If there's an `expected` type then the types
+// of its params are used as the types of the lambda's params.
+fn infer_lambda(
+    ctx: &mut Context,
+    lambda: &Lambda,
+    expected: Option<&types::LamType>,
+) -> Result<(Subst, Type), String> {
+    let Lambda {
+        params,
+        body,
+        is_async,
+        return_type: rt_type_ann,
+        type_params,
+        ..
+    } = lambda;
+
+    // Rest params can't be matched up with the lambda's params by position.
+    let expected_params: Vec<Type> = match expected {
+        Some(expected) => expected
+            .params
+            .iter()
+            .take_while(|param| !matches!(param.variant, Variant::Rest(_)))
+            .cloned()
+            .collect(),
+        None => vec![],
+    };
+
+    let mut new_ctx = ctx.clone();
+    new_ctx.is_async = is_async.to_owned();
+
+    let type_params_map: HashMap<String, Type> = match type_params {
+        Some(params) => params
+            .iter()
+            .map(|param| (param.name.name.to_owned(), new_ctx.fresh_var()))
+            .collect(),
+        None => HashMap::default(),
+    };
+
+    let params: Result<Vec<(Subst, Type)>, String> = params
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let (ps, pa, pt) = infer_pattern(param, &new_ctx, &type_params_map)?;
+
+            // Params are checked against the params of the expected type
+            // which gives params without type annotations their types.
+            let s = match expected_params.get(i) {
+                Some(expected_param) => unify(expected_param, &pt, &new_ctx)?,
+                None => Subst::default(),
+            };
+            let ps = compose_subs(&s, &ps);
+            let pa = pa.apply(&s);
+            let pt = pt.apply(&s);
+
+            // Inserts any new variables introduced by infer_pattern() into
+            // the current context.
+            for (name, scheme) in pa {
+                new_ctx.values.insert(name, scheme);
+            }
+
+            Ok((ps, pt))
+        })
+        .collect();
+
+    let (ss, ts): (Vec<_>, Vec<_>) = params?.iter().cloned().unzip();
+
+    let (rs, rt) = infer_expr(&mut new_ctx, body)?;
+
+    // Copies over the count from new_ctx so that it's unique across Contexts.
+    ctx.state.count.set(new_ctx.state.count.get());
+
+    let rt = if *is_async && !is_promise(&rt) {
+        ctx.alias("Promise", Some(vec![rt]))
+    } else {
+        rt
+    };
+
+    let s = match rt_type_ann {
+        Some(rt_type_ann) => unify(
+            &rt,
+            &infer_type_ann_with_params(rt_type_ann, ctx, &type_params_map)?,
+            ctx,
+        )?,
+        None => Subst::default(),
+    };
+    let t = ctx.lam(ts, Box::from(rt));
+    let s = compose_subs(&s, &compose_subs(&rs, &compose_many_subs(&ss)));
+    let t = t.apply(&s);
+
+    Ok((s, t))
+}
+
 fn infer_let(
     pat: &Pattern,
     init: &Expr,
@@ -456,28 +846,268 @@ fn infer_let(
     Ok((s, t))
 }
 
+fn infer_app(
+    ctx: &mut Context,
+    lam: &Expr,
+    args: &[ExprOrSpread],
+) -> Result<(Subst, Type), String> {
+    infer_call(ctx, lam, vec![], args)
+}
+
+// `leading_arg_types` are the types of args that are passed before `args` but
+// that don't appear in the source, e.g. the strings of a tagged template.
+fn infer_call(
+    ctx: &mut Context,
+    lam: &Expr,
+    leading_arg_types: Vec<Type>,
+    args: &[ExprOrSpread],
+) -> Result<(Subst, Type), String> {
+    let mut ss: Vec<Subst> = vec![];
+    let leading_count = leading_arg_types.len();
+    let mut arg_types: Vec<Type> = leading_arg_types;
+
+    let (s1, lam_type) = infer_expr(ctx, lam)?;
+    ss.push(s1);
+
+    // The params of the function being called are the expected types of the
+    // args.  Args after a spread or a rest param can't be matched up with the
+    // params so their types are inferred without an expected type.
+    let params: Vec<Type> = match &lam_type.variant {
+        Variant::Lam(types::LamType { params, .. }) => params
+            .iter()
+            .take_while(|param| !matches!(param.variant, Variant::Rest(_)))
+            .cloned()
+            .collect(),
+        _ => vec![],
+    };
+    let spread_index = args.iter().position(|arg| arg.spread.is_some());
+
+    // Context sensitive args are checked after the other args so that the
+    // type params in their expected types have been inferred from the other
+    // args, e.g. `T` in `map((x) => x.name, arr)` is inferred from `arr`.
+    let (deferred, args_in_order): (Vec<_>, Vec<_>) = args
+        .iter()
+        .enumerate()
+        .partition(|(_, arg)| arg.spread.is_none() && is_context_sensitive(&arg.expr));
+    let mut inferred_args: Vec<(usize, Type)> = vec![];
+    for (i, arg) in args_in_order.into_iter().chain(deferred) {
+        let param = match spread_index {
+            Some(spread_index) if i >= spread_index => None,
+            _ => params.get(leading_count + i),
+        };
+        let (arg_s, arg_t) = match param {
+            Some(param) => {
+                let param = param.apply(&compose_many_subs(&ss));
+                check_expr(ctx, arg.expr.as_ref(), &param)?
+            }
+            None => infer_expr(ctx, arg.expr.as_ref())?,
+        };
+        ss.push(arg_s);
+        inferred_args.push((i, arg_t));
+    }
+
+    inferred_args.sort_by_key(|(i, _)| *i);
+    for (i, arg_t) in inferred_args {
+        let arg = &args[i];
+        if arg.spread.is_some() {
+            match arg_t.variant {
+                Variant::Tuple(types) => arg_types.extend(types.to_owned()),
+                _ => arg_types.push(ctx.rest(arg_t))
+            }
+        } else {
+            arg_types.push(arg_t);
+        }
+    }
+
+    let ret_type = ctx.fresh_var();
+    // Are we missing an `apply()` call here?
+    // Maybe, I could see us needing an apply to handle generic functions properly
+    // s3       <- unify (apply s2 t1) (TArr t2 tv)
+    let call_type = Type {
+        id: ctx.fresh_id(),
+        frozen: false,
+        variant: Variant::Lam(types::LamType {
+            params: arg_types,
+            ret: Box::from(ret_type.clone()),
+            is_call: true,
+        }),
+    };
+    let s3 = unify(&call_type, &lam_type, ctx)?;
+
+    ss.push(s3);
+
+    let s = compose_many_subs(&ss);
+    let t = ret_type.apply(&s);
+
+    // return (s3 `compose` s2 `compose` s1, apply s3 tv)
+    Ok((s, t))
+}
+
+// Lambdas with params that don't have type annotations get the types of those
+// params from their expected type.
+fn is_context_sensitive(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lambda(Lambda { params, .. }) => params.iter().any(|param| match param {
+            Pattern::Ident(BindingIdent { type_ann, .. })
+            | Pattern::Rest(RestPat { type_ann, .. })
+            | Pattern::Object(ObjectPat { type_ann, .. })
+            | Pattern::Array(ArrayPat { type_ann, .. }) => type_ann.is_none(),
+            Pattern::Lit(_) | Pattern::Is(_) => false,
+        }),
+        _ => false,
+    }
+}
+
+// Conditions can be of any type since they're checked for truthiness, but
+// type variables are still inferred to be booleans.
+fn unify_cond(t: &Type, ctx: &Context) -> Result<Subst, String> {
+    match &t.variant {
+        Variant::Var => unify(t, &ctx.prim(Primitive::Bool), ctx),
+        _ => Ok(Subst::default()),
+    }
+}
+
+fn is_class(name: &str, ctx: &Context) -> bool {
+    matches!(
+        ctx.types.get(name).map(|scheme| &scheme.ty.variant),
+        Some(Variant::Class(_))
+    )
+}
+
 fn is_promise(ty: &Type) -> bool {
     matches!(&ty.variant, Variant::Alias(types::AliasType { name, .. }) if name == "Promise")
 }
 
-fn infer_property_type(
-    obj_t: &Type,
-    prop: &MemberProp,
-    ctx: &Context,
-) -> Result<(Subst, Type), String> {
+fn infer_property_type(obj_t: &Type, name: &str, ctx: &Context) -> Result<(Subst, Type), String> {
     match &obj_t.variant {
         Variant::Object(props) => {
-            let mem_t = ctx.mem(obj_t.clone(), &prop.name());
-            match props.iter().find(|p| p.name == prop.name()) {
+            let mem_t = ctx.mem(obj_t.clone(), name);
+            match props.iter().find(|p| p.name == name) {
                 Some(_) => Ok((Subst::default(), mem_t)),
                 None => Err(String::from("Record literal doesn't contain property")),
             }
         }
         Variant::Alias(alias) => {
             let t = lookup_alias(ctx, alias)?;
-            infer_property_type(&t, prop, ctx)
+            infer_property_type(&t, name, ctx)
+        }
+        Variant::Class(types::ClassType {
+            name: class_name,
+            props,
+            ..
+        }) => {
+            match props.iter().find(|p| p.name == name) {
+                Some(p) => Ok((Subst::default(), p.get_type(ctx))),
+                None => Err(format!(
+                    "Property '{name}' doesn't exist on class '{class_name}'"
+                )),
+            }
+        }
+        // There's no guarantee that a prop covered by an index signature
+        // exists so we include `undefined` in its type.
+        Variant::Index(index) if index_applies_to_prop(&index.key, name) => {
+            let t = ctx.union(vec![
+                index.value.as_ref().to_owned(),
+                ctx.prim(Primitive::Undefined),
+            ]);
+            Ok((Subst::default(), t))
+        }
+        Variant::Index(_) => Err(String::from("Record literal doesn't contain property")),
+        Variant::Prim(Primitive::Any) => Ok((Subst::default(), obj_t.to_owned())),
+        Variant::Prim(Primitive::Unknown) => Err(String::from("Object is of type 'unknown'")),
+        _ if is_type_op(obj_t) => match expand_type(obj_t, ctx)? {
+            t if &t != obj_t => infer_property_type(&t, name, ctx),
+            _ => Err(format!("Property '{name}' does not exist on type '{obj_t}'")),
+        },
+        // Interfaces with call signatures are intersections of the signatures
+        // and an object type containing the interface's props.  Object types
+        // with both props and an index signature are also intersections.
+        Variant::Intersection(types) => {
+            let obj = types.iter().find(|t| match &t.variant {
+                Variant::Object(props) => props.iter().any(|p| p.name == name),
+                _ => false,
+            });
+            let index = types.iter().find(|t| matches!(t.variant, Variant::Index(_)));
+            match obj.or(index) {
+                Some(t) => infer_property_type(t, name, ctx),
+                None => Err(String::from("Record literal doesn't contain property")),
+            }
+        }
+        _ => Err(format!("Property '{name}' does not exist on type '{obj_t}'")),
+    }
+}
+
+// Handles dynamic member access, e.g. `obj[key]`.  String literal keys are
+// treated the same as regular member access.
+fn infer_computed_property_type(
+    obj_t: &Type,
+    key_t: &Type,
+    ctx: &Context,
+) -> Result<(Subst, Type), String> {
+    match (&obj_t.variant, &key_t.variant) {
+        (Variant::Alias(alias), _) => {
+            let t = lookup_alias(ctx, alias)?;
+            infer_computed_property_type(&t, key_t, ctx)
+        }
+        (Variant::Prim(Primitive::Any), _) => Ok((Subst::default(), obj_t.to_owned())),
+        _ if is_type_op(obj_t) => match expand_type(obj_t, ctx)? {
+            t if &t != obj_t => infer_computed_property_type(&t, key_t, ctx),
+            _ => Err(format!("Type '{obj_t}' has no index signature")),
+        },
+        (Variant::Tuple(types), Variant::Lit(types::Lit::Num(value))) => {
+            match value.parse::<usize>().ok().and_then(|i| types.get(i)) {
+                Some(t) => Ok((Subst::default(), t.to_owned())),
+                None => Err(format!(
+                    "Tuple type '{obj_t}' has no element at index '{value}'"
+                )),
+            }
+        }
+        (Variant::Readonly(t), _) => infer_computed_property_type(t, key_t, ctx),
+        (Variant::Array(elem_t), _) => {
+            let elem_t = elem_t.as_ref().to_owned();
+            let s = unify(key_t, &ctx.prim(Primitive::Num), ctx)?;
+            let t = ctx.union(vec![elem_t, ctx.prim(Primitive::Undefined)]);
+            Ok((s, t))
+        }
+        (_, Variant::Lit(types::Lit::Str(name))) => infer_property_type(obj_t, name, ctx),
+        // Keys like `"x" | "y"` or `keyof Point` access multiple props.
+        (_, Variant::Union(keys))
+            if keys
+                .iter()
+                .all(|key| matches!(key.variant, Variant::Lit(types::Lit::Str(_)))) =>
+        {
+            let mut ss = vec![];
+            let mut ts = vec![];
+            for key in keys {
+                let (s, t) = infer_computed_property_type(obj_t, key, ctx)?;
+                ss.push(s);
+                ts.push(unwrap_member_type(&t, ctx));
+            }
+            Ok((compose_many_subs(&ss), ctx.union(ts)))
+        }
+        (_, Variant::Alias(alias)) => {
+            let key_t = lookup_alias(ctx, alias)?;
+            infer_computed_property_type(obj_t, &key_t, ctx)
+        }
+        _ if is_type_op(key_t) => match expand_type(key_t, ctx)? {
+            t if &t != key_t => infer_computed_property_type(obj_t, &t, ctx),
+            _ => Err(format!("Type '{key_t}' cannot be used as an index type")),
+        },
+        (Variant::Index(index), _) => {
+            let s = unify(key_t, &index.key, ctx)?;
+            let t = ctx.union(vec![
+                index.value.as_ref().to_owned(),
+                ctx.prim(Primitive::Undefined),
+            ]);
+            Ok((s, t))
+        }
+        (Variant::Intersection(types), _) => {
+            match types.iter().find(|t| matches!(t.variant, Variant::Index(_))) {
+                Some(t) => infer_computed_property_type(t, key_t, ctx),
+                None => Err(format!("Type '{obj_t}' has no index signature")),
+            }
         }
-        _ => todo!("Unhandled {obj_t} in infer_property_type"),
+        _ => Err(format!("Type '{obj_t}' has no index signature")),
     }
 }
 
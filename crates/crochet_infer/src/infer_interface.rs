@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use crochet_ast::*;
+
+use super::context::{lookup_alias, Context};
+use super::infer_type_ann::*;
+use super::types::{self, freeze_scheme, Scheme, Type, Variant};
+
+struct InterfaceDecl<'a> {
+    id: &'a Ident,
+    type_params: &'a Option<Vec<TypeParam>>,
+    extends: &'a [TypeRef],
+    members: &'a [TypeElem],
+}
+
+// Infers the types of all of the interfaces in the program and adds them to
+// `ctx.types`.  Multiple declarations of the same interface are merged the
+// same way TypeScript merges them.  The interfaces that an interface extends
+// are inferred first so that their members can be copied into it.
+pub fn infer_interfaces(ctx: &mut Context, prog: &Program) -> Result<(), String> {
+    let mut interfaces: Vec<(String, Vec<InterfaceDecl>)> = vec![];
+    for stmt in &prog.body {
+        if let Statement::InterfaceDecl {
+            id,
+            type_params,
+            extends,
+            members,
+            ..
+        } = stmt
+        {
+            let decl = InterfaceDecl {
+                id,
+                type_params,
+                extends,
+                members,
+            };
+            match interfaces.iter_mut().find(|(name, _)| name == &id.name) {
+                Some((_, decls)) => decls.push(decl),
+                None => interfaces.push((id.name.to_owned(), vec![decl])),
+            }
+        }
+    }
+
+    let mut done: HashSet<String> = HashSet::new();
+    for (name, _) in &interfaces {
+        infer_interface(ctx, name, &interfaces, &mut done, &mut vec![])?;
+    }
+
+    Ok(())
+}
+
+fn infer_interface(
+    ctx: &mut Context,
+    name: &str,
+    interfaces: &[(String, Vec<InterfaceDecl>)],
+    done: &mut HashSet<String>,
+    in_progress: &mut Vec<String>,
+) -> Result<(), String> {
+    if done.contains(name) {
+        return Ok(());
+    }
+    if in_progress.iter().any(|n| n == name) {
+        return Err(format!(
+            "Interface '{name}' recursively references itself as a base type"
+        ));
+    }
+    let decls = match interfaces.iter().find(|(n, _)| n == name) {
+        Some((_, decls)) => decls,
+        None => return Ok(()),
+    };
+
+    in_progress.push(name.to_owned());
+    for decl in decls {
+        for base in decl.extends {
+            infer_interface(ctx, &base.name, interfaces, done, in_progress)?;
+        }
+    }
+    in_progress.pop();
+
+    let type_params = decls[0].type_params;
+    let param_names = |type_params: &Option<Vec<TypeParam>>| -> Vec<String> {
+        type_params
+            .iter()
+            .flatten()
+            .map(|param| param.name.name.to_owned())
+            .collect()
+    };
+    if decls
+        .iter()
+        .any(|decl| param_names(decl.type_params) != param_names(type_params))
+    {
+        return Err(format!(
+            "All declarations of '{name}' must have identical type parameters"
+        ));
+    }
+
+    let type_ann = interface_type_ann(name, decls)?;
+    let scheme = infer_scheme_with_type_params(&type_ann, type_params, ctx)?;
+
+    // The bases and members are flattened into a single object type so that
+    // interfaces can be used anywhere an object type can.  Interfaces with
+    // call signatures or an index signature are the intersection of those
+    // signatures and the object.
+    let mut calls: Vec<Type> = vec![];
+    let mut props: Vec<types::TProp> = vec![];
+    let mut index: Option<Type> = None;
+    let types = match &scheme.ty.variant {
+        Variant::Intersection(types) => types.to_owned(),
+        _ => vec![scheme.ty.to_owned()],
+    };
+    for t in &types {
+        let (base_calls, base_props, base_index) = match &t.variant {
+            Variant::Alias(alias) => split_object_type(&expand_alias(ctx, alias)?)?,
+            _ => {
+                let parts = split_object_type(t)?;
+                check_repeated_props(name, &parts.1)?;
+                parts
+            }
+        };
+        calls.extend(base_calls);
+        index = base_index.or(index);
+        for prop in base_props {
+            props.retain(|p| p.name != prop.name);
+            props.push(prop);
+        }
+    }
+
+    let mut types = calls;
+    types.push(ctx.object(props));
+    types.extend(index);
+    let ty = match types.len() {
+        1 => types.remove(0),
+        _ => ctx.intersection(types),
+    };
+
+    ctx.types.insert(
+        name.to_owned(),
+        freeze_scheme(Scheme {
+            qualifiers: scheme.qualifiers,
+            ty,
+        }),
+    );
+    done.insert(name.to_owned());
+
+    Ok(())
+}
+
+type ObjectParts = (Vec<Type>, Vec<types::TProp>, Option<Type>);
+
+fn expand_alias(ctx: &Context, alias: &types::AliasType) -> Result<Type, String> {
+    let t = lookup_alias(ctx, alias)?;
+    match &t.variant {
+        Variant::Alias(alias) => expand_alias(ctx, alias),
+        _ => Ok(t),
+    }
+}
+
+// Splits an object type, or an intersection of call signatures, an object
+// type and an index signature, into its call signatures, props and index
+// signature.
+fn split_object_type(t: &Type) -> Result<ObjectParts, String> {
+    match &t.variant {
+        Variant::Object(props) => Ok((vec![], props.to_owned(), None)),
+        Variant::Index(_) => Ok((vec![], vec![], Some(t.to_owned()))),
+        Variant::Lam(_) => Ok((vec![t.to_owned()], vec![], None)),
+        Variant::Intersection(types) => {
+            let mut calls = vec![];
+            let mut props = vec![];
+            let mut index = None;
+            for t in types {
+                let (t_calls, t_props, t_index) = split_object_type(t)?;
+                calls.extend(t_calls);
+                props.extend(t_props);
+                index = t_index.or(index);
+            }
+            Ok((calls, props, index))
+        }
+        _ => Err(format!("An interface can only extend an object type, found {t}")),
+    }
+}
+
+// The declarations of an interface can repeat a prop as long as they all give
+// it the same type, e.g. `interface Point {x: number}` can be followed by
+// `interface Point {x: number, y: number}` but not `interface Point {x: string}`.
+fn check_repeated_props(name: &str, props: &[types::TProp]) -> Result<(), String> {
+    for (i, prop) in props.iter().enumerate() {
+        if props[..i].iter().any(|p| p.name == prop.name && p != prop) {
+            return Err(format!(
+                "Duplicate property '{}' in interface '{name}'",
+                prop.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Desugars all of the declarations of an interface into a single intersection
+// type containing its bases, its call signatures, and an object type with all
+// of its props and methods.  Methods with the same name are overloads so their
+// signatures are intersected, e.g. `{f(number): number, f(string): string}`
+// becomes `{f: ((number) => number) & ((string) => string)}`.
+fn interface_type_ann(name: &str, decls: &[InterfaceDecl]) -> Result<TypeAnn, String> {
+    let span = decls[0].id.span.to_owned();
+    let mut types: Vec<TypeAnn> = vec![];
+    let mut calls: Vec<TypeAnn> = vec![];
+    let mut props: Vec<TProp> = vec![];
+    let mut index: Option<Box<TIndex>> = None;
+    let mut methods: Vec<(TMethod, Vec<TypeAnn>)> = vec![];
+
+    for decl in decls {
+        types.extend(decl.extends.iter().cloned().map(TypeAnn::TypeRef));
+
+        for member in decl.members {
+            let key = match member {
+                TypeElem::Prop(prop) => &prop.name,
+                TypeElem::Method(method) => &method.name,
+                TypeElem::Call(lam) => {
+                    calls.push(TypeAnn::Lam(lam.to_owned()));
+                    continue;
+                }
+                TypeElem::Index(elem) => {
+                    if index.is_some() {
+                        return Err(format!("Duplicate index signature in interface '{name}'"));
+                    }
+                    index = Some(Box::from(elem.to_owned()));
+                    continue;
+                }
+            };
+            let is_prop = props.iter().any(|prop| &prop.name == key);
+            let method = methods.iter_mut().find(|(method, _)| &method.name == key);
+
+            match (member, method) {
+                (TypeElem::Method(method), Some((_, overloads))) if !is_prop => {
+                    overloads.push(TypeAnn::Lam(method.lam.to_owned()))
+                }
+                // Repeated props are checked by `check_repeated_props` once
+                // their types have been inferred.
+                (TypeElem::Prop(prop), None) => props.push(prop.to_owned()),
+                (_, Some(_)) => return Err(format!("Duplicate property '{key}' in interface '{name}'")),
+                (_, None) if is_prop => {
+                    return Err(format!("Duplicate property '{key}' in interface '{name}'"))
+                }
+                (TypeElem::Method(method), None) => methods.push((
+                    method.to_owned(),
+                    vec![TypeAnn::Lam(method.lam.to_owned())],
+                )),
+                (TypeElem::Call(_) | TypeElem::Index(_), None) => unreachable!(),
+            }
+        }
+    }
+
+    for (method, mut overloads) in methods {
+        let type_ann = match overloads.len() {
+            1 => overloads.remove(0),
+            _ => TypeAnn::Intersection(IntersectionType {
+                span: method.span.to_owned(),
+                types: overloads,
+            }),
+        };
+        props.push(TProp {
+            span: method.span,
+            name: method.name,
+            optional: method.optional,
+            readonly: false,
+            type_ann: Box::from(type_ann),
+        });
+    }
+
+    types.extend(calls);
+    types.push(TypeAnn::Object(ObjectType {
+        span: span.clone(),
+        props,
+        index,
+    }));
+
+    Ok(TypeAnn::Intersection(IntersectionType { span, types }))
+}
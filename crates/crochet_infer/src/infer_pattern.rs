@@ -2,11 +2,11 @@ use std::collections::HashMap;
 
 use crochet_ast::*;
 
-use super::context::Context;
-use super::infer_expr::infer_expr;
+use super::context::{lookup_alias, Context};
+use super::infer_expr::{check_expr, infer_expr};
 use super::infer_type_ann::*;
 use super::substitutable::{Subst, Substitutable};
-use super::types::{self, Scheme, Type};
+use super::types::{self, Scheme, Type, Variant};
 use super::unify::unify;
 use super::util::*;
 
@@ -29,7 +29,7 @@ pub fn infer_pattern(
     // type annotation.
     match get_type_ann(pat) {
         Some(type_ann) => {
-            let type_ann_ty = infer_type_ann_with_params(&type_ann, ctx, type_param_map);
+            let type_ann_ty = infer_type_ann_with_params(&type_ann, ctx, type_param_map)?;
 
             // Allowing type_ann_ty to be a subtype of pat_type because
             // only non-refutable patterns can have type annotations.
@@ -85,7 +85,7 @@ fn infer_pattern_rec(pat: &Pattern, ctx: &Context, assump: &mut Assump) -> Resul
         }
         Pattern::Rest(RestPat { arg, .. }) => {
             let t = match get_type_ann(arg) {
-                Some(type_ann) => infer_type_ann(&type_ann, ctx),
+                Some(type_ann) => infer_type_ann(&type_ann, ctx)?,
                 None => {
                     // TODO: wrap this in an array type
                     infer_pattern_rec(arg.as_ref(), ctx, assump)?
@@ -129,6 +129,7 @@ fn infer_pattern_rec(pat: &Pattern, ctx: &Context, assump: &mut Assump) -> Resul
                             Some(types::TProp {
                                 name: key.name.to_owned(),
                                 optional: false,
+                                readonly: false,
                                 ty: value_type,
                             })
                         }
@@ -146,6 +147,7 @@ fn infer_pattern_rec(pat: &Pattern, ctx: &Context, assump: &mut Assump) -> Resul
                             Some(types::TProp {
                                 name: key.name.to_owned(),
                                 optional: false,
+                                readonly: false,
                                 ty: tv,
                             })
                         }
@@ -189,13 +191,22 @@ pub fn infer_pattern_and_init(
     let type_param_map = HashMap::new();
     let (ps, pa, pt) = infer_pattern(pat, ctx, &type_param_map)?;
 
-    let (is, it) = infer_expr(ctx, init)?;
+    // Type annotations on the pattern are the expected type of the initializer.
+    let (is, it) = match (pu, get_type_ann(pat)) {
+        (PatternUsage::Assign, Some(_)) => check_expr(ctx, init, &pt)?,
+        _ => infer_expr(ctx, init)?,
+    };
 
     // Unifies initializer and pattern.
     let s = match pu {
         // Assign: The inferred type of the init value must be a sub-type
         // of the pattern it's being assigned to.
-        PatternUsage::Assign => unify(&it, &pt, ctx)?,
+        PatternUsage::Assign => match (pat, get_type_ann(pat)) {
+            (Pattern::Array(_) | Pattern::Object(_), None) => {
+                unify(&strip_readonly(&it, ctx)?, &pt, ctx)?
+            }
+            _ => unify(&it, &pt, ctx)?,
+        },
         // Matching: The pattern must be a sub-type of the expression
         // it's being matched against
         PatternUsage::Match => unify(&pt, &it, ctx)?,
@@ -208,3 +219,28 @@ pub fn infer_pattern_and_init(
 
     Ok((t, s))
 }
+
+// Destructuring only reads from the value being destructured so readonly
+// arrays and tuples can be destructured into the types of patterns which are
+// mutable, e.g. `let [x, y] = pair` where `pair` is `readonly [number, string]`.
+fn strip_readonly(t: &Type, ctx: &Context) -> Result<Type, String> {
+    match &t.variant {
+        Variant::Readonly(t) => strip_readonly(t, ctx),
+        Variant::Alias(alias) => {
+            let alias_t = lookup_alias(ctx, alias)?;
+            match alias_t.variant {
+                Variant::Readonly(_) | Variant::Tuple(_) | Variant::Array(_) => {
+                    strip_readonly(&alias_t, ctx)
+                }
+                _ => Ok(t.to_owned()),
+            }
+        }
+        Variant::Tuple(types) => {
+            let types: Result<Vec<_>, String> =
+                types.iter().map(|t| strip_readonly(t, ctx)).collect();
+            Ok(ctx.tuple(types?))
+        }
+        Variant::Array(elem_t) => Ok(ctx.array(strip_readonly(elem_t, ctx)?)),
+        _ => Ok(t.to_owned()),
+    }
+}
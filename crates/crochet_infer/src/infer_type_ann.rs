@@ -3,15 +3,17 @@ use std::iter::Iterator;
 
 use crochet_ast::*;
 
-use super::context::Context;
-use super::types::{freeze, Scheme, TProp, Type};
+use super::context::{lookup_alias, Context};
+use super::types::{self, freeze, IndexType, Scheme, TProp, Type, Variant};
+use super::unify::unify;
+use super::util::{expand_type, index_applies_to_prop, is_type_op, template_literal_type};
 
-pub fn infer_scheme(type_ann: &TypeAnn, ctx: &Context) -> Scheme {
+pub fn infer_scheme(type_ann: &TypeAnn, ctx: &Context) -> Result<Scheme, String> {
     match type_ann {
         TypeAnn::Lam(LamType { type_params, .. }) => {
             infer_scheme_with_type_params(type_ann, type_params, ctx)
         }
-        _ => Scheme::from(infer_type_ann(type_ann, ctx)),
+        _ => Ok(Scheme::from(infer_type_ann(type_ann, ctx)?)),
     }
 }
 
@@ -19,104 +21,442 @@ pub fn infer_scheme_with_type_params(
     type_ann: &TypeAnn,
     type_params: &Option<Vec<TypeParam>>,
     ctx: &Context,
-) -> Scheme {
+) -> Result<Scheme, String> {
     // NOTE: There's a scoping issue when using this mapping hash map.
     // <T>(arg: T, cb: <T>(T) => T) => T
     // The <T> type param list for `cb` shadows the outer `T`
-    let type_param_map: HashMap<String, Type> = match type_params {
+    //
+    // The type vars are kept in a Vec as well so that the order of the
+    // qualifiers matches the order of the type params.  `lookup_alias`
+    // relies on this when replacing qualifiers with type args.
+    let type_vars: Vec<(String, Type)> = match type_params {
         Some(params) => params
             .iter()
             .map(|param| (param.name.name.to_owned(), ctx.fresh_var()))
             .collect(),
-        None => HashMap::default(),
+        None => vec![],
     };
+    let type_param_map: HashMap<String, Type> = type_vars.iter().cloned().collect();
 
     // Infers the type from type annotation and replaces all type references whose names
     // appear in `mapping` with a type variable whose `id` is the value in the mapping.
-    let type_ann_ty = infer_type_ann_with_params(type_ann, ctx, &type_param_map);
+    let type_ann_ty = infer_type_ann_with_params(type_ann, ctx, &type_param_map)?;
 
     // Creates a Scheme with the correct qualifiers for the type references that were
     // replaced with type variables.
-    Scheme {
-        qualifiers: type_param_map.values().map(|tv| tv.id).collect(),
+    Ok(Scheme {
+        qualifiers: type_vars.iter().map(|(_, tv)| tv.id).collect(),
         ty: type_ann_ty,
-    }
+    })
 }
 
-pub fn infer_type_ann(type_ann: &TypeAnn, ctx: &Context) -> Type {
-    let type_ann_ty = infer_type_ann_rec(type_ann, ctx, &HashMap::default());
-    freeze(type_ann_ty)
+pub fn infer_type_ann(type_ann: &TypeAnn, ctx: &Context) -> Result<Type, String> {
+    let type_ann_ty = infer_type_ann_rec(type_ann, ctx, &HashMap::default())?;
+    Ok(freeze(type_ann_ty))
 }
 
 pub fn infer_type_ann_with_params(
     type_ann: &TypeAnn,
     ctx: &Context,
     type_param_map: &HashMap<String, Type>,
-) -> Type {
-    freeze(infer_type_ann_rec(type_ann, ctx, type_param_map))
+) -> Result<Type, String> {
+    Ok(freeze(infer_type_ann_rec(type_ann, ctx, type_param_map)?))
 }
 
 fn infer_type_ann_rec(
     type_ann: &TypeAnn,
     ctx: &Context,
     type_param_map: &HashMap<String, Type>,
-) -> Type {
+) -> Result<Type, String> {
     match type_ann {
-        TypeAnn::Lam(LamType { params, ret, .. }) => {
-            let params: Vec<_> = params
+        TypeAnn::Lam(LamType {
+            params,
+            ret,
+            type_params,
+            ..
+        }) => {
+            // Type params on lambdas that aren't at the top-level of a type
+            // annotation are added to the current scope so that references
+            // to them are valid.
+            // TODO: generalize nested lambdas with type params
+            let mut type_param_map = type_param_map.to_owned();
+            if let Some(type_params) = type_params {
+                for param in type_params {
+                    if !type_param_map.contains_key(&param.name.name) {
+                        type_param_map.insert(param.name.name.to_owned(), ctx.fresh_var());
+                    }
+                }
+            }
+
+            let params: Result<Vec<_>, String> = params
                 .iter()
-                .map(|arg| infer_type_ann_rec(arg, ctx, type_param_map))
+                .map(|param| infer_type_ann_rec(&param.type_ann, ctx, &type_param_map))
                 .collect();
-            let ret = Box::from(infer_type_ann_rec(ret.as_ref(), ctx, type_param_map));
-            ctx.lam(params, ret)
+            let ret = Box::from(infer_type_ann_rec(ret.as_ref(), ctx, &type_param_map)?);
+            Ok(ctx.lam(params?, ret))
         }
-        TypeAnn::Lit(LitType { lit, .. }) => ctx.lit(lit.to_owned()),
-        TypeAnn::Prim(PrimType { prim, .. }) => ctx.prim(prim.to_owned()),
-        TypeAnn::Object(ObjectType { props, .. }) => {
-            let props: Vec<_> = props
+        TypeAnn::Lit(LitType { lit, .. }) => Ok(ctx.lit(lit.to_owned())),
+        TypeAnn::Prim(PrimType { prim, .. }) => Ok(ctx.prim(prim.to_owned())),
+        TypeAnn::Object(ObjectType { props, index, .. }) => {
+            let props: Result<Vec<_>, String> = props
                 .iter()
-                .map(|prop| TProp {
-                    name: prop.name.to_owned(),
-                    optional: prop.optional,
-                    ty: infer_type_ann_rec(prop.type_ann.as_ref(), ctx, type_param_map),
+                .map(|prop| {
+                    Ok(TProp {
+                        name: prop.name.to_owned(),
+                        optional: prop.optional,
+                        readonly: prop.readonly,
+                        ty: infer_type_ann_rec(prop.type_ann.as_ref(), ctx, type_param_map)?,
+                    })
                 })
                 .collect();
-            ctx.object(props)
+            let props = props?;
+
+            match index {
+                Some(index) => {
+                    let index = infer_index(index, ctx, type_param_map)?;
+                    check_props_conform_to_index(&props, &index, ctx)?;
+                    match props.is_empty() {
+                        true => Ok(index),
+                        false => Ok(ctx.intersection(vec![ctx.object(props), index])),
+                    }
+                }
+                None => Ok(ctx.object(props)),
+            }
         }
         TypeAnn::TypeRef(TypeRef {
             name, type_params, ..
         }) => match type_param_map.get(name) {
-            Some(tv) => tv.to_owned(),
+            Some(tv) => match type_params {
+                Some(_) => Err(format!("Type parameter '{name}' is not generic")),
+                None => Ok(tv.to_owned()),
+            },
             None => {
-                let type_params = type_params.clone().map(|params| {
-                    params
-                        .iter()
-                        .map(|param| infer_type_ann_rec(param, ctx, type_param_map))
-                        .collect()
-                });
-                ctx.alias(name, type_params)
+                let scheme = ctx
+                    .types
+                    .get(name)
+                    .ok_or(format!("Can't find type '{name}'"))?;
+
+                let expected = scheme.qualifiers.len();
+                let actual = type_params.as_ref().map_or(0, |params| params.len());
+                if expected != actual {
+                    return Err(format!(
+                        "Type '{name}' expects {expected} type argument(s) but was given {actual}"
+                    ));
+                }
+
+                let type_params = match type_params {
+                    Some(params) => {
+                        let params: Result<Vec<_>, String> = params
+                            .iter()
+                            .map(|param| infer_type_ann_rec(param, ctx, type_param_map))
+                            .collect();
+                        Some(params?)
+                    }
+                    None => None,
+                };
+                Ok(ctx.alias(name, type_params))
             }
         },
-        TypeAnn::Union(UnionType { types, .. }) => ctx.union(
-            types
+        TypeAnn::Union(UnionType { types, .. }) => {
+            let types: Result<Vec<_>, String> = types
                 .iter()
                 .map(|t| infer_type_ann_rec(t, ctx, type_param_map))
-                .collect(),
-        ),
-        TypeAnn::Intersection(IntersectionType { types, .. }) => ctx.intersection(
-            types
+                .collect();
+            Ok(ctx.union(types?))
+        }
+        TypeAnn::Intersection(IntersectionType { types, .. }) => {
+            let types: Result<Vec<_>, String> = types
                 .iter()
                 .map(|t| infer_type_ann_rec(t, ctx, type_param_map))
-                .collect(),
-        ),
-        TypeAnn::Tuple(TupleType { types, .. }) => ctx.tuple(
-            types
+                .collect();
+            Ok(ctx.intersection(types?))
+        }
+        TypeAnn::Tuple(TupleType { types, .. }) => {
+            let types: Result<Vec<_>, String> = types
                 .iter()
                 .map(|t| infer_type_ann_rec(t, ctx, type_param_map))
-                .collect(),
-        ),
-        TypeAnn::Array(ArrayType { elem_type, .. }) => {
-            ctx.array(infer_type_ann_rec(elem_type, ctx, type_param_map))
+                .collect();
+            Ok(ctx.tuple(types?))
+        }
+        TypeAnn::Array(ArrayType { elem_type, .. }) => Ok(ctx.array(infer_type_ann_rec(
+            elem_type,
+            ctx,
+            type_param_map,
+        )?)),
+        TypeAnn::Readonly(ReadonlyType { type_ann, .. }) => match type_ann.as_ref() {
+            TypeAnn::Array(_) | TypeAnn::Tuple(_) => Ok(ctx.readonly(infer_type_ann_rec(
+                type_ann,
+                ctx,
+                type_param_map,
+            )?)),
+            _ => Err(String::from(
+                "'readonly' type modifier is only permitted on array and tuple types",
+            )),
+        },
+        TypeAnn::KeyOf(KeyOfType { type_ann, .. }) => {
+            let operand = infer_type_ann_rec(type_ann, ctx, type_param_map)?;
+            let t = ctx.keyof(operand.to_owned());
+            // Reports errors for types without keys, e.g. `keyof number`,
+            // even though the result is evaluated lazily.
+            if !is_query(&operand) {
+                expand_type(&t, ctx)?;
+            }
+            Ok(t)
+        }
+        // Type declarations are inferred before values so `typeof x` is
+        // evaluated lazily if `x` hasn't been inferred yet.
+        TypeAnn::Query(QueryType { name, .. }) => match ctx.values.contains_key(&name.name) {
+            true => ctx.lookup_value(&name.name),
+            false => Ok(ctx.query(&name.name)),
+        },
+        TypeAnn::IndexedAccess(IndexedAccessType {
+            obj_type,
+            index_type,
+            ..
+        }) => {
+            let obj = infer_type_ann_rec(obj_type, ctx, type_param_map)?;
+            let index = infer_type_ann_rec(index_type, ctx, type_param_map)?;
+            let t = infer_indexed_access(&obj, &index, ctx)?;
+            // Reports errors for props that don't exist, e.g. `Point["z"]`,
+            // even though the result is evaluated lazily.
+            if !is_query(&obj) {
+                expand_type(&t, ctx)?;
+            }
+            Ok(t)
+        }
+        TypeAnn::Conditional(ConditionalType {
+            check_type,
+            extends_type,
+            true_type,
+            false_type,
+            ..
+        }) => {
+            let check = infer_type_ann_rec(check_type, ctx, type_param_map)?;
+
+            // Types declared with `infer` in `extends_type` can be referenced
+            // in `true_type`.  The declaration and the references share the
+            // same id so that they're replaced together.
+            let mut extends_map = type_param_map.to_owned();
+            let mut true_map = type_param_map.to_owned();
+            for name in infer_names(extends_type) {
+                let id = ctx.fresh_id();
+                let infer_t = Type {
+                    id,
+                    frozen: false,
+                    variant: Variant::Infer(name.to_owned()),
+                };
+                let bound_t = Type {
+                    id,
+                    frozen: false,
+                    variant: Variant::Bound(name.to_owned()),
+                };
+                extends_map.insert(name.to_owned(), infer_t);
+                true_map.insert(name, bound_t);
+            }
+
+            let extends = infer_type_ann_rec(extends_type, ctx, &extends_map)?;
+            let true_type = infer_type_ann_rec(true_type, ctx, &true_map)?;
+            let false_type = infer_type_ann_rec(false_type, ctx, type_param_map)?;
+            Ok(ctx.conditional(check, extends, true_type, false_type))
+        }
+        TypeAnn::Infer(InferType { name, .. }) => match type_param_map.get(&name.name) {
+            Some(t) if matches!(t.variant, Variant::Infer(_)) => Ok(t.to_owned()),
+            _ => Err(String::from(
+                "'infer' declarations are only permitted in the 'extends' clause of a conditional type",
+            )),
+        },
+        TypeAnn::Mapped(MappedType {
+            type_param,
+            constraint,
+            optional,
+            readonly,
+            type_ann,
+            ..
+        }) => {
+            let constraint = infer_type_ann_rec(constraint, ctx, type_param_map)?;
+            let key = Type {
+                id: ctx.fresh_id(),
+                frozen: false,
+                variant: Variant::Bound(type_param.name.to_owned()),
+            };
+            let mut value_map = type_param_map.to_owned();
+            value_map.insert(type_param.name.to_owned(), key.to_owned());
+            let value = infer_type_ann_rec(type_ann, ctx, &value_map)?;
+            Ok(ctx.mapped(
+                key,
+                constraint,
+                value,
+                optional.to_owned(),
+                readonly.to_owned(),
+            ))
+        }
+        TypeAnn::TemplateLiteral(TemplateLiteralType { quasis, types, .. }) => {
+            let quasis: Vec<_> = quasis
+                .iter()
+                .map(|quasi| match &quasi.cooked {
+                    Lit::Str(Str { value, .. }) => value.to_owned(),
+                    _ => panic!("quasi.cooked must be a string"),
+                })
+                .collect();
+            let types: Result<Vec<_>, String> = types
+                .iter()
+                .map(|t| infer_type_ann_rec(t, ctx, type_param_map))
+                .collect();
+            Ok(template_literal_type(quasis, types?, ctx))
+        }
+        TypeAnn::Rest(RestType { type_ann, .. }) => {
+            let t = infer_type_ann_rec(type_ann, ctx, type_param_map)?;
+            let expanded_t = match &t.variant {
+                Variant::Alias(alias) => lookup_alias(ctx, alias)?,
+                _ => t.to_owned(),
+            };
+            let elem_t = match &expanded_t.variant {
+                Variant::Readonly(t) => t.as_ref(),
+                _ => &expanded_t,
+            };
+            // `infer` types in rest elements are bound to the rest of the tuple
+            // they're matched against, e.g. `[any, ...infer R]`.
+            match &elem_t.variant {
+                Variant::Array(_)
+                | Variant::Tuple(_)
+                | Variant::Var
+                | Variant::Infer(_)
+                | Variant::Bound(_) => Ok(ctx.rest(t)),
+                _ => Err(format!("A rest element type must be an array type, found '{t}'")),
+            }
+        }
+    }
+}
+
+// Returns the names of the types declared with `infer` in the `extends` clause
+// of a conditional type.  `infer` declarations in nested conditional types
+// belong to those conditional types so they're skipped.
+fn infer_names(type_ann: &TypeAnn) -> Vec<String> {
+    match type_ann {
+        TypeAnn::Infer(InferType { name, .. }) => vec![name.name.to_owned()],
+        TypeAnn::Lam(LamType { params, ret, .. }) => {
+            let mut names: Vec<_> = params
+                .iter()
+                .flat_map(|param| infer_names(&param.type_ann))
+                .collect();
+            names.extend(infer_names(ret));
+            names
+        }
+        TypeAnn::Object(ObjectType { props, index, .. }) => {
+            let mut names: Vec<_> = props
+                .iter()
+                .flat_map(|prop| infer_names(&prop.type_ann))
+                .collect();
+            if let Some(index) = index {
+                names.extend(infer_names(&index.type_ann));
+            }
+            names
+        }
+        TypeAnn::TypeRef(TypeRef { type_params, .. }) => match type_params {
+            Some(type_params) => type_params.iter().flat_map(infer_names).collect(),
+            None => vec![],
+        },
+        TypeAnn::Union(UnionType { types, .. })
+        | TypeAnn::Intersection(IntersectionType { types, .. })
+        | TypeAnn::Tuple(TupleType { types, .. }) => types.iter().flat_map(infer_names).collect(),
+        TypeAnn::Array(ArrayType { elem_type, .. }) => infer_names(elem_type),
+        TypeAnn::Readonly(ReadonlyType { type_ann, .. })
+        | TypeAnn::KeyOf(KeyOfType { type_ann, .. })
+        | TypeAnn::Rest(RestType { type_ann, .. }) => infer_names(type_ann),
+        TypeAnn::IndexedAccess(IndexedAccessType {
+            obj_type,
+            index_type,
+            ..
+        }) => {
+            let mut names = infer_names(obj_type);
+            names.extend(infer_names(index_type));
+            names
+        }
+        TypeAnn::Mapped(MappedType { type_ann, .. }) => infer_names(type_ann),
+        TypeAnn::TemplateLiteral(TemplateLiteralType { types, .. }) => {
+            types.iter().flat_map(infer_names).collect()
+        }
+        TypeAnn::Lit(_) | TypeAnn::Prim(_) | TypeAnn::Query(_) | TypeAnn::Conditional(_) => {
+            vec![]
+        }
+    }
+}
+
+// Literal index types, or unions of them, are converted to member types which
+// are keyed by the prop's name, e.g. `T["a" | "b"]` is the same as
+// `T["a"] | T["b"]`.
+fn infer_indexed_access(obj: &Type, index: &Type, ctx: &Context) -> Result<Type, String> {
+    match &index.variant {
+        Variant::Lit(types::Lit::Str(name) | types::Lit::Num(name)) => {
+            Ok(ctx.mem(obj.to_owned(), name))
+        }
+        Variant::Union(types) => {
+            let types: Result<Vec<_>, String> = types
+                .iter()
+                .map(|index| infer_indexed_access(obj, index, ctx))
+                .collect();
+            Ok(ctx.union(types?))
+        }
+        Variant::Alias(alias) => infer_indexed_access(obj, &lookup_alias(ctx, alias)?, ctx),
+        // These are evaluated once the index or the object's type is known.
+        Variant::Var
+        | Variant::Bound(_)
+        | Variant::Query(_)
+        | Variant::Prim(Primitive::Str | Primitive::Num) => {
+            Ok(ctx.indexed_access(obj.to_owned(), index.to_owned()))
+        }
+        _ if is_type_op(index) => match expand_type(index, ctx)? {
+            expanded_index if &expanded_index != index => {
+                infer_indexed_access(obj, &expanded_index, ctx)
+            }
+            _ => Ok(ctx.indexed_access(obj.to_owned(), index.to_owned())),
+        },
+        _ => Err(format!("Type '{index}' cannot be used as an index type")),
+    }
+}
+
+// The values that `typeof x` refers to aren't known when type declarations are
+// inferred so type operators that use them aren't checked until they're used.
+fn is_query(t: &Type) -> bool {
+    matches!(t.variant, Variant::Query(_))
+}
+
+fn infer_index(
+    index: &TIndex,
+    ctx: &Context,
+    type_param_map: &HashMap<String, Type>,
+) -> Result<Type, String> {
+    let key = infer_type_ann_rec(&index.key_type, ctx, type_param_map)?;
+    if !matches!(
+        key.variant,
+        Variant::Prim(Primitive::Str) | Variant::Prim(Primitive::Num)
+    ) {
+        return Err(String::from(
+            "An index signature parameter type must be 'string' or 'number'",
+        ));
+    }
+    let value = infer_type_ann_rec(&index.type_ann, ctx, type_param_map)?;
+    Ok(ctx.index(key, value, index.readonly))
+}
+
+// The types of named props must be assignable to the type of the index
+// signature in the same object type, e.g. `{name: string, [key: string]: number}`
+// is an error.  Only props whose names are numbers are checked against
+// `number` index signatures.
+fn check_props_conform_to_index(props: &[TProp], index: &Type, ctx: &Context) -> Result<(), String> {
+    if let Variant::Index(IndexType { key, value, .. }) = &index.variant {
+        for prop in props {
+            if !index_applies_to_prop(key, &prop.name) {
+                continue;
+            }
+            let prop_t = prop.get_type(ctx);
+            if unify(&prop_t, value, ctx).is_err() {
+                return Err(format!(
+                    "Property '{}' of type '{prop_t}' is not assignable to '{key}' index type '{value}'",
+                    prop.name
+                ));
+            }
         }
     }
+    Ok(())
 }
@@ -1,7 +1,10 @@
 mod context;
+mod infer_class;
 mod infer_expr;
+mod infer_interface;
 mod infer_pattern;
 mod infer_type_ann;
+mod narrow;
 mod substitutable;
 mod unify;
 mod util;
@@ -15,20 +18,23 @@ pub use infer::*;
 #[cfg(test)]
 mod tests {
     use chumsky::prelude::*;
+    use crochet_ast::{FileId, SourceFile};
     use crochet_parser::*;
 
     use super::*;
 
     fn infer(input: &str) -> String {
         let mut ctx = Context::default();
-        let expr = expr_parser().parse(input).unwrap();
+        let file = SourceFile::new(FileId(0), "input.crochet", input);
+        let expr = expr_parser().parse(stream(&file)).unwrap();
         let scheme = infer::infer_expr(&mut ctx, &expr).unwrap();
         println!("scheme = {:#?}", scheme);
         format!("{scheme}")
     }
 
     fn infer_prog(input: &str) -> Context {
-        let prog = parser().parse(input).unwrap();
+        let file = SourceFile::new(FileId(0), "input.crochet", input);
+        let prog = parse(&file).unwrap();
         infer::infer_prog(&prog).unwrap()
     }
 
@@ -521,8 +527,8 @@ mod tests {
         assert_eq!(get_type("sum", &ctx), "number");
 
         // Ensures we aren't polluting the outside context
-        assert!(ctx.values.get("x").is_none());
-        assert!(ctx.values.get("y").is_none());
+        assert!(!ctx.values.contains_key("x"));
+        assert!(!ctx.values.contains_key("y"));
     }
 
     #[test]
@@ -569,8 +575,8 @@ mod tests {
         assert_eq!(get_type("sum", &ctx), "5 | 1 | 0");
 
         // Ensures we aren't polluting the outside context
-        assert!(ctx.values.get("x").is_none());
-        assert!(ctx.values.get("y").is_none());
+        assert!(!ctx.values.contains_key("x"));
+        assert!(!ctx.values.contains_key("y"));
     }
 
     #[test]
@@ -606,7 +612,7 @@ mod tests {
         assert_eq!(get_type("sum", &ctx), "number");
 
         // Ensures we aren't polluting the outside context
-        assert!(ctx.values.get("x").is_none());
+        assert!(!ctx.values.contains_key("x"));
     }
 
     #[test]
@@ -627,8 +633,8 @@ mod tests {
         assert_eq!(get_type("result", &ctx), "number | string | true");
 
         // Ensures we aren't polluting the outside context
-        assert!(ctx.values.get("x").is_none());
-        assert!(ctx.values.get("y").is_none());
+        assert!(!ctx.values.contains_key("x"));
+        assert!(!ctx.values.contains_key("y"));
     }
 
     #[test]
@@ -660,7 +666,7 @@ mod tests {
         assert_eq!(get_type("sum", &ctx), "number");
 
         // Ensures we aren't polluting the outside context
-        assert!(ctx.values.get("x").is_none());
+        assert!(!ctx.values.contains_key("x"));
     }
 
     #[test]
@@ -715,7 +721,7 @@ mod tests {
         assert_eq!(get_type("result", &ctx), "number");
 
         // Ensures we aren't polluting the outside context
-        assert!(ctx.values.get("x").is_none());
+        assert!(!ctx.values.contains_key("x"));
     }
 
     #[test]
@@ -736,7 +742,7 @@ mod tests {
         assert_eq!(get_type("result", &ctx), "string | number | true");
 
         // Ensures we aren't polluting the outside context
-        assert!(ctx.values.get("x").is_none());
+        assert!(!ctx.values.contains_key("x"));
     }
 
     #[test]
@@ -785,8 +791,8 @@ mod tests {
         assert_eq!(get_type("result", &ctx), "number");
 
         // Ensures we aren't polluting the outside context
-        assert!(ctx.values.get("x").is_none());
-        assert!(ctx.values.get("y").is_none());
+        assert!(!ctx.values.contains_key("x"));
+        assert!(!ctx.values.contains_key("y"));
     }
 
     // TODO: handle refutable patterns in if-else
@@ -1617,6 +1623,18 @@ mod tests {
         );
     }
 
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn promise_type_args_are_checked_after_expanding_aliases() {
+        let src = r#"
+        type StrPromise = Promise<string>
+        declare let p: Promise<number>
+        let q: StrPromise = p
+        "#;
+
+        infer_prog(src);
+    }
+
     #[test]
     #[should_panic = "Can't use `await` inside non-async lambda"]
     fn await_only_works_in_async_functions() {
@@ -1669,7 +1687,7 @@ mod tests {
         "#;
         let ctx = infer_prog(src);
 
-        assert_eq!(get_type("elem", &ctx), "JSXElement");
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
     }
 
     #[test]
@@ -1680,7 +1698,7 @@ mod tests {
         "#;
         let ctx = infer_prog(src);
 
-        assert_eq!(get_type("elem", &ctx), "JSXElement");
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
     }
 
     #[test]
@@ -1692,7 +1710,7 @@ mod tests {
         "#;
         let ctx = infer_prog(src);
 
-        assert_eq!(get_type("elem", &ctx), "JSXElement");
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
     }
 
     // TODO: disallow extra props
@@ -1705,7 +1723,7 @@ mod tests {
         "#;
         let ctx = infer_prog(src);
 
-        assert_eq!(get_type("elem", &ctx), "JSXElement");
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
     }
 
     #[test]
@@ -1777,18 +1795,29 @@ mod tests {
     }
 
     #[test]
-    fn infer_template_literal_as_string() {
+    fn infer_template_literal_as_string_literal() {
         let src = r#"let str = `hello, "world"!`"#;
         let ctx = infer_prog(src);
 
-        assert_eq!(get_type("str", &ctx), "string");
+        assert_eq!(get_type("str", &ctx), r#""hello, "world"!""#);
     }
 
     #[test]
-    fn infer_template_literal_with_expressions_as_string() {
+    fn infer_template_literal_with_literal_expressions_as_string_literal() {
         let src = r#"let str = `hello, ${true}!`"#;
         let ctx = infer_prog(src);
 
+        assert_eq!(get_type("str", &ctx), r#""hello, true!""#);
+    }
+
+    #[test]
+    fn infer_template_literal_with_expressions_as_string() {
+        let src = r#"
+        declare let name: string
+        let str = `hello, ${name}!`
+        "#;
+        let ctx = infer_prog(src);
+
         assert_eq!(get_type("str", &ctx), "string");
     }
 
@@ -1834,4 +1863,2347 @@ mod tests {
         
         infer_prog(src);
     }
+
+    #[test]
+    fn recursive_type_alias() {
+        let src = r#"
+        type List<T> = {head: T, tail: List<T> | null}
+        declare let list: List<number>
+        let head = list.head
+        let tail = list.tail
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("head", &ctx), "number");
+        assert_eq!(get_type("tail", &ctx), "List<number> | null");
+    }
+
+    #[test]
+    fn assign_obj_to_recursive_type_alias() {
+        let src = r#"
+        type List<T> = {head: T, tail: List<T> | null}
+        declare let empty: null
+        let list: List<number> = {head: 1, tail: {head: 2, tail: empty}}
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("list", &ctx), "List<number>");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn assign_obj_to_recursive_type_alias_with_incorrect_type() {
+        let src = r#"
+        type List<T> = {head: T, tail: List<T> | null}
+        declare let empty: null
+        let list: List<number> = {head: 1, tail: {head: "hello", tail: empty}}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn recursive_type_alias_through_array() {
+        let src = r#"
+        type Json = string | number | boolean | Json[]
+        let json: Json = ["hello", [5, true]]
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("json", &ctx), "Json");
+    }
+
+    #[test]
+    fn unify_alias_with_another_alias() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        type Vector = {x: number, y: number}
+        declare let p: Point
+        let v: Vector = p
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("v", &ctx), "Vector");
+    }
+
+    #[test]
+    fn unify_recursive_alias_with_another_recursive_alias() {
+        let src = r#"
+        type List<T> = {head: T, tail: List<T> | null}
+        type Stack<T> = {head: T, tail: Stack<T> | null}
+        declare let list: List<number>
+        let stack: Stack<number> = list
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("stack", &ctx), "Stack<number>");
+    }
+
+    #[test]
+    fn unify_mutually_recursive_aliases() {
+        let src = r#"
+        type Tree = {value: number, children: Forest}
+        type Forest = Tree[]
+        declare let forest: Forest
+        let tree: Tree = {value: 5, children: forest}
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("tree", &ctx), "Tree");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn unify_recursive_aliases_with_different_structure() {
+        let src = r#"
+        type List<T> = {head: T, tail: List<T> | null}
+        type Stack<T> = {top: T, rest: Stack<T> | null}
+        declare let list: List<number>
+        let stack: Stack<number> = list
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Type alias 'A' circularly references itself"]
+    fn alias_referencing_itself() {
+        infer_prog("type A = string | A");
+    }
+
+    #[test]
+    #[should_panic = "Type alias 'A' circularly references itself"]
+    fn aliases_referencing_each_other() {
+        let src = r#"
+        type A = B
+        type B = A
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Type alias 'B' circularly references itself"]
+    fn alias_referencing_itself_through_type_arg() {
+        let src = r#"
+        type Id<T> = T
+        type B = Id<B>
+        declare let b: B
+        let c: number = b
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Type alias 'C' circularly references itself"]
+    fn alias_referencing_itself_through_nested_type_args() {
+        let src = r#"
+        type Id<T> = T
+        type C = Id<Id<C>>
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn alias_with_nested_type_args_of_the_same_alias() {
+        let src = r#"
+        type Id<T> = T
+        type Box<T> = {value: T}
+        type A = Id<Id<number>>
+        type B = Id<Box<Id<B>>>
+        declare let a: A
+        let b: number = a
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Can't find type 'Bar'"]
+    fn type_ann_with_unknown_type() {
+        let src = r#"
+        declare let foo: Bar
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Can't find type 'Bar'"]
+    fn type_decl_with_unknown_type() {
+        let src = r#"
+        type Foo = {bar: Bar}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Type 'Foo' expects 1 type argument(s) but was given 2"]
+    fn type_ann_with_too_many_type_args() {
+        let src = r#"
+        type Foo<T> = {bar: T}
+        declare let foo: Foo<number, string>
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Type 'Foo' expects 2 type argument(s) but was given 0"]
+    fn type_ann_with_missing_type_args() {
+        let src = r#"
+        type Foo<A, B> = {a: A, b: B}
+        let foo = (x: Foo) => x
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Type 'List' expects 1 type argument(s) but was given 0"]
+    fn type_decl_with_missing_type_args_in_forward_reference() {
+        let src = r#"
+        type Tree = {children: List}
+        type List<T> = {head: T, tail: List<T> | null}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Type parameter 'T' is not generic"]
+    fn type_param_used_as_generic_type() {
+        let src = r#"
+        type Foo<T> = {bar: T<number>}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn type_args_are_applied_in_declaration_order() {
+        let src = r#"
+        type Pair<A, B> = {first: A, second: B}
+        declare let pair: Pair<number, string>
+        let first = pair.first
+        let second = pair.second
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("first", &ctx), "number");
+        assert_eq!(get_type("second", &ctx), "string");
+    }
+
+    #[test]
+    fn infer_enum_ctors() {
+        let src = r#"
+        enum Shape { Circle(r: number), Square(s: number) }
+        let circle = Shape.Circle(5)
+        let square = Shape.Square(4)
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(
+            get_type("Shape", &ctx),
+            "{Circle: (number) => Shape, Square: (number) => Shape}"
+        );
+        assert_eq!(get_type("circle", &ctx), "Shape");
+        assert_eq!(get_type("square", &ctx), "Shape");
+    }
+
+    #[test]
+    fn infer_generic_enum_ctors() {
+        let src = r#"
+        enum Option<T> { Some(value: T), None }
+        let some = Option.Some(5)
+        let none: Option<number> = Option.None
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(
+            get_type("Option", &ctx),
+            "<t0>{Some: (t0) => Option<t0>, None: Option<t0>}"
+        );
+        assert_eq!(get_type("some", &ctx), "Option<5>");
+        assert_eq!(get_type("none", &ctx), "Option<number>");
+    }
+
+    #[test]
+    fn enum_is_a_tagged_union() {
+        let src = r#"
+        enum Shape { Circle(r: number), Square(s: number) }
+        let shape: Shape = {kind: "Square", s: 5}
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("shape", &ctx), "Shape");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn enum_ctor_with_incorrect_arg() {
+        let src = r#"
+        enum Shape { Circle(r: number), Square(s: number) }
+        let circle = Shape.Circle("hello")
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn match_enum_with_if_let() {
+        let src = r#"
+        enum Shape { Circle(r: number), Square(s: number) }
+        let area = (shape: Shape) => if let {kind: "Circle", r} = shape {
+            3 * r * r
+        } else if let {kind: "Square", s} = shape {
+            s * s
+        } else {
+            0
+        }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("area", &ctx), "(Shape) => number");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn match_enum_with_incorrect_field() {
+        let src = r#"
+        enum Shape { Circle(r: number), Square(s: number) }
+        let radius = (shape: Shape) => if let {kind: "Circle", s} = shape {
+            s
+        } else {
+            0
+        }
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn enum_referenced_before_declaration() {
+        let src = r#"
+        type Drawing = {shape: Shape}
+        enum Shape { Circle(r: number), Square(s: number) }
+        declare let drawing: Drawing
+        let circle: Shape = drawing.shape
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("circle", &ctx), "Shape");
+    }
+
+    #[test]
+    #[should_panic = "Duplicate variant 'Circle' in enum 'Shape'"]
+    fn enum_with_duplicate_variants() {
+        let src = r#"
+        enum Shape { Circle(r: number), Circle(d: number) }
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Variant 'Circle' can't have a field named 'kind'"]
+    fn enum_variant_with_tag_field() {
+        let src = r#"
+        enum Shape { Circle(kind: string), Square(s: number) }
+        "#;
+        infer_prog(src);
+    }
+
+    const POINT_CLASS: &str = r#"
+    class Point {
+        x: number
+        y: number
+        constructor(x, y) => {
+            this.x = x;
+            this.y = y;
+        }
+        add(other: Point) => new Point(this.x + other.x, this.y + other.y)
+        sum() => this.x + this.y
+    }
+    "#;
+
+    #[test]
+    fn infer_class_constructor() {
+        let ctx = infer_prog(POINT_CLASS);
+
+        assert_eq!(get_type("Point", &ctx), "(number, number) => Point");
+    }
+
+    #[test]
+    fn infer_class_instance_and_methods() {
+        let src = format!(
+            "{POINT_CLASS}\n{}",
+            r#"
+            let p = new Point(5, 10)
+            let q = p.add(p)
+            let total = q.sum()
+            let x = q.x
+            "#
+        );
+        let ctx = infer_prog(&src);
+
+        assert_eq!(get_type("p", &ctx), "Point");
+        assert_eq!(get_type("q", &ctx), "Point");
+        assert_eq!(get_type("total", &ctx), "number");
+        assert_eq!(get_type("x", &ctx), "number");
+    }
+
+    #[test]
+    fn class_without_constructor() {
+        let src = r#"
+        class Foo {
+            bar() => "bar"
+        }
+        let foo = new Foo()
+        let bar = foo.bar()
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("Foo", &ctx), "() => Foo");
+        assert_eq!(get_type("bar", &ctx), "\"bar\"");
+    }
+
+    #[test]
+    fn methods_can_call_other_methods() {
+        let src = r#"
+        class Rectangle {
+            w: number
+            h: number
+            constructor(w, h) => {
+                this.w = w;
+                this.h = h;
+            }
+            area() => this.w * this.h
+            double_area() => 2 * this.area()
+        }
+        let double_area = new Rectangle(2, 3).double_area()
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("double_area", &ctx), "number");
+    }
+
+    #[test]
+    fn class_instance_is_a_subtype_of_object() {
+        let src = format!(
+            "{POINT_CLASS}\n{}",
+            r#"
+            let get_x = (pt: {x: number}) => pt.x
+            let x = get_x(new Point(5, 10))
+            "#
+        );
+        let ctx = infer_prog(&src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn classes_are_nominal() {
+        let src = r#"
+        class Foo {
+            x: number
+            constructor(x) => {this.x = x}
+        }
+        class Bar {
+            x: number
+            constructor(x) => {this.x = x}
+        }
+        let foo: Foo = new Bar(5)
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn object_is_not_a_class_instance() {
+        let src = format!(
+            "{POINT_CLASS}\n{}",
+            r#"
+            let p: Point = {x: 5, y: 10}
+            "#
+        );
+        infer_prog(&src);
+    }
+
+    #[test]
+    #[should_panic = "Class constructor 'Point' cannot be invoked without 'new'"]
+    fn calling_class_without_new() {
+        let src = format!("{POINT_CLASS}\nlet p = Point(5, 10)");
+        infer_prog(&src);
+    }
+
+    #[test]
+    #[should_panic = "Only classes can be instantiated using 'new'"]
+    fn new_with_non_class() {
+        let src = r#"
+        let foo = () => 5
+        let bar = new foo()
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Property 'z' doesn't exist on class 'Point'"]
+    fn accessing_missing_class_property() {
+        let src = format!("{POINT_CLASS}\nlet z = new Point(5, 10).z");
+        infer_prog(&src);
+    }
+
+    #[test]
+    #[should_panic = "Only properties of 'this' can be assigned to"]
+    fn assigning_to_non_this_property() {
+        let src = format!(
+            "{POINT_CLASS}\n{}",
+            r#"
+            let p = new Point(5, 10)
+            let q = p.x = 5
+            "#
+        );
+        infer_prog(&src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn assigning_wrong_type_to_field() {
+        let src = r#"
+        class Foo {
+            x: number
+            constructor() => {this.x = "hello"}
+        }
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Duplicate member 'x' in class 'Foo'"]
+    fn class_with_duplicate_members() {
+        let src = r#"
+        class Foo {
+            x: number
+            x() => 5
+        }
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn narrow_class_with_is_pattern() {
+        let src = format!(
+            "{POINT_CLASS}\n{}",
+            r#"
+            declare let value: Point | string
+            let x = if let p is Point = value {
+                p.x
+            } else {
+                0
+            }
+            "#
+        );
+        let ctx = infer_prog(&src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+    }
+
+    #[test]
+    fn infer_interface_props() {
+        let src = r#"
+        interface Point {
+            x: number
+            y: number
+        }
+        let p: Point = {x: 5, y: 10}
+        let x = p.x
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("p", &ctx), "Point");
+        assert_eq!(get_type("x", &ctx), "number");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn interface_with_missing_prop() {
+        let src = r#"
+        interface Point {
+            x: number
+            y: number
+        }
+        let p: Point = {x: 5}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn interface_declarations_are_merged() {
+        let src = r#"
+        interface Point {x: number}
+        declare let p: Point
+        interface Point {y: number}
+        let sum = p.x + p.y
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(format!("{}", ctx.types.get("Point").unwrap()), "{x: number, y: number}");
+        assert_eq!(get_type("sum", &ctx), "number");
+    }
+
+    #[test]
+    fn interface_extends_interfaces() {
+        let src = r#"
+        interface Point3D extends Point, HasZ {}
+        interface Point {x: number, y: number}
+        interface HasZ {z: number}
+        declare let p: Point3D
+        let z = p.z
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(
+            format!("{}", ctx.types.get("Point3D").unwrap()),
+            "{x: number, y: number, z: number}"
+        );
+        assert_eq!(get_type("z", &ctx), "number");
+    }
+
+    #[test]
+    fn generic_interface_extends_generic_interface() {
+        let src = r#"
+        interface Box<T> {value: T}
+        interface LabeledBox<T> extends Box<T> {label: string}
+        declare let box: LabeledBox<number>
+        let value = box.value
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("value", &ctx), "number");
+    }
+
+    #[test]
+    fn interface_extends_type_alias() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        interface Circle extends Point {radius: number}
+        let circle: Circle = {x: 0, y: 0, radius: 5}
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("circle", &ctx), "Circle");
+    }
+
+    #[test]
+    fn interface_method_signatures_and_overloads() {
+        let src = r#"
+        interface Formatter {
+            format(value: number): string
+        }
+        interface Formatter {
+            format(value: boolean, verbose: boolean): string
+            reset(): undefined
+        }
+        declare let fmt: Formatter
+        let a = fmt.format(5)
+        let b = fmt.format(true, false)
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("a", &ctx), "string");
+        assert_eq!(get_type("b", &ctx), "string");
+    }
+
+    #[test]
+    fn interface_call_signatures() {
+        let src = r#"
+        interface Parser {
+            (input: string): number
+            name: string
+        }
+        declare let parse: Parser
+        let result = parse("5")
+        let name = parse.name
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("result", &ctx), "number");
+        assert_eq!(get_type("name", &ctx), "string");
+    }
+
+    #[test]
+    #[should_panic = "Duplicate property 'x' in interface 'Point'"]
+    fn interface_with_duplicate_props() {
+        let src = r#"
+        interface Point {x: number}
+        interface Point {x: string}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn interface_declarations_can_repeat_props_with_the_same_type() {
+        let src = r#"
+        interface Box<T> {value: T, label?: string}
+        interface Box<T> {value: T, label?: string, size: number}
+        declare let box: Box<boolean>
+        let value = box.value
+        let size = box.size
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("value", &ctx), "boolean");
+        assert_eq!(get_type("size", &ctx), "number");
+    }
+
+    #[test]
+    #[should_panic = "Duplicate property 'label' in interface 'Box'"]
+    fn interface_declarations_with_repeated_optional_and_required_prop() {
+        let src = r#"
+        interface Box {label?: string}
+        interface Box {label: string}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "All declarations of 'Box' must have identical type parameters"]
+    fn interface_declarations_with_different_type_params() {
+        let src = r#"
+        interface Box<T> {value: T}
+        interface Box<U> {label: string}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Interface 'A' recursively references itself as a base type"]
+    fn interface_extends_itself() {
+        let src = r#"
+        interface A extends B {}
+        interface B extends A {}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "An interface can only extend an object type"]
+    fn interface_extends_non_object_type() {
+        let src = r#"
+        type Name = string
+        interface Person extends Name {}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn narrow_with_typeof() {
+        let src = r#"
+        declare let value: string | number
+        let result = if typeof value == "string" {
+            value
+        } else {
+            value + 1
+        }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("result", &ctx), "string | number");
+    }
+
+    #[test]
+    fn narrow_with_typeof_not_equal() {
+        let src = r#"
+        declare let value: string | number | boolean
+        let result = if "number" != typeof value {
+            value
+        } else {
+            value
+        }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("result", &ctx), "string | number | boolean");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn narrow_with_typeof_alternate_error() {
+        let src = r#"
+        declare let value: string | number
+        let result = if typeof value == "number" {
+            value
+        } else {
+            value + 1
+        }
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn narrow_with_not_equal_null() {
+        let src = r#"
+        declare let value: number | null
+        let result = if value != null {
+            value + 1
+        } else {
+            0
+        }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("result", &ctx), "number");
+    }
+
+    #[test]
+    fn narrow_with_equal_literal() {
+        let src = r#"
+        type Dir = "up" | "down" | "left"
+        declare let dir: Dir
+        let result = if dir == "up" {
+            dir
+        } else {
+            dir
+        }
+        let up = if dir == "up" { dir } else { "up" }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("up", &ctx), "\"up\"");
+        assert_eq!(get_type("result", &ctx), "\"up\" | \"down\" | \"left\"");
+    }
+
+    #[test]
+    fn narrow_with_truthiness() {
+        let src = r#"
+        declare let value: {x: number} | undefined
+        let x = if value { value.x } else { 0 }
+        let y = if !value { 0 } else { value.x }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+        assert_eq!(get_type("y", &ctx), "number");
+    }
+
+    #[test]
+    fn narrow_without_else() {
+        let src = r#"
+        declare let log: (number) => undefined
+        declare let value: number | null
+        let result = if value != null { log(value) }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("result", &ctx), "undefined");
+    }
+
+    #[test]
+    fn narrowing_is_scoped_to_branch() {
+        let src = r#"
+        declare let value: number | null
+        let result = if value != null { value } else { 0 }
+        let after = value
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("after", &ctx), "number | null");
+    }
+
+    #[test]
+    fn infer_typeof_and_not() {
+        let src = r#"
+        let t = typeof 5
+        let b = !true
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("t", &ctx), "string");
+        assert_eq!(get_type("b", &ctx), "boolean");
+    }
+
+    #[test]
+    fn never_is_assignable_to_everything() {
+        let src = r#"
+        declare let fail: () => never
+        let x: number = fail()
+        let y: {a: string} = fail()
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+        assert_eq!(get_type("y", &ctx), "{a: string}");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn nothing_is_assignable_to_never() {
+        let src = r#"
+        let x: never = 5
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn everything_is_assignable_to_unknown() {
+        let src = r#"
+        declare let log: (unknown) => undefined
+        let a = log(5)
+        let b = log("hello")
+        let c = log({x: 5})
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("a", &ctx), "undefined");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn unknown_is_not_assignable_to_other_types() {
+        let src = r#"
+        declare let value: unknown
+        let x: number = value
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Object is of type 'unknown'"]
+    fn accessing_property_on_unknown() {
+        let src = r#"
+        declare let value: unknown
+        let x = value.x
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn narrow_unknown_with_typeof() {
+        let src = r#"
+        declare let value: unknown
+        let x = if typeof value == "number" { value + 1 } else { 0 }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+    }
+
+    #[test]
+    fn any_is_assignable_both_ways() {
+        let src = r#"
+        declare let value: any
+        let x: number = value
+        let y: any = "hello"
+        let z = value.foo.bar
+        let w = value(1, 2)
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+        assert_eq!(get_type("y", &ctx), "any");
+        assert_eq!(get_type("z", &ctx), "any");
+        assert_eq!(get_type("w", &ctx), "any");
+    }
+
+    #[test]
+    fn narrowing_to_never_for_exhaustiveness() {
+        let src = r#"
+        declare let value: string | number
+        let result = if typeof value == "string" {
+            value
+        } else if typeof value == "number" {
+            value
+        } else {
+            let unreachable: never = value;
+            unreachable
+        }
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("result", &ctx), "string | number");
+    }
+
+    #[test]
+    fn union_types_simplify_never_unknown_and_any() {
+        let ctx = Context::default();
+        let num = ctx.prim(types::Primitive::Num);
+        let never = ctx.prim(types::Primitive::Never);
+        let unknown = ctx.prim(types::Primitive::Unknown);
+        let any = ctx.prim(types::Primitive::Any);
+
+        assert_eq!(format!("{}", crate::util::union_types(&num, &never, &ctx)), "number");
+        assert_eq!(format!("{}", crate::util::union_types(&never, &never, &ctx)), "never");
+        assert_eq!(format!("{}", crate::util::union_types(&num, &unknown, &ctx)), "unknown");
+        assert_eq!(format!("{}", crate::util::union_types(&any, &num, &ctx)), "any");
+    }
+
+    #[test]
+    fn type_assertion_with_any() {
+        let src = r#"
+        declare let parse: (string) => any
+        let point = parse("{x: 5, y: 10}") as {x: number, y: number}
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("point", &ctx), "{x: number, y: number}");
+    }
+
+    #[test]
+    fn type_assertion_can_widen_and_narrow() {
+        let src = r#"
+        declare let value: string | number
+        let num = value as number
+        let wide = 5 as number | string
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("num", &ctx), "number");
+        assert_eq!(get_type("wide", &ctx), "number | string");
+    }
+
+    #[test]
+    #[should_panic = "Conversion of type 'string' to type 'number' may be a mistake"]
+    fn type_assertion_requires_overlap() {
+        let src = r#"
+        declare let value: string
+        let num = value as number
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn satisfies_keeps_inferred_type() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        let p = {x: 5, y: 10} satisfies Point
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("p", &ctx), "{x: 5, y: 10}");
+    }
+
+    #[test]
+    #[should_panic = "Type '{x: 5}' does not satisfy the expected type 'Point'"]
+    fn satisfies_checks_assignability() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        let p = {x: 5} satisfies Point
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn callback_params_are_inferred_from_callee() {
+        let src = r#"
+        declare let map: <T, U>(T[], (T) => U) => U[]
+        declare let people: {name: string, age: number}[]
+        let names = map(people, (person) => person.name)
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("names", &ctx), "string[]");
+    }
+
+    #[test]
+    fn callback_params_with_destructuring() {
+        let src = r#"
+        declare let for_each: ({x: number, y: number}[], ({x: number, y: number}) => undefined) => undefined
+        declare let points: {x: number, y: number}[]
+        let result = for_each(points, ({x, y}) => undefined)
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("result", &ctx), "undefined");
+    }
+
+    #[test]
+    fn lambda_params_are_inferred_from_type_annotation() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        let get_x: (Point) => number = (p) => p.x
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("get_x", &ctx), "(Point) => number");
+    }
+
+    #[test]
+    fn lambdas_in_object_literals_are_checked_against_expected_type() {
+        let src = r#"
+        type Handlers = {on_click?: ({x: number}) => undefined, label: string}
+        declare let register: (Handlers) => undefined
+        let result = register({on_click: (event) => { let x = event.x; undefined }, label: "ok"})
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("result", &ctx), "undefined");
+    }
+
+    #[test]
+    fn callback_params_are_inferred_from_later_args() {
+        let src = r#"
+        declare let map: <T, U>((T) => U, T[]) => U[]
+        declare let people: {name: string, age: number}[]
+        let names = map((person) => person.name, people)
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("names", &ctx), "string[]");
+    }
+
+    #[test]
+    fn lambda_params_are_inferred_from_union_with_lambda() {
+        let src = r#"
+        let get_a: ((x: {a: number}) => number) | string = (x) => x.a
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Property 'a' does not exist on type"]
+    fn lambda_params_without_expected_type_report_missing_props() {
+        let src = r#"
+        let get_a: ((x: {a: number}) => number) | ((y: {a: string}) => string) = (x) => x.a
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn checked_callback_must_match_expected_type() {
+        let src = r#"
+        declare let map: <T, U>(T[], (T) => U) => U[]
+        declare let people: {name: string}[]
+        let names = map(people, (person) => person.name * 2)
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn readonly_props() {
+        let src = r#"
+        type Config = {readonly name: string, retries?: number}
+        declare let config: Config
+        let name = config.name
+        let copy: {name: string} = config
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("name", &ctx), "string");
+        assert_eq!(get_type("copy", &ctx), "{name: string}");
+    }
+
+    #[test]
+    fn mutable_arrays_are_assignable_to_readonly_arrays() {
+        let src = r#"
+        declare let nums: number[]
+        let readonly_nums: readonly number[] = nums
+        let pair: readonly [number, string] = [5, "hello"]
+        let readonly_strs: readonly string[] = ["a", "b"]
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("readonly_nums", &ctx), "readonly number[]");
+        assert_eq!(get_type("pair", &ctx), "readonly [number, string]");
+    }
+
+    #[test]
+    #[should_panic = "The type 'readonly number[]' is 'readonly' and cannot be assigned to the mutable type 'number[]'"]
+    fn readonly_arrays_are_not_assignable_to_mutable_arrays() {
+        let src = r#"
+        declare let nums: readonly number[]
+        let mutable_nums: number[] = nums
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "is 'readonly' and cannot be assigned to the mutable type"]
+    fn readonly_tuples_are_not_assignable_to_mutable_tuples() {
+        let src = r#"
+        declare let pair: readonly [number, string]
+        let mutable_pair: [number, string] = pair
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn readonly_tuples_can_be_destructured() {
+        let src = r#"
+        type Entry = readonly [readonly [number, string], boolean]
+        declare let pair: readonly [number, string]
+        declare let entry: Entry
+        let [x, y] = pair
+        let [[a, b], c] = entry
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+        assert_eq!(get_type("y", &ctx), "string");
+        assert_eq!(get_type("a", &ctx), "number");
+        assert_eq!(get_type("b", &ctx), "string");
+    }
+
+    #[test]
+    #[should_panic = "is 'readonly' and cannot be assigned to the mutable type"]
+    fn readonly_tuples_cannot_be_destructured_into_mutable_type_annotations() {
+        let src = r#"
+        declare let pair: readonly [number, string]
+        let [x, y]: [number, string] = pair
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "'readonly' type modifier is only permitted on array and tuple types"]
+    fn readonly_only_applies_to_arrays_and_tuples() {
+        let src = r#"
+        declare let x: readonly number
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn readonly_props_in_interfaces() {
+        let src = r#"
+        interface Point {
+            readonly x: number,
+            readonly y: number,
+        }
+        declare let p: Point
+        let x = p.x
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+        assert_eq!(
+            format!("{}", ctx.types.get("Point").unwrap()),
+            "{readonly x: number, readonly y: number}"
+        );
+    }
+
+    #[test]
+    fn index_signature_access() {
+        let src = r#"
+        type Dict = {[key: string]: number}
+        declare let dict: Dict
+        declare let key: string
+        let a = dict.foo
+        let b = dict[key]
+        let c = dict["bar"]
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("a", &ctx), "number | undefined");
+        assert_eq!(get_type("b", &ctx), "number | undefined");
+        assert_eq!(get_type("c", &ctx), "number | undefined");
+    }
+
+    #[test]
+    fn index_signature_with_props() {
+        let src = r#"
+        declare let scores: {[name: string]: number, total: number}
+        let total = scores.total
+        let other = scores.other
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("total", &ctx), "number");
+        assert_eq!(get_type("other", &ctx), "number | undefined");
+    }
+
+    #[test]
+    fn object_literals_are_assignable_to_index_signatures() {
+        let src = r#"
+        let dict: {[key: string]: number} = {a: 1, b: 2}
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("dict", &ctx), "{[key: string]: number}");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn props_must_conform_to_index_signature_when_assigning() {
+        let src = r#"
+        let dict: {[key: string]: number} = {a: 1, b: "hello"}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Property 'total' of type 'string' is not assignable to 'string' index type 'number'"]
+    fn props_must_conform_to_index_signature_in_object_types() {
+        let src = r#"
+        declare let scores: {[name: string]: number, total: string}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "An index signature parameter type must be 'string' or 'number'"]
+    fn index_signature_keys_must_be_strings_or_numbers() {
+        let src = r#"
+        declare let dict: {[key: boolean]: number}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn dynamic_access_key_must_match_index_signature() {
+        let src = r#"
+        declare let dict: {[key: number]: string}
+        let x = dict[true]
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn dynamic_array_access() {
+        let src = r#"
+        declare let nums: number[]
+        let x = nums[0]
+        let pair = [5, "hello"]
+        let y = pair[1]
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number | undefined");
+        assert_eq!(get_type("y", &ctx), "\"hello\"");
+    }
+
+    #[test]
+    fn index_signatures_in_interfaces() {
+        let src = r#"
+        interface Env {
+            readonly [name: string]: string,
+            HOME: string,
+        }
+        declare let env: Env
+        let home = env.HOME
+        let path = env.PATH
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("home", &ctx), "string");
+        assert_eq!(get_type("path", &ctx), "string | undefined");
+    }
+
+    #[test]
+    fn keyof_object_type() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        declare let key: keyof Point
+        let x: keyof Point = "x"
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("key", &ctx), "keyof Point");
+        assert_eq!(get_type("x", &ctx), "keyof Point");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn keyof_object_type_rejects_other_keys() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        let z: keyof Point = "z"
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn typeof_value() {
+        let src = r#"
+        let point = {x: 5, y: 10}
+        declare let other: typeof point
+        let x = other.x
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("other", &ctx), "{x: 5, y: 10}");
+        assert_eq!(get_type("x", &ctx), "5");
+    }
+
+    #[test]
+    fn typeof_value_in_type_decl() {
+        let src = r#"
+        type T = typeof p
+        type K = keyof typeof p
+        declare let p: {x: number, y: string}
+        declare let q: T
+        let x = q.x
+        let key: K = "y"
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+    }
+
+    #[test]
+    #[should_panic = "Can't find value: p"]
+    fn typeof_missing_value_in_type_decl() {
+        let src = r#"
+        type T = typeof p
+        declare let q: T
+        let x: number = q
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn keyof_typeof_value() {
+        let src = r#"
+        let point = {x: 5, y: 10}
+        let key: keyof typeof point = "y"
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("key", &ctx), "keyof {x: 5, y: 10}");
+    }
+
+    #[test]
+    fn indexed_access_types() {
+        let src = r#"
+        type Point = {x: number, y: string}
+        declare let x: Point["x"]
+        declare let xy: Point["x" | "y"]
+        let a: number = x
+        let b: number | string = xy
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "Point[\"x\"]");
+        assert_eq!(get_type("xy", &ctx), "Point[\"x\"] | Point[\"y\"]");
+        assert_eq!(get_type("a", &ctx), "number");
+    }
+
+    #[test]
+    fn indexed_access_with_keyof() {
+        let src = r#"
+        type Point = {x: number, y: string}
+        declare let value: Point[keyof Point]
+        let a: number | string = value
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("value", &ctx), "Point[\"x\"] | Point[\"y\"]");
+    }
+
+    #[test]
+    #[should_panic = "Property 'z' does not exist on type '{x: number, y: string}'"]
+    fn indexed_access_missing_prop() {
+        let src = r#"
+        type Point = {x: number, y: string}
+        declare let z: Point["z"]
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn dynamic_access_with_keyof() {
+        let src = r#"
+        type Point = {x: number, y: string}
+        declare let point: Point
+        declare let key: keyof Point
+        let value = point[key]
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("value", &ctx), "number | string");
+    }
+
+    #[test]
+    fn generic_keyof() {
+        let src = r#"
+        declare let get: <T>(T, keyof T) => undefined
+        let point = {x: 5, y: 10}
+        get(point, "x")
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn generic_keyof_invalid_key() {
+        let src = r#"
+        declare let get: <T>(T, keyof T) => undefined
+        let point = {x: 5, y: 10}
+        get(point, "z")
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn conditional_types() {
+        let src = r#"
+        type IsString<T> = T extends string ? true : false
+        let a: IsString<"hello"> = true
+        let b: IsString<number> = false
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("a", &ctx), "IsString<\"hello\">");
+        assert_eq!(get_type("b", &ctx), "IsString<number>");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn conditional_types_false_branch() {
+        let src = r#"
+        type IsString<T> = T extends string ? true : false
+        let a: IsString<number> = true
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn conditional_types_with_infer() {
+        let src = r#"
+        type ReturnType<F> = F extends (any) => infer R ? R : never
+        type ElementType<T> = T extends (infer E)[] ? E : T
+        let a: ReturnType<(number) => string> = "hello"
+        let b: ElementType<boolean[]> = true
+        let c: ElementType<number> = 5
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn conditional_types_infer_type_args() {
+        let src = r#"
+        type Unwrap<T> = T extends Promise<infer U> ? U : T
+        declare let p: Unwrap<Promise<number>>
+        declare let s: Unwrap<string>
+        let a: number = p
+        let b: string = s
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn conditional_types_infer_type_args_mismatch() {
+        let src = r#"
+        type Unwrap<T> = T extends Promise<infer U> ? U : T
+        declare let p: Unwrap<Promise<number>>
+        let a: string = p
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn conditional_types_infer_rest_elements() {
+        let src = r#"
+        type Tail<T> = T extends [any, ...infer R] ? R : never
+        type Init<T> = T extends [...infer R, any] ? R : never
+        declare let tail: Tail<[number, string, boolean]>
+        declare let init: Init<[number, string, boolean]>
+        declare let empty: Tail<[]>
+        let a: [string, boolean] = tail
+        let b: [number, string] = init
+        let c: never = empty
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn conditional_types_infer_rest_elements_mismatch() {
+        let src = r#"
+        type Tail<T> = T extends [any, ...infer R] ? R : never
+        declare let tail: Tail<[number, string, boolean]>
+        let a: [number, string] = tail
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn conditional_types_with_infer_mismatch() {
+        let src = r#"
+        type ReturnType<F> = F extends (any) => infer R ? R : never
+        let a: ReturnType<(number) => string> = 5
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn conditional_types_distribute_over_unions() {
+        let src = r#"
+        type Exclude<T, U> = T extends U ? never : T
+        declare let x: Exclude<"a" | "b" | "c", "a">
+        let y: "b" | "c" = x
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn conditional_types_distribute_over_unions_excludes_members() {
+        let src = r#"
+        type Exclude<T, U> = T extends U ? never : T
+        let x: Exclude<"a" | "b" | "c", "a"> = "a"
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "'infer' declarations are only permitted in the 'extends' clause of a conditional type"]
+    fn infer_outside_of_extends() {
+        let src = r#"
+        type Foo<T> = T extends string ? infer U : never
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn mapped_types() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        type Partial<T> = {[K in keyof T]?: T[K]}
+        type Readonly<T> = {readonly [K in keyof T]: T[K]}
+        let p: Partial<Point> = {x: 5}
+        declare let r: Readonly<Point>
+        let x = p.x
+        let y = r.y
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number | undefined");
+        assert_eq!(get_type("y", &ctx), "number");
+    }
+
+    #[test]
+    fn mapped_types_remove_modifiers() {
+        let src = r#"
+        type Required<T> = {-readonly [K in keyof T]-?: T[K]}
+        declare let p: Required<{readonly x?: number, y: string}>
+        let q: {x: number, y: string} = p
+        let x = p.x
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("x", &ctx), "number");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn mapped_types_missing_required_prop() {
+        let src = r#"
+        type Point = {x: number, y: number}
+        type Required<T> = {[K in keyof T]-?: T[K]}
+        let p: Required<Point> = {x: 5}
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn mapped_types_with_pick_and_record() {
+        let src = r#"
+        type Point = {x: number, y: number, z: number}
+        type Pick<T, K> = {[P in K]: T[P]}
+        type Record<K, V> = {[P in K]: V}
+        let p: Pick<Point, "x" | "y"> = {x: 1, y: 2}
+        let r: Record<"a" | "b", boolean> = {a: true, b: false}
+        let d: Record<string, number> = {foo: 5}
+        let foo = d.foo
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("foo", &ctx), "number | undefined");
+    }
+
+    #[test]
+    fn string_literals_match_template_literal_types() {
+        let src = r#"
+        type EventName = `on${string}`
+        type Route = `/users/${number}`
+        let click: EventName = "onclick"
+        let route: Route = "/users/123"
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("click", &ctx), "EventName");
+        assert_eq!(get_type("route", &ctx), "Route");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn string_literal_missing_template_literal_prefix() {
+        let src = r#"
+        let click: `on${string}` = "click"
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn non_numeric_string_in_number_placeholder() {
+        let src = r#"
+        let route: `/users/${number}` = "/users/me"
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn template_literal_types_with_literals_are_string_literals() {
+        let src = r#"
+        type Side = "top" | "bottom"
+        declare let margin: `margin-${Side | "left"}`
+        declare let id: `id-${5}`
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("id", &ctx), r#""id-5""#);
+        assert_eq!(
+            get_type("margin", &ctx),
+            r#""margin-top" | "margin-bottom" | "margin-left""#
+        );
+    }
+
+    #[test]
+    fn template_literal_types_convert_numbers_like_js() {
+        let src = r#"
+        declare let a: `v${1.50}`
+        declare let b: `v${100.0}`
+        declare let c: `v${1000000000000000000000}`
+        declare let d: `v${0.0000001}`
+        let e = `v${1.50}`
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("a", &ctx), r#""v1.5""#);
+        assert_eq!(get_type("b", &ctx), r#""v100""#);
+        assert_eq!(get_type("c", &ctx), r#""v1e+21""#);
+        assert_eq!(get_type("d", &ctx), r#""v1e-7""#);
+        assert_eq!(get_type("e", &ctx), r#""v1.5""#);
+    }
+
+    #[test]
+    fn template_literal_expressions_match_template_literal_types() {
+        let src = r#"
+        let id = 5
+        let route: `/users/${number}` = `/users/${id}`
+        let handler: `on${string}` = `on${"click"}`
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("route", &ctx), "`/users/${number}`");
+        assert_eq!(get_type("handler", &ctx), "`on${string}`");
+    }
+
+    #[test]
+    fn template_literal_expressions_with_non_literal_parts() {
+        let src = r#"
+        declare let x: string
+        declare let n: number
+        let handler: `on${string}` = `on${x}`
+        let route: `/u/${number}` = `/u/${n}`
+        let name = `on${x}`
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("handler", &ctx), "`on${string}`");
+        assert_eq!(get_type("route", &ctx), "`/u/${number}`");
+        assert_eq!(get_type("name", &ctx), "string");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn template_literal_expressions_with_mismatched_parts() {
+        let src = r#"
+        declare let x: string
+        let route: `/u/${number}` = `/u/${x}`
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn template_literal_types_are_strings() {
+        let src = r#"
+        declare let event: `on${string}`
+        let name: string = event
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("name", &ctx), "string");
+    }
+
+    #[test]
+    fn infer_types_in_template_literal_types() {
+        let src = r#"
+        type EventType<T> = T extends `on${infer E}` ? E : never
+        type Params<T> = T extends `${infer A}/${infer B}` ? [A, B] : never
+        let click: EventType<"onclick"> = "click"
+        let params: Params<"users/123/posts"> = ["users", "123/posts"]
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn infer_types_in_template_literal_types_mismatch() {
+        let src = r#"
+        type EventType<T> = T extends `on${infer E}` ? E : never
+        let click: EventType<"onclick"> = "onclick"
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn tuple_types_with_rest_elements() {
+        let src = r#"
+        let a: [number, ...string[]] = [5, "hello", "world"]
+        let b: [number, ...string[]] = [5]
+        let c: [...number[], string] = [1, 2, "three"]
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("a", &ctx), "[number, ...string[]]");
+        assert_eq!(get_type("c", &ctx), "[...number[], string]");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn tuple_types_with_rest_elements_mismatch() {
+        let src = r#"
+        let a: [number, ...string[]] = [5, "hello", 10]
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn assign_variadic_tuples() {
+        let src = r#"
+        declare let t: [number, string, ...boolean[]]
+        let a: (number | string | boolean)[] = t
+        let b: [number, ...(string | boolean)[]] = t
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn assign_variadic_tuple_to_fixed_length_tuple() {
+        let src = r#"
+        declare let t: [number, ...number[]]
+        let a: [number, number] = t
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "A rest element type must be an array type, found 'number'"]
+    fn rest_element_must_be_an_array() {
+        let src = r#"
+        declare let t: [string, ...number]
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn lambda_types_with_rest_params() {
+        let src = r#"
+        declare let sum: (...args: number[]) => number
+        declare let log: (string, ...number[]) => undefined
+        let total = sum(1, 2, 3)
+        let result = log("values", 1, 2)
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("sum", &ctx), "(...number[]) => number");
+        assert_eq!(get_type("total", &ctx), "number");
+        assert_eq!(get_type("result", &ctx), "undefined");
+    }
+
+    #[test]
+    fn variadic_generic_tuples() {
+        let src = r#"
+        declare let last: <T, U>([...T, U]) => U
+        declare let init: <T, U>([...T, U]) => T
+        declare let concat: <T, U>(T, U) => [...T, ...U]
+        let a = last([1, "two", true])
+        let b = init([1, "two", true])
+        let c = concat([1, 2], ["three"])
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("a", &ctx), "true");
+        assert_eq!(get_type("b", &ctx), r#"[1, "two"]"#);
+        assert_eq!(get_type("c", &ctx), r#"[1, 2, "three"]"#);
+    }
+
+    #[test]
+    fn infer_tagged_template_literal() {
+        let src = r#"
+        declare let sql: (TemplateStringsArray, ...(string | number)[]) => string
+        let query = sql`SELECT * FROM ${"users"} WHERE id = ${5}`
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("query", &ctx), "string");
+    }
+
+    #[test]
+    fn infer_tagged_template_literal_with_member_tag() {
+        let src = r#"
+        declare let styled: {div: (TemplateStringsArray, ...string[]) => number}
+        let div = styled.div`color: ${"red"};`
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("div", &ctx), "number");
+    }
+
+    #[test]
+    fn infer_tagged_template_literal_with_generic_tag() {
+        let src = r#"
+        declare let tag: <T>(TemplateStringsArray, T) => T
+        let value = tag`value: ${5}`
+        let strings = (strings: TemplateStringsArray) => strings.raw
+        let raw = strings`hello`
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("value", &ctx), "5");
+        assert_eq!(get_type("raw", &ctx), "readonly string[]");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn infer_tagged_template_literal_with_wrong_value_type() {
+        let src = r#"
+        declare let tag: (TemplateStringsArray, number) => string
+        tag`value: ${"five"}`
+        "#;
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_fragment() {
+        let src = r#"
+        let Foo = () => <div>Hello, world!</div>
+        let elem = <><Foo />{"bar"}</>
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    fn jsx_custom_element_with_boolean_attr() {
+        let src = r#"
+        type Props = {disabled: boolean}
+        let Button = (props: Props) => <button disabled={props.disabled} />
+        let elem = <Button disabled />
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    fn jsx_custom_element_with_spread_attrs() {
+        let src = r#"
+        type Props = {msg: string, count: number}
+        let Foo = (props: Props) => <div>{props.msg}</div>
+        let props = {msg: "hello"}
+        let elem = <Foo {...props} count={5} />
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_custom_element_with_incorrect_spread_attrs() {
+        let src = r#"
+        type Props = {msg: string}
+        let Foo = (props: Props) => <div>{props.msg}</div>
+        let props = {msg: 5}
+        let elem = <Foo {...props} />
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_member_element() {
+        let src = r#"
+        type Props = {value: number}
+        let Ctx = {Provider: (props: Props) => <div>{props.value}</div>}
+        let elem = <Ctx.Provider value={5}></Ctx.Provider>
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_member_element_with_incorrect_props() {
+        let src = r#"
+        type Props = {value: number}
+        let Ctx = {Provider: (props: Props) => <div>{props.value}</div>}
+        let elem = <Ctx.Provider value="hello"></Ctx.Provider>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_intrinsic_element_with_dashed_attrs() {
+        let src = r#"
+        let elem = <div data-foo="bar" aria-label="baz" hidden />
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    #[should_panic = "Can't find type: world"]
+    fn jsx_children_are_inferred() {
+        let src = r#"
+        let elem = <div>Hello, {world}!</div>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Can't find type: world"]
+    fn jsx_fragment_children_are_inferred() {
+        let src = r#"
+        let elem = <>Hello, {world}!</>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_custom_element_with_string_children() {
+        let src = r#"
+        type Props = {children: string}
+        let Foo = (props: Props) => <div>{props.children}</div>
+        let elem = <Foo>Hello, world!</Foo>
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_custom_element_with_incorrect_children() {
+        let src = r#"
+        type Props = {children: string}
+        let Foo = (props: Props) => <div>{props.children}</div>
+        let elem = <Foo><div /></Foo>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_custom_element_with_too_many_children() {
+        let src = r#"
+        type Props = {children: string}
+        let Foo = (props: Props) => <div>{props.children}</div>
+        let elem = <Foo>Hello, <b>world</b></Foo>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_custom_element_with_function_child() {
+        let src = r#"
+        type Props = {children: (count: number) => JSX.Element}
+        let Counter = (props: Props) => props.children(5)
+        let elem = <Counter>{(count) => <div>{count}</div>}</Counter>
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_custom_element_with_incorrect_function_child() {
+        let src = r#"
+        type Props = {children: (count: number) => JSX.Element}
+        let Counter = (props: Props) => props.children(5)
+        let elem = <Counter>{(count: string) => <div>{count}</div>}</Counter>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_custom_element_with_multiple_children() {
+        let src = r#"
+        type Props = {children: [string, number, JSX.Element]}
+        let Foo = (props: Props) => <div />
+        let elem = <Foo>Count: {5}<br /></Foo>
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    fn jsx_custom_element_with_spread_children() {
+        let src = r#"
+        type Props = {children: JSX.Element[]}
+        let List = (props: Props) => <ul>{...props.children}</ul>
+        let items = [<li>foo</li>, <li>bar</li>]
+        let elem = <List>{...items}</List>
+        let elem2 = <List><li>first</li>{...items}</List>
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+        assert_eq!(get_type("elem2", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    fn jsx_whitespace_with_line_breaks_is_not_a_child() {
+        let src = r#"
+        type Props = {children: JSX.Element}
+        let Foo = (props: Props) => <div>{props.children}</div>
+        let elem = <Foo>
+            <span>Hello</span>
+        </Foo>
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    #[should_panic = "'children' are specified twice, as an attribute and as JSX children"]
+    fn jsx_children_specified_twice() {
+        let src = r#"
+        type Props = {children: string}
+        let Foo = (props: Props) => <div>{props.children}</div>
+        let elem = <Foo children="foo">bar</Foo>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Can't find type: foo"]
+    fn jsx_intrinsic_element_attrs_are_inferred() {
+        let src = r#"
+        let elem = <div id={foo} />
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_intrinsic_element_with_intrinsic_elements() {
+        let src = r#"
+        namespace JSX {
+            type IntrinsicElements = {
+                div: {id?: string},
+                input: {value?: string, disabled?: boolean, onChange?: (value: string) => undefined},
+            }
+        }
+        let props = {value: "hello"}
+        let elem = <div id="foo"><input value="bar" disabled /></div>
+        let elem2 = <input {...props} onChange={(value) => undefined} />
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+        assert_eq!(get_type("elem2", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    fn jsx_intrinsic_element_with_intrinsic_elements_interface() {
+        let src = r#"
+        namespace JSX {
+            interface IntrinsicElements {
+                input: {value?: string},
+            }
+        }
+        let elem = <input value="hello" />
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_intrinsic_element_with_incorrect_attr_value() {
+        let src = r#"
+        namespace JSX {
+            type IntrinsicElements = {
+                input: {value?: string, onChange?: (value: string) => undefined},
+            }
+        }
+        let elem = <input value={5} />
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_intrinsic_element_with_incorrect_event_handler() {
+        let src = r#"
+        namespace JSX {
+            type IntrinsicElements = {
+                input: {value?: string, onChange?: (value: string) => undefined},
+            }
+        }
+        let elem = <input onChange="oops" />
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Property 'foo' does not exist on intrinsic element 'input'"]
+    fn jsx_intrinsic_element_with_unknown_attr() {
+        let src = r#"
+        namespace JSX {
+            type IntrinsicElements = {
+                input: {value?: string},
+            }
+        }
+        let elem = <input foo="bar" />
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_intrinsic_element_with_undeclared_dashed_attrs() {
+        let src = r#"
+        namespace JSX {
+            type IntrinsicElements = {
+                div: {id?: string},
+            }
+        }
+        let elem = <div data-foo="x" aria-label="y" />
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "'span' is not a known intrinsic element"]
+    fn jsx_unknown_intrinsic_element() {
+        let src = r#"
+        namespace JSX {
+            type IntrinsicElements = {
+                input: {value?: string},
+            }
+        }
+        let elem = <span />
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn optional_prop_with_incorrect_type() {
+        let src = r#"
+        let p: {x?: number} = {x: "hello"}
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_element_type_can_be_declared() {
+        let src = r#"
+        namespace JSX {
+            type Element = {type: string, props: {}}
+        }
+        let Foo = () => <div>Hello, world!</div>
+        let render = (elem: JSX.Element) => elem.type
+        let result = render(<Foo />)
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("Foo", &ctx), "() => JSX.Element");
+        assert_eq!(get_type("result", &ctx), "string");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_element_type_is_checked() {
+        let src = r#"
+        namespace JSX {
+            type Element = {type: string, props: {}}
+        }
+        let elem: JSX.Element = {type: 5, props: {}}
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    fn jsx_element_children_attribute() {
+        let src = r#"
+        namespace JSX {
+            interface ElementChildrenAttribute {
+                kids: {}
+            }
+        }
+        type Props = {kids: string}
+        let Foo = (props: Props) => <div>{props.kids}</div>
+        let elem = <Foo>Hello, world!</Foo>
+        "#;
+        let ctx = infer_prog(src);
+
+        assert_eq!(get_type("elem", &ctx), "JSX.Element");
+    }
+
+    #[test]
+    #[should_panic = "Unification failure"]
+    fn jsx_intrinsic_element_children_are_checked_with_children_attribute() {
+        let src = r#"
+        namespace JSX {
+            type IntrinsicElements = {
+                b: {children?: string},
+            }
+            type ElementChildrenAttribute = {children: {}}
+        }
+        let elem = <b>{5}</b>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "Property 'children' does not exist on intrinsic element 'br'"]
+    fn jsx_intrinsic_element_without_children_prop() {
+        let src = r#"
+        namespace JSX {
+            type IntrinsicElements = {
+                br: {},
+            }
+            type ElementChildrenAttribute = {children: {}}
+        }
+        let elem = <br>Hello</br>
+        "#;
+
+        infer_prog(src);
+    }
+
+    #[test]
+    #[should_panic = "'JSX.ElementChildrenAttribute' must have exactly one property"]
+    fn jsx_element_children_attribute_with_multiple_props() {
+        let src = r#"
+        namespace JSX {
+            type ElementChildrenAttribute = {children: {}, kids: {}}
+        }
+        let Foo = () => <div />
+        let elem = <Foo>Hello</Foo>
+        "#;
+
+        infer_prog(src);
+    }
 }
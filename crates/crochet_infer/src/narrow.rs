@@ -0,0 +1,261 @@
+use crochet_ast::*;
+
+use super::context::{lookup_alias, Context};
+use super::infer_expr::infer_expr;
+use super::substitutable::Subst;
+use super::types::{self, Scheme, Type, Variant};
+use super::util::{expand_type, is_type_op};
+
+// A list of identifiers and the types they've been narrowed to.
+pub type Refinements = Vec<(String, Type)>;
+
+// Computes the refinements for the consequent and alternate branches of an
+// `if` based on its condition.  The following conditions are supported:
+// - `typeof x == "string"`
+// - `x == null`, `x != "foo"`, etc.
+// - `x` (truthiness)
+// - `!cond` where `cond` is one of the above
+//
+// Identifiers are narrowed to `never` if none of the types in their union
+// match the condition.  Values of type `unknown` can be narrowed using `typeof`.
+pub fn narrow(cond: &Expr, ctx: &Context) -> Result<(Refinements, Refinements), String> {
+    match cond {
+        Expr::Unary(Unary {
+            op: UnaryOp::Not,
+            arg,
+            ..
+        }) => {
+            let (consequent, alternate) = narrow(arg, ctx)?;
+            Ok((alternate, consequent))
+        }
+        Expr::Op(Op {
+            op: op @ (BinOp::EqEq | BinOp::NotEq),
+            left,
+            right,
+            ..
+        }) => {
+            let refinements = match (left.as_ref(), right.as_ref()) {
+                (
+                    Expr::Unary(Unary {
+                        op: UnaryOp::Typeof,
+                        arg,
+                        ..
+                    }),
+                    Expr::Lit(Lit::Str(tag)),
+                )
+                | (
+                    Expr::Lit(Lit::Str(tag)),
+                    Expr::Unary(Unary {
+                        op: UnaryOp::Typeof,
+                        arg,
+                        ..
+                    }),
+                ) => match arg.as_ref() {
+                    Expr::Ident(Ident { name, .. }) => match narrow_unknown(name, &tag.value, ctx)? {
+                        Some(refinements) => Some(refinements),
+                        None => narrow_ident(name, ctx, |t| {
+                            typeof_tag(t, ctx).map(|t_tag| t_tag == tag.value)
+                        })?,
+                    },
+                    _ => None,
+                },
+                (Expr::Ident(Ident { name, .. }), Expr::Lit(lit))
+                | (Expr::Lit(lit), Expr::Ident(Ident { name, .. })) => {
+                    narrow_ident(name, ctx, |t| equals_lit(t, lit, ctx))?
+                }
+                _ => None,
+            };
+            Ok(match (refinements, op) {
+                (Some((consequent, alternate)), BinOp::EqEq) => (consequent, alternate),
+                (Some((consequent, alternate)), _) => (alternate, consequent),
+                (None, _) => (vec![], vec![]),
+            })
+        }
+        Expr::Ident(Ident { name, .. }) => {
+            let refinements = narrow_ident(name, ctx, |t| is_truthy(t, ctx))?;
+            Ok(refinements.unwrap_or_default())
+        }
+        _ => Ok((vec![], vec![])),
+    }
+}
+
+// Infers the type of `expr` with the identifiers in `refinements` narrowed.
+pub fn infer_refined(
+    ctx: &mut Context,
+    refinements: &Refinements,
+    expr: &Expr,
+) -> Result<(Subst, Type), String> {
+    if refinements.is_empty() {
+        return infer_expr(ctx, expr);
+    }
+
+    let mut new_ctx = ctx.clone();
+    for (name, t) in refinements {
+        new_ctx
+            .values
+            .insert(name.to_owned(), Scheme::from(t.to_owned()));
+    }
+
+    let result = infer_expr(&mut new_ctx, expr);
+
+    // Copies over the count from new_ctx so that it's unique across Contexts.
+    ctx.state.count.set(new_ctx.state.count.get());
+
+    result
+}
+
+// Splits the type of `name`, which is usually a union, into the types where
+// `pred` is true and the types where it's false.  `pred` returns None when it
+// can't tell in which case the type appears in both.
+fn narrow_ident<F>(
+    name: &str,
+    ctx: &Context,
+    pred: F,
+) -> Result<Option<(Refinements, Refinements)>, String>
+where
+    F: Fn(&Type) -> Option<bool>,
+{
+    let t = expand_alias(&ctx.lookup_value(name)?, ctx)?;
+    let types = match &t.variant {
+        Variant::Union(types) => types.to_owned(),
+        _ => vec![t],
+    };
+
+    let mut consequent: Vec<Type> = vec![];
+    let mut alternate: Vec<Type> = vec![];
+    for t in &types {
+        match pred(t) {
+            Some(true) => consequent.push(t.to_owned()),
+            Some(false) => alternate.push(t.to_owned()),
+            None => {
+                consequent.push(t.to_owned());
+                alternate.push(t.to_owned());
+            }
+        }
+    }
+
+    let refine = |types: Vec<Type>| -> Refinements {
+        match types.len() {
+            0 => vec![(name.to_owned(), ctx.prim(Primitive::Never))],
+            1 => vec![(name.to_owned(), types[0].to_owned())],
+            _ => vec![(name.to_owned(), ctx.union(types))],
+        }
+    };
+
+    Ok(Some((refine(consequent), refine(alternate))))
+}
+
+// Values of type `unknown` can be narrowed to primitive types using `typeof`.
+fn narrow_unknown(
+    name: &str,
+    tag: &str,
+    ctx: &Context,
+) -> Result<Option<(Refinements, Refinements)>, String> {
+    let t = ctx.lookup_value(name)?;
+    if !matches!(t.variant, Variant::Prim(Primitive::Unknown)) {
+        return Ok(None);
+    }
+    let prim = match tag {
+        "number" => Primitive::Num,
+        "boolean" => Primitive::Bool,
+        "string" => Primitive::Str,
+        "undefined" => Primitive::Undefined,
+        _ => return Ok(None),
+    };
+    Ok(Some((vec![(name.to_owned(), ctx.prim(prim))], vec![])))
+}
+
+fn expand_alias(t: &Type, ctx: &Context) -> Result<Type, String> {
+    match &t.variant {
+        Variant::Alias(alias) => expand_alias(&lookup_alias(ctx, alias)?, ctx),
+        _ if is_type_op(t) => match expand_type(t, ctx)? {
+            expanded_t if &expanded_t != t => expand_alias(&expanded_t, ctx),
+            expanded_t => Ok(expanded_t),
+        },
+        _ => Ok(t.to_owned()),
+    }
+}
+
+// Returns the string that `typeof` returns for values of type `t`.
+fn typeof_tag(t: &Type, ctx: &Context) -> Option<String> {
+    let tag = match &t.variant {
+        Variant::Prim(prim) => match prim {
+            Primitive::Num => "number",
+            Primitive::Bool => "boolean",
+            Primitive::Str => "string",
+            Primitive::Undefined => "undefined",
+            Primitive::Null => "object",
+            Primitive::Never | Primitive::Unknown | Primitive::Any => return None,
+        },
+        Variant::Lit(lit) => match lit {
+            types::Lit::Num(_) => "number",
+            types::Lit::Bool(_) => "boolean",
+            types::Lit::Str(_) => "string",
+            types::Lit::Undefined => "undefined",
+            types::Lit::Null => "object",
+        },
+        Variant::Lam(_) => "function",
+        Variant::Object(_)
+        | Variant::Index(_)
+        | Variant::Tuple(_)
+        | Variant::Array(_)
+        | Variant::Readonly(_)
+        | Variant::Class(_) => "object",
+        Variant::Alias(_) => return typeof_tag(&expand_alias(t, ctx).ok()?, ctx),
+        _ => return None,
+    };
+    Some(String::from(tag))
+}
+
+// Returns whether values of type `t` are equal to `lit`.  This can only be
+// determined for types with a single value, otherwise we return None if the
+// type contains `lit`.
+fn equals_lit(t: &Type, lit: &Lit, ctx: &Context) -> Option<bool> {
+    match (&t.variant, lit) {
+        (Variant::Prim(Primitive::Null) | Variant::Lit(types::Lit::Null), Lit::Null(_)) => Some(true),
+        (
+            Variant::Prim(Primitive::Undefined) | Variant::Lit(types::Lit::Undefined),
+            Lit::Undefined(_),
+        ) => Some(true),
+        (Variant::Lit(types::Lit::Num(n1)), Lit::Num(n2)) => Some(n1 == &n2.value),
+        (Variant::Lit(types::Lit::Str(s1)), Lit::Str(s2)) => Some(s1 == &s2.value),
+        (Variant::Lit(types::Lit::Bool(b1)), Lit::Bool(b2)) => Some(b1 == &b2.value),
+        (Variant::Prim(Primitive::Num), Lit::Num(_))
+        | (Variant::Prim(Primitive::Str), Lit::Str(_))
+        | (Variant::Prim(Primitive::Bool), Lit::Bool(_)) => None,
+        (Variant::Alias(_), _) => equals_lit(&expand_alias(t, ctx).ok()?, lit, ctx),
+        (Variant::Var, _) => None,
+        _ => Some(false),
+    }
+}
+
+// Returns whether values of type `t` are truthy.  Types with both truthy and
+// falsy values, e.g. `number` which includes `0`, return None.
+fn is_truthy(t: &Type, ctx: &Context) -> Option<bool> {
+    match &t.variant {
+        Variant::Prim(prim) => match prim {
+            Primitive::Null | Primitive::Undefined => Some(false),
+            Primitive::Num
+            | Primitive::Bool
+            | Primitive::Str
+            | Primitive::Never
+            | Primitive::Unknown
+            | Primitive::Any => None,
+        },
+        Variant::Lit(lit) => match lit {
+            types::Lit::Num(n) => Some(n.parse::<f64>() != Ok(0.0)),
+            types::Lit::Bool(b) => Some(*b),
+            types::Lit::Str(s) => Some(!s.is_empty()),
+            types::Lit::Null | types::Lit::Undefined => Some(false),
+        },
+        Variant::Lam(_)
+        | Variant::Object(_)
+        | Variant::Index(_)
+        | Variant::Tuple(_)
+        | Variant::Array(_)
+        | Variant::Readonly(_)
+        | Variant::Class(_) => Some(true),
+        Variant::Alias(_) => is_truthy(&expand_alias(t, ctx).ok()?, ctx),
+        _ => None,
+    }
+}
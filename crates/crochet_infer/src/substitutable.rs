@@ -29,17 +29,52 @@ impl Substitutable for Type {
                     Variant::Union(types) => Variant::Union(types.apply(sub)),
                     Variant::Intersection(types) => Variant::Intersection(types.apply(sub)),
                     Variant::Object(props) => Variant::Object(props.apply(sub)),
+                    Variant::Index(index) => Variant::Index(IndexType {
+                        key: Box::from(index.key.apply(sub)),
+                        value: Box::from(index.value.apply(sub)),
+                        readonly: index.readonly,
+                    }),
                     Variant::Alias(alias) => Variant::Alias(AliasType {
                         type_params: alias.type_params.apply(sub),
                         ..alias.to_owned()
                     }),
                     Variant::Tuple(types) => Variant::Tuple(types.apply(sub)),
                     Variant::Array(t) => Variant::Array(Box::from(t.apply(sub))),
+                    Variant::Readonly(t) => Variant::Readonly(Box::from(t.apply(sub))),
                     Variant::Rest(arg) => Variant::Rest(Box::from(arg.apply(sub))),
                     Variant::Member(member) => Variant::Member(MemberType {
                         obj: Box::from(member.obj.apply(sub)),
                         ..member.to_owned()
                     }),
+                    Variant::KeyOf(t) => Variant::KeyOf(Box::from(t.apply(sub))),
+                    Variant::IndexedAccess(access) => Variant::IndexedAccess(IndexedAccessType {
+                        obj: Box::from(access.obj.apply(sub)),
+                        index: Box::from(access.index.apply(sub)),
+                    }),
+                    Variant::Conditional(cond) => Variant::Conditional(ConditionalType {
+                        check: Box::from(cond.check.apply(sub)),
+                        extends: Box::from(cond.extends.apply(sub)),
+                        true_type: Box::from(cond.true_type.apply(sub)),
+                        false_type: Box::from(cond.false_type.apply(sub)),
+                    }),
+                    Variant::Mapped(mapped) => Variant::Mapped(MappedType {
+                        key: Box::from(mapped.key.apply(sub)),
+                        constraint: Box::from(mapped.constraint.apply(sub)),
+                        value: Box::from(mapped.value.apply(sub)),
+                        ..mapped.to_owned()
+                    }),
+                    Variant::Query(_) | Variant::Infer(_) | Variant::Bound(_) => {
+                        self.variant.to_owned()
+                    }
+                    Variant::TemplateLiteral(tl) => Variant::TemplateLiteral(TemplateLiteralType {
+                        quasis: tl.quasis.to_owned(),
+                        types: tl.types.apply(sub),
+                    }),
+                    Variant::Class(class) => Variant::Class(ClassType {
+                        props: class.props.apply(sub),
+                        type_args: class.type_args.apply(sub),
+                        ..class.to_owned()
+                    }),
                 };
                 Type {
                     variant,
@@ -62,11 +97,54 @@ impl Substitutable for Type {
             Variant::Union(types) => types.ftv(),
             Variant::Intersection(types) => types.ftv(),
             Variant::Object(props) => props.ftv(),
+            Variant::Index(IndexType { key, value, .. }) => {
+                let mut result = key.ftv();
+                result.extend(value.ftv());
+                result
+            }
             Variant::Alias(AliasType { type_params, .. }) => type_params.ftv(),
             Variant::Tuple(types) => types.ftv(),
             Variant::Array(t) => t.ftv(),
+            Variant::Readonly(t) => t.ftv(),
             Variant::Rest(arg) => arg.ftv(),
             Variant::Member(MemberType { obj, .. }) => obj.ftv(),
+            Variant::KeyOf(t) => t.ftv(),
+            Variant::IndexedAccess(IndexedAccessType { obj, index }) => {
+                let mut result = obj.ftv();
+                result.extend(index.ftv());
+                result
+            }
+            Variant::Conditional(ConditionalType {
+                check,
+                extends,
+                true_type,
+                false_type,
+            }) => {
+                let mut result = check.ftv();
+                result.extend(extends.ftv());
+                result.extend(true_type.ftv());
+                result.extend(false_type.ftv());
+                result
+            }
+            Variant::Mapped(MappedType {
+                constraint, value, ..
+            }) => {
+                let mut result = constraint.ftv();
+                result.extend(value.ftv());
+                result
+            }
+            Variant::Query(_) => HashSet::new(),
+            // These are bound by the conditional or mapped type that they
+            // appear in.
+            Variant::Infer(_) | Variant::Bound(_) => HashSet::new(),
+            Variant::TemplateLiteral(TemplateLiteralType { types, .. }) => types.ftv(),
+            Variant::Class(ClassType {
+                props, type_args, ..
+            }) => {
+                let mut result = props.ftv();
+                result.extend(type_args.ftv());
+                result
+            }
         }
     }
 }
@@ -104,7 +182,7 @@ impl Substitutable for Env {
     }
     fn ftv(&self) -> HashSet<i32> {
         // we can't use iter_values() here because it's a consuming iterator
-        self.iter().flat_map(|(_, b)| b.ftv()).collect()
+        self.values().flat_map(|b| b.ftv()).collect()
     }
 }
 
@@ -144,7 +222,7 @@ fn norm_type(ty: Type) -> Type {
             types.sort_by_key(|k| k.id);
 
             if types.len() == 1 {
-                types.get(0).unwrap().to_owned()
+                types.first().unwrap().to_owned()
             } else {
                 Type {
                     variant: Variant::Union(types),
@@ -160,7 +238,7 @@ fn norm_type(ty: Type) -> Type {
             types.sort_by_key(|k| k.id);
 
             if types.len() == 1 {
-                types.get(0).unwrap().to_owned()
+                types.first().unwrap().to_owned()
             } else {
                 Type {
                     variant: Variant::Intersection(types),
@@ -168,6 +246,24 @@ fn norm_type(ty: Type) -> Type {
                 }
             }
         }
+        Variant::Tuple(types) => {
+            // Spreads of tuples are replaced with the tuple's elements, e.g.
+            // `[...T, U]` becomes `[A, B, U]` once `T` is `[A, B]`.
+            let types: Vec<Type> = types
+                .iter()
+                .flat_map(|t| match &t.variant {
+                    Variant::Rest(arg) => match &arg.variant {
+                        Variant::Tuple(types) => types.to_owned(),
+                        _ => vec![t.to_owned()],
+                    },
+                    _ => vec![t.to_owned()],
+                })
+                .collect();
+            Type {
+                variant: Variant::Tuple(types),
+                ..ty
+            }
+        }
         _ => ty,
     }
 }
@@ -5,4 +5,4 @@ pub mod scheme;
 pub use r#type::*;
 pub use lit::*;
 pub use scheme::*;
-pub use crochet_ast::Primitive;
+pub use crochet_ast::{MappedModifier, Primitive};
@@ -3,12 +3,13 @@ use std::fmt;
 use std::hash::Hash;
 
 use crate::Context;
-use crate::types::{Lit, Primitive};
+use crate::types::{Lit, MappedModifier, Primitive};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct TProp {
     pub name: String,
     pub optional: bool,
+    pub readonly: bool,
     pub ty: Type,
 }
 
@@ -23,7 +24,15 @@ impl TProp {
 
 impl fmt::Display for TProp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let Self { name, optional, ty } = self;
+        let Self {
+            name,
+            optional,
+            readonly,
+            ty,
+        } = self;
+        if *readonly {
+            write!(f, "readonly ")?;
+        }
         match optional {
             false => write!(f, "{name}: {ty}"),
             true => write!(f, "{name}?: {ty}"),
@@ -88,6 +97,29 @@ impl Hash for AliasType {
     }
 }
 
+// Classes are nominal, two class types are only the same if they have the same
+// name and type args.  `props` contains the fields and methods of instances of
+// the class.  Only built-in classes like `Promise` have type args.
+#[derive(Clone, Debug, Eq)]
+pub struct ClassType {
+    pub name: String,
+    pub props: Vec<TProp>,
+    pub type_args: Vec<Type>,
+}
+
+impl PartialEq for ClassType {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.type_args == other.type_args
+    }
+}
+
+impl Hash for ClassType {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.type_args.hash(state);
+    }
+}
+
 #[derive(Clone, Debug, Eq)]
 pub struct MemberType {
     pub obj: Box<Type>,
@@ -107,6 +139,52 @@ impl Hash for MemberType {
     }
 }
 
+// An index signature, e.g. `{[key: string]: number}`.  Object types with both
+// props and an index signature are represented as the intersection of an
+// object type and an index signature.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IndexType {
+    pub key: Box<Type>,
+    pub value: Box<Type>,
+    pub readonly: bool,
+}
+
+// `T extends U ? X : Y`, evaluated once `T` is known.  `infer` types in
+// `extends` are replaced in `true_type` with the types they matched.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConditionalType {
+    pub check: Box<Type>,
+    pub extends: Box<Type>,
+    pub true_type: Box<Type>,
+    pub false_type: Box<Type>,
+}
+
+// `{[K in C]: V}`, evaluated once the keys in `C` are known.  `key` is a
+// `Variant::Bound` type which is replaced with each key in `value`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MappedType {
+    pub key: Box<Type>,
+    pub constraint: Box<Type>,
+    pub value: Box<Type>,
+    pub optional: Option<MappedModifier>,
+    pub readonly: Option<MappedModifier>,
+}
+
+// `T[K]` where `K` isn't known yet.  Once it is, it's evaluated to one or more
+// `Variant::Member` types.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IndexedAccessType {
+    pub obj: Box<Type>,
+    pub index: Box<Type>,
+}
+
+// `` `on${string}` ``.  `quasis` has one more element than `types`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TemplateLiteralType {
+    pub quasis: Vec<String>,
+    pub types: Vec<Type>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Variant {
     Var,
@@ -116,11 +194,30 @@ pub enum Variant {
     Union(Vec<Type>),
     Intersection(Vec<Type>),
     Object(Vec<TProp>),
+    Index(IndexType),
     Alias(AliasType),
     Tuple(Vec<Type>),
     Array(Box<Type>),
+    // Only arrays and tuples can be readonly.
+    Readonly(Box<Type>),
     Rest(Box<Type>),
     Member(MemberType),
+    // `keyof T` is evaluated once `T` is known.
+    KeyOf(Box<Type>),
+    // `typeof x` where `x` hadn't been inferred yet when the type annotation
+    // was inferred, e.g. in type declarations.  It's evaluated once `x` is known.
+    Query(String),
+    IndexedAccess(IndexedAccessType),
+    Conditional(ConditionalType),
+    Mapped(MappedType),
+    // `infer T` in the `extends` clause of a conditional type.
+    Infer(String),
+    // A reference to a type declared with `infer` or the key of a mapped type.
+    // These share their `id` with the declaration so that they're replaced
+    // when the declaration is substituted.
+    Bound(String),
+    TemplateLiteral(TemplateLiteralType),
+    Class(ClassType),
 }
 
 #[derive(Clone, Debug, Eq)]
@@ -166,6 +263,14 @@ impl fmt::Display for Type {
                 write!(f, "{}", join(types, " & "))
             }
             Variant::Object(props) => write!(f, "{{{}}}", join(props, ", ")),
+            Variant::Index(IndexType {
+                key,
+                value,
+                readonly,
+            }) => match readonly {
+                true => write!(f, "{{readonly [key: {key}]: {value}}}"),
+                false => write!(f, "{{[key: {key}]: {value}}}"),
+            },
             Variant::Alias(AliasType {
                 name, type_params, ..
             }) => match type_params {
@@ -174,8 +279,54 @@ impl fmt::Display for Type {
             },
             Variant::Tuple(types) => write!(f, "[{}]", join(types, ", ")),
             Variant::Array(t) => write!(f, "{t}[]"),
+            Variant::Readonly(t) => write!(f, "readonly {t}"),
             Variant::Rest(arg) => write!(f, "...{arg}"),
             Variant::Member(MemberType { obj, prop, .. }) => write!(f, "{obj}[\"{prop}\"]"),
+            Variant::KeyOf(t) => write!(f, "keyof {t}"),
+            Variant::Query(name) => write!(f, "typeof {name}"),
+            Variant::IndexedAccess(IndexedAccessType { obj, index }) => write!(f, "{obj}[{index}]"),
+            Variant::Conditional(ConditionalType {
+                check,
+                extends,
+                true_type,
+                false_type,
+            }) => write!(f, "{check} extends {extends} ? {true_type} : {false_type}"),
+            Variant::Mapped(MappedType {
+                key,
+                constraint,
+                value,
+                optional,
+                readonly,
+            }) => {
+                write!(f, "{{")?;
+                match readonly {
+                    Some(MappedModifier::Add) => write!(f, "readonly ")?,
+                    Some(MappedModifier::Remove) => write!(f, "-readonly ")?,
+                    None => (),
+                }
+                write!(f, "[{key} in {constraint}]")?;
+                match optional {
+                    Some(MappedModifier::Add) => write!(f, "?")?,
+                    Some(MappedModifier::Remove) => write!(f, "-?")?,
+                    None => (),
+                }
+                write!(f, ": {value}}}")
+            }
+            Variant::Infer(name) => write!(f, "infer {name}"),
+            Variant::Bound(name) => write!(f, "{name}"),
+            Variant::TemplateLiteral(TemplateLiteralType { quasis, types }) => {
+                write!(f, "`")?;
+                for (quasi, t) in quasis.iter().zip(types) {
+                    write!(f, "{quasi}${{{t}}}")?;
+                }
+                write!(f, "{}`", quasis.last().unwrap())
+            }
+            Variant::Class(ClassType {
+                name, type_args, ..
+            }) => match type_args.is_empty() {
+                true => write!(f, "{name}"),
+                false => write!(f, "{name}<{}>", join(type_args, ", ")),
+            },
         }
     }
 }
@@ -203,6 +354,11 @@ fn set_frozen(ty: Type, frozen: bool) -> Type {
                 })
                 .collect(),
         ),
+        Variant::Index(index) => Variant::Index(IndexType {
+            key: Box::from(freeze(index.key.as_ref().clone())),
+            value: Box::from(freeze(index.value.as_ref().clone())),
+            readonly: index.readonly,
+        }),
         Variant::Alias(alias) => Variant::Alias(AliasType {
             name: alias.name,
             type_params: alias
@@ -211,11 +367,49 @@ fn set_frozen(ty: Type, frozen: bool) -> Type {
         }),
         Variant::Tuple(types) => Variant::Tuple(types.into_iter().map(freeze).collect()),
         Variant::Array(t) => Variant::Array(Box::from(freeze(t.as_ref().clone()))),
+        Variant::Readonly(t) => Variant::Readonly(Box::from(freeze(t.as_ref().clone()))),
         Variant::Rest(arg) => Variant::Rest(Box::from(freeze(arg.as_ref().clone()))),
         Variant::Member(member) => Variant::Member(MemberType {
             obj: Box::from(freeze(member.obj.as_ref().clone())),
             prop: member.prop,
         }),
+        Variant::KeyOf(t) => Variant::KeyOf(Box::from(freeze(t.as_ref().clone()))),
+        Variant::IndexedAccess(access) => Variant::IndexedAccess(IndexedAccessType {
+            obj: Box::from(freeze(access.obj.as_ref().clone())),
+            index: Box::from(freeze(access.index.as_ref().clone())),
+        }),
+        Variant::Conditional(cond) => Variant::Conditional(ConditionalType {
+            check: Box::from(freeze(cond.check.as_ref().clone())),
+            extends: Box::from(freeze(cond.extends.as_ref().clone())),
+            true_type: Box::from(freeze(cond.true_type.as_ref().clone())),
+            false_type: Box::from(freeze(cond.false_type.as_ref().clone())),
+        }),
+        Variant::Mapped(mapped) => Variant::Mapped(MappedType {
+            key: Box::from(freeze(mapped.key.as_ref().clone())),
+            constraint: Box::from(freeze(mapped.constraint.as_ref().clone())),
+            value: Box::from(freeze(mapped.value.as_ref().clone())),
+            optional: mapped.optional,
+            readonly: mapped.readonly,
+        }),
+        Variant::Query(name) => Variant::Query(name),
+        Variant::Infer(name) => Variant::Infer(name),
+        Variant::Bound(name) => Variant::Bound(name),
+        Variant::TemplateLiteral(tl) => Variant::TemplateLiteral(TemplateLiteralType {
+            quasis: tl.quasis,
+            types: tl.types.into_iter().map(freeze).collect(),
+        }),
+        Variant::Class(class) => Variant::Class(ClassType {
+            name: class.name,
+            props: class
+                .props
+                .into_iter()
+                .map(|prop| TProp {
+                    ty: freeze(prop.ty),
+                    ..prop
+                })
+                .collect(),
+            type_args: class.type_args.into_iter().map(freeze).collect(),
+        }),
     };
     Type {
         variant,
@@ -8,12 +8,35 @@ use super::util::*;
 // Returns Ok(substitions) if t2 admits all values from t1 and an Err() otherwise.
 pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
     let result = match (&t1.variant, &t2.variant) {
+        // `infer` types in the `extends` clause of a conditional type are bound
+        // to the type they're matched against.
+        (_, Variant::Infer(_)) => bind(&t2.id, t1),
+        // Type variables are bound to `never`, `unknown` and `any` by the
+        // `Variant::Var` arms below.
+        (Variant::Prim(Primitive::Never), _) if !matches!(t2.variant, Variant::Var) => {
+            Ok(Subst::default())
+        }
+        (_, Variant::Prim(Primitive::Unknown)) if !matches!(t1.variant, Variant::Var) => {
+            Ok(Subst::default())
+        }
+        // Calling a value of type `any` returns `any`.
+        (Variant::Lam(types::LamType { is_call: true, ret, .. }), Variant::Prim(Primitive::Any)) => {
+            unify(ret, t2, ctx)
+        }
+        (Variant::Prim(Primitive::Any), _) if !matches!(t2.variant, Variant::Var) => {
+            Ok(Subst::default())
+        }
+        (_, Variant::Prim(Primitive::Any)) if !matches!(t1.variant, Variant::Var) => {
+            Ok(Subst::default())
+        }
         (Variant::Lit(lit), Variant::Prim(prim)) => {
             let b = matches!(
                 (lit, prim),
                 (types::Lit::Num(_), Primitive::Num)
                     | (types::Lit::Str(_), Primitive::Str)
                     | (types::Lit::Bool(_), Primitive::Bool)
+                    | (types::Lit::Null, Primitive::Null)
+                    | (types::Lit::Undefined, Primitive::Undefined)
             );
             if b {
                 Ok(Subst::default())
@@ -21,6 +44,20 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
                 Err(String::from("Unification failure"))
             }
         }
+        (Variant::Lit(types::Lit::Str(value)), Variant::TemplateLiteral(tl)) => {
+            unify_template_literal(value, &tl.quasis, &tl.types, ctx)
+        }
+        (Variant::TemplateLiteral(_), Variant::Prim(Primitive::Str)) => Ok(Subst::default()),
+        (Variant::TemplateLiteral(tl1), Variant::TemplateLiteral(tl2))
+            if tl1.quasis == tl2.quasis =>
+        {
+            let mut s = Subst::new();
+            for (t1, t2) in tl1.types.iter().zip(&tl2.types) {
+                let s1 = unify(&t1.apply(&s), &t2.apply(&s), ctx)?;
+                s = compose_subs(&s1, &s);
+            }
+            Ok(s)
+        }
         (Variant::Lam(lam1), Variant::Lam(lam2)) => {
             let mut s = Subst::new();
             // If `lam1` is a function call then we treat it differently.  Instead
@@ -136,9 +173,14 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
                 .iter()
                 .map(|prop2| {
                     let mut b = false;
+                    let mut found = false;
                     let mut ss = vec![];
                     for prop1 in props1.iter() {
+                        // Like TypeScript, readonly props can be assigned to
+                        // mutable ones.  Only readonly arrays and tuples can't
+                        // be used where mutable ones are expected.
                         if prop1.name == prop2.name {
+                            found = true;
                             if let Ok(s) = unify(&prop1.get_type(ctx), &prop2.get_type(ctx), ctx) {
                                 b = true;
                                 ss.push(s);
@@ -148,8 +190,10 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
 
                     match b {
                         true => Ok(compose_many_subs(&ss)),
+                        // Optional props can be omitted, but if they're
+                        // present their types still have to match.
                         false => {
-                            if prop2.optional {
+                            if prop2.optional && !found {
                                 Ok(Subst::default())
                             } else {
                                 Err(String::from("Unification failure"))
@@ -163,25 +207,40 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
             Ok(compose_many_subs(&ss))
         }
         (Variant::Tuple(types1), Variant::Tuple(types2)) => {
-            let mut before2: Vec<Type> = vec![];
-            let mut after2: Vec<Type> = vec![];
-            let mut maybe_rest2: Option<Type> = None;
-
-            for t in types2 {
-                match &t.variant {
-                    Variant::Rest(rest_type) => {
-                        if maybe_rest2.is_some() {
-                            return Err(String::from("Only one rest pattern is allowed in a tuple"));
-                        }
-                        maybe_rest2 = Some(rest_type.as_ref().to_owned());
-                    }
-                    _ => match maybe_rest2 {
-                        Some(_) => after2.push(t.to_owned()),
-                        None => before2.push(t.to_owned()),
-                    },
+            let (before1, maybe_rest1, after1) = split_tuple(types1)?;
+            let (before2, maybe_rest2, after2) = split_tuple(types2)?;
+
+            // A tuple with a rest element can have any number of elements so
+            // it can only be assigned to another tuple with a rest element.
+            // The elements around its rest element are matched up with the
+            // ones around the other tuple's rest element.
+            if let Some(rest1) = maybe_rest1 {
+                let rest2 = match maybe_rest2 {
+                    Some(rest2) => rest2,
+                    None => return Err(String::from("Unification failure")),
+                };
+                if before1.len() < before2.len() || after1.len() < after2.len() {
+                    return Err(String::from("not enough elements to unpack"));
+                }
+
+                let mut ss: Vec<Subst> = vec![];
+                for (t1, t2) in before1.iter().zip(before2.iter()) {
+                    ss.push(unify(t1, t2, ctx)?);
                 }
+                let after1_start = after1.len() - after2.len();
+                for (t1, t2) in after1[after1_start..].iter().zip(after2.iter()) {
+                    ss.push(unify(t1, t2, ctx)?);
+                }
+
+                let mut middle1 = before1[before2.len()..].to_vec();
+                middle1.push(ctx.rest(rest1));
+                middle1.extend(after1[..after1_start].to_vec());
+                ss.push(unify(&ctx.tuple(middle1), &rest2, ctx)?);
+
+                return Ok(compose_many_subs(&ss));
             }
 
+            let types1 = before1;
             let min_len = before2.len() + after2.len();
 
             // It's okay if t1 has extra properties, but it has to have all of t2's properties.
@@ -224,12 +283,53 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
             } else {
                 let mut ss = vec![];
                 for t1 in tuple_types.iter() {
-                    let s = unify(t1, array_type.as_ref(), ctx)?;
+                    // The rest element is itself an array (or tuple) so it's
+                    // checked against the whole array type.
+                    let s = match &t1.variant {
+                        Variant::Rest(rest) => unify(rest, t2, ctx)?,
+                        _ => unify(t1, array_type.as_ref(), ctx)?,
+                    };
                     ss.push(s)
                 }
                 Ok(compose_many_subs(&ss))
             }
         }
+        (Variant::Array(elem_type1), Variant::Array(elem_type2)) => {
+            unify(elem_type1, elem_type2, ctx)
+        }
+        // Type operators are evaluated lazily, once their operands are known,
+        // so type variables are bound to the unevaluated type.
+        (Variant::Var, _) if is_type_op(t2) => bind(&t1.id, t2),
+        (_, Variant::Var) if is_type_op(t1) => bind(&t2.id, t1),
+        _ if is_type_op(t1) || is_type_op(t2) => {
+            let expanded_t1 = expand_type(t1, ctx)?;
+            let expanded_t2 = expand_type(t2, ctx)?;
+            match &expanded_t1 != t1 || &expanded_t2 != t2 {
+                true => unify(&expanded_t1, &expanded_t2, ctx),
+                false if t1 == t2 => Ok(Subst::default()),
+                false => Err(String::from("Unification failure")),
+            }
+        }
+        // Aliases are matched against the members of a union before being
+        // expanded so that their type args are compared with those of aliases
+        // with the same name, e.g. `Promise<number>` and `Promise<string>`.
+        // They're only expanded if they expand to a union themselves.
+        (Variant::Alias(alias), Variant::Union(types)) => {
+            let ss: Vec<_> = types.iter().filter_map(|t2| unify(t1, t2, ctx).ok()).collect();
+            if !ss.is_empty() {
+                return Ok(compose_many_subs(&ss));
+            }
+            let alias_t = lookup_alias(ctx, alias)?;
+            match &alias_t.variant {
+                Variant::Union(_) | Variant::Alias(_) => {
+                    unify_assuming(t1, t2, ctx, || unify(&alias_t, t2, ctx))
+                }
+                _ if is_type_op(&alias_t) => {
+                    unify_assuming(t1, t2, ctx, || unify(&alias_t, t2, ctx))
+                }
+                _ => Err(String::from("Unification failure")),
+            }
+        }
         (Variant::Union(types), _) => {
             let result: Result<Vec<_>, _> = types.iter().map(|t1| unify(t1, t2, ctx)).collect();
             let ss = result?; // This is only okay if all calls to is_subtype are okay
@@ -252,6 +352,13 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
         }
         (Variant::Var, _) => bind(&t1.id, t2),
         (_, Variant::Var) => bind(&t2.id, t1),
+        (Variant::Readonly(t1), Variant::Readonly(t2)) => unify(t1, t2, ctx),
+        (Variant::Readonly(_), Variant::Array(_) | Variant::Tuple(_)) => Err(format!(
+            "The type '{t1}' is 'readonly' and cannot be assigned to the mutable type '{t2}'"
+        )),
+        // Mutable arrays and tuples can be used where readonly ones are expected.
+        (_, Variant::Readonly(t2)) => unify(t1, t2, ctx),
+        _ if has_index(t1) || has_index(t2) => unify_index(t1, t2, ctx),
         (Variant::Object(props), Variant::Intersection(types)) => {
             let obj_types: Vec<_> = types
                 .iter()
@@ -284,7 +391,7 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
 
                     let s1 = unify(&ctx.object(obj_props), &obj_type, ctx)?;
 
-                    let rest_type = rest_types.get(0).unwrap();
+                    let rest_type = rest_types.first().unwrap();
                     let s2 = unify(&ctx.object(rest_props), rest_type, ctx)?;
 
                     let s = compose_subs(&s2, &s1);
@@ -323,7 +430,7 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
 
                     let s_obj = unify(&obj_type, &ctx.object(obj_props), ctx)?;
 
-                    let rest_type = rest_types.get(0).unwrap();
+                    let rest_type = rest_types.first().unwrap();
                     let s_rest = unify(rest_type, &ctx.object(rest_props), ctx)?;
 
                     let s = compose_subs(&s_rest, &s_obj);
@@ -332,6 +439,25 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
                 _ => Err(String::from("Unification is undecidable")),
             }
         }
+        (Variant::Class(class1), Variant::Class(class2)) => {
+            if class1.name == class2.name && class1.type_args.len() == class2.type_args.len() {
+                let result: Result<Vec<_>, _> = class1
+                    .type_args
+                    .iter()
+                    .zip(class2.type_args.iter())
+                    .map(|(t1, t2)| unify(t1, t2, ctx))
+                    .collect();
+                Ok(compose_many_subs_with_context(&result?, ctx))
+            } else {
+                Err(String::from("Unification failure"))
+            }
+        }
+        // Instances of a class can be used where an object type is expected as
+        // long as they have all of the properties in the object type.  The
+        // reverse isn't true since classes are nominal.
+        (Variant::Class(class), Variant::Object(_)) => {
+            unify(&ctx.object(class.props.to_owned()), t2, ctx)
+        }
         (Variant::Alias(alias1), Variant::Alias(alias2)) => {
             if alias1.name == alias2.name {
                 match (&alias1.type_params, &alias2.type_params) {
@@ -348,17 +474,22 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
                     _ => Err(String::from("Alias type mismatch")),
                 }
             } else {
-                todo!("unify(): handle aliases that point to another alias")
+                // Expanding `alias1` will eventually lead to `alias2` being
+                // expanded as well if it isn't simply a reference to `alias2`.
+                unify_assuming(t1, t2, ctx, || {
+                    let alias_t = lookup_alias(ctx, alias1)?;
+                    unify(&alias_t, t2, ctx)
+                })
             }
         }
-        (_, Variant::Alias(alias)) => {
+        (_, Variant::Alias(alias)) => unify_assuming(t1, t2, ctx, || {
             let alias_t = lookup_alias(ctx, alias)?;
             unify(t1, &alias_t, ctx)
-        }
-        (Variant::Alias(alias), _) => {
+        }),
+        (Variant::Alias(alias), _) => unify_assuming(t1, t2, ctx, || {
             let alias_t = lookup_alias(ctx, alias)?;
             unify(&alias_t, t2, ctx)
-        }
+        }),
         (v1, v2) => {
             if v1 == v2 {
                 Ok(Subst::new())
@@ -373,6 +504,185 @@ pub fn unify(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
     result
 }
 
+fn has_index(t: &Type) -> bool {
+    match &t.variant {
+        Variant::Index(_) => true,
+        Variant::Intersection(types) => types.iter().any(has_index),
+        _ => false,
+    }
+}
+
+// Returns the props and index signature of an object type, an index
+// signature, or an intersection of them.
+fn object_parts(
+    t: &Type,
+    ctx: &Context,
+) -> Result<(Vec<types::TProp>, Option<types::IndexType>), String> {
+    match &t.variant {
+        Variant::Object(props) => Ok((props.to_owned(), None)),
+        Variant::Class(types::ClassType { props, .. }) => Ok((props.to_owned(), None)),
+        Variant::Index(index) => Ok((vec![], Some(index.to_owned()))),
+        Variant::Alias(alias) => object_parts(&lookup_alias(ctx, alias)?, ctx),
+        Variant::Intersection(types) => {
+            let mut props = vec![];
+            let mut index = None;
+            for t in types {
+                let (t_props, t_index) = object_parts(t, ctx)?;
+                props.extend(t_props);
+                index = t_index.or(index);
+            }
+            Ok((props, index))
+        }
+        _ => Err(String::from("Unification failure")),
+    }
+}
+
+// Unifies object types where at least one of them has an index signature.
+// Props that are missing from `t1` are looked up in its index signature, but
+// only if they're optional in `t2` since the index signature doesn't guarantee
+// that the prop exists.  All of the props in `t1` must be assignable to the
+// index signature in `t2`.
+fn unify_index(t1: &Type, t2: &Type, ctx: &Context) -> Result<Subst, String> {
+    let (props1, index1) = object_parts(t1, ctx)?;
+    let (props2, index2) = object_parts(t2, ctx)?;
+
+    let mut ss = vec![];
+    for prop2 in &props2 {
+        match props1.iter().find(|prop1| prop1.name == prop2.name) {
+            Some(prop1) => ss.push(unify(&prop1.get_type(ctx), &prop2.get_type(ctx), ctx)?),
+            None => match &index1 {
+                Some(index1)
+                    if prop2.optional && index_applies_to_prop(&index1.key, &prop2.name) =>
+                {
+                    ss.push(unify(&index1.value, &prop2.ty, ctx)?)
+                }
+                _ if prop2.optional => (),
+                _ => {
+                    return Err(format!(
+                        "Property '{}' is missing in type '{t1}'",
+                        prop2.name
+                    ))
+                }
+            },
+        }
+    }
+
+    if let Some(index2) = &index2 {
+        for prop1 in &props1 {
+            if index_applies_to_prop(&index2.key, &prop1.name) {
+                ss.push(unify(&prop1.get_type(ctx), &index2.value, ctx)?);
+            }
+        }
+        if let Some(index1) = &index1 {
+            ss.push(unify(&index2.key, &index1.key, ctx)?);
+            ss.push(unify(&index1.value, &index2.value, ctx)?);
+        }
+    }
+
+    Ok(compose_many_subs(&ss))
+}
+
+// Used when expanding aliases so that recursive types can be unified.  If
+// we're already in the process of checking `t1` against `t2` then we assume
+// that the check holds instead of expanding the aliases again (and again).
+fn unify_assuming<F>(t1: &Type, t2: &Type, ctx: &Context, f: F) -> Result<Subst, String>
+where
+    F: FnOnce() -> Result<Subst, String>,
+{
+    let key = (t1.to_owned(), t2.to_owned());
+    if ctx.state.assumptions.borrow().contains(&key) {
+        return Ok(Subst::default());
+    }
+
+    ctx.state.assumptions.borrow_mut().insert(key.clone());
+    let result = f();
+    ctx.state.assumptions.borrow_mut().remove(&key);
+
+    result
+}
+
+// Matches the string `value` against the quasis and placeholder types of a
+// template literal type.  Shorter matches for each placeholder are tried first
+// so an `infer` placeholder is bound to as little of the string as possible,
+// unless it's the last one in which case it's bound to the rest of the string.
+fn unify_template_literal(
+    value: &str,
+    quasis: &[String],
+    types: &[Type],
+    ctx: &Context,
+) -> Result<Subst, String> {
+    let value = match value.strip_prefix(quasis[0].as_str()) {
+        Some(value) => value,
+        None => return Err(String::from("Unification failure")),
+    };
+    match types.split_first() {
+        Some((t, types)) => {
+            let ends = value.char_indices().map(|(i, _)| i).chain([value.len()]);
+            for end in ends {
+                if let Ok(s1) = unify_placeholder(&value[..end], t, ctx) {
+                    if let Ok(s2) = unify_template_literal(&value[end..], &quasis[1..], types, ctx)
+                    {
+                        return Ok(compose_subs(&s2, &s1));
+                    }
+                }
+            }
+            Err(String::from("Unification failure"))
+        }
+        None if value.is_empty() => Ok(Subst::default()),
+        None => Err(String::from("Unification failure")),
+    }
+}
+
+fn unify_placeholder(value: &str, t: &Type, ctx: &Context) -> Result<Subst, String> {
+    match &t.variant {
+        Variant::Prim(Primitive::Num) => {
+            let is_num = value.parse::<f64>().is_ok()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'));
+            match is_num {
+                true => Ok(Subst::default()),
+                false => Err(String::from("Unification failure")),
+            }
+        }
+        Variant::Prim(Primitive::Bool) if value == "true" || value == "false" => {
+            Ok(Subst::default())
+        }
+        Variant::Lit(lit) if lit_to_string(lit) == value => Ok(Subst::default()),
+        Variant::Union(types) => types
+            .iter()
+            .find_map(|t| unify_placeholder(value, t, ctx).ok())
+            .ok_or_else(|| String::from("Unification failure")),
+        _ => unify(&ctx.lit_type(types::Lit::Str(value.to_owned())), t, ctx),
+    }
+}
+
+// Splits the types of a tuple into the ones before the rest element, the rest
+// element's type, and the ones after it.
+#[allow(clippy::type_complexity)]
+fn split_tuple(types: &[Type]) -> Result<(Vec<Type>, Option<Type>, Vec<Type>), String> {
+    let mut before: Vec<Type> = vec![];
+    let mut after: Vec<Type> = vec![];
+    let mut maybe_rest: Option<Type> = None;
+
+    for t in types {
+        match &t.variant {
+            Variant::Rest(rest_type) => {
+                if maybe_rest.is_some() {
+                    return Err(String::from("Only one rest pattern is allowed in a tuple"));
+                }
+                maybe_rest = Some(rest_type.as_ref().to_owned());
+            }
+            _ => match maybe_rest {
+                Some(_) => after.push(t.to_owned()),
+                None => before.push(t.to_owned()),
+            },
+        }
+    }
+
+    Ok((before, maybe_rest, after))
+}
+
 fn bind(id: &i32, t: &Type) -> Result<Subst, String> {
     // | t == TVar a     = return nullSubst
     // | occursCheck a t = throwError $ InfiniteType a t
@@ -393,18 +703,18 @@ fn occurs_check(id: &i32, t: &Type) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crochet_ast::Lit;
+    use crochet_ast::{Lit, Span};
 
     fn num(val: &str) -> Lit {
-        Lit::num(val.to_owned(), 0..0)
+        Lit::num(val.to_owned(), Span::default())
     }
 
     fn str(val: &str) -> Lit {
-        Lit::str(val.to_owned(), 0..0)
+        Lit::str(val.to_owned(), Span::default())
     }
 
     fn bool(val: &bool) -> Lit {
-        Lit::bool(val.to_owned(), 0..0)
+        Lit::bool(val.to_owned(), Span::default())
     }
 
     #[test]
@@ -429,17 +739,20 @@ mod tests {
             types::TProp {
                 name: String::from("foo"),
                 optional: false,
+                readonly: false,
                 ty: ctx.lit(num("5")),
             },
             types::TProp {
                 name: String::from("bar"),
                 optional: false,
+                readonly: false,
                 ty: ctx.lit(bool(&true)),
             },
             // Having extra properties is okay
             types::TProp {
                 name: String::from("baz"),
                 optional: false,
+                readonly: false,
                 ty: ctx.prim(Primitive::Str),
             },
         ]);
@@ -448,11 +761,13 @@ mod tests {
             types::TProp {
                 name: String::from("foo"),
                 optional: false,
+                readonly: false,
                 ty: ctx.prim(Primitive::Num),
             },
             types::TProp {
                 name: String::from("bar"),
                 optional: true,
+                readonly: false,
                 ty: ctx.prim(Primitive::Bool),
             },
             // It's okay for qux to not appear in the subtype since
@@ -460,6 +775,7 @@ mod tests {
             types::TProp {
                 name: String::from("qux"),
                 optional: true,
+                readonly: false,
                 ty: ctx.prim(Primitive::Str),
             },
         ]);
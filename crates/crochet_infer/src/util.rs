@@ -3,9 +3,10 @@ use defaultmap::*;
 use std::collections::{HashMap, HashSet};
 use std::iter::Iterator;
 
-use super::context::{Context, Env};
+use super::context::{lookup_alias, Context, Env};
 use super::substitutable::{Subst, Substitutable};
 use super::types::*;
+use super::unify::unify;
 
 pub fn normalize(sc: &Scheme, ctx: &Context) -> Scheme {
     let body = &sc.ty;
@@ -68,6 +69,7 @@ pub fn normalize(sc: &Scheme, ctx: &Context) -> Scheme {
                     .map(|prop| TProp {
                         name: prop.name.clone(),
                         optional: prop.optional,
+                        readonly: prop.readonly,
                         // NOTE: we don't use prop.get_type(ctx) here because we're tracking
                         // the optionality of the property in the TProp that's returned.
                         ty: norm_type(&prop.ty, mapping, ctx),
@@ -78,6 +80,18 @@ pub fn normalize(sc: &Scheme, ctx: &Context) -> Scheme {
                     ..ty.to_owned()
                 }
             }
+            Variant::Index(IndexType {
+                key,
+                value,
+                readonly,
+            }) => Type {
+                variant: Variant::Index(IndexType {
+                    key: Box::from(norm_type(key, mapping, ctx)),
+                    value: Box::from(norm_type(value, mapping, ctx)),
+                    readonly: *readonly,
+                }),
+                ..ty.to_owned()
+            },
             Variant::Alias(AliasType { name, type_params }) => {
                 let type_params = type_params.clone().map(|params| {
                     params
@@ -104,6 +118,10 @@ pub fn normalize(sc: &Scheme, ctx: &Context) -> Scheme {
                 variant: Variant::Array(Box::from(norm_type(t, mapping, ctx))),
                 ..ty.to_owned()
             },
+            Variant::Readonly(t) => Type {
+                variant: Variant::Readonly(Box::from(norm_type(t, mapping, ctx))),
+                ..ty.to_owned()
+            },
             Variant::Rest(arg) => Type {
                 variant: Variant::Rest(Box::from(norm_type(arg, mapping, ctx))),
                 ..ty.to_owned()
@@ -115,6 +133,71 @@ pub fn normalize(sc: &Scheme, ctx: &Context) -> Scheme {
                 }),
                 ..ty.to_owned()
             },
+            Variant::KeyOf(t) => Type {
+                variant: Variant::KeyOf(Box::from(norm_type(t, mapping, ctx))),
+                ..ty.to_owned()
+            },
+            Variant::IndexedAccess(IndexedAccessType { obj, index }) => Type {
+                variant: Variant::IndexedAccess(IndexedAccessType {
+                    obj: Box::from(norm_type(obj, mapping, ctx)),
+                    index: Box::from(norm_type(index, mapping, ctx)),
+                }),
+                ..ty.to_owned()
+            },
+            Variant::Conditional(ConditionalType {
+                check,
+                extends,
+                true_type,
+                false_type,
+            }) => Type {
+                variant: Variant::Conditional(ConditionalType {
+                    check: Box::from(norm_type(check, mapping, ctx)),
+                    extends: Box::from(norm_type(extends, mapping, ctx)),
+                    true_type: Box::from(norm_type(true_type, mapping, ctx)),
+                    false_type: Box::from(norm_type(false_type, mapping, ctx)),
+                }),
+                ..ty.to_owned()
+            },
+            Variant::Mapped(mapped) => Type {
+                variant: Variant::Mapped(MappedType {
+                    constraint: Box::from(norm_type(&mapped.constraint, mapping, ctx)),
+                    value: Box::from(norm_type(&mapped.value, mapping, ctx)),
+                    ..mapped.to_owned()
+                }),
+                ..ty.to_owned()
+            },
+            Variant::Query(_) | Variant::Infer(_) | Variant::Bound(_) => ty.to_owned(),
+            Variant::TemplateLiteral(TemplateLiteralType { quasis, types }) => Type {
+                variant: Variant::TemplateLiteral(TemplateLiteralType {
+                    quasis: quasis.to_owned(),
+                    types: types.iter().map(|t| norm_type(t, mapping, ctx)).collect(),
+                }),
+                ..ty.to_owned()
+            },
+            Variant::Class(ClassType {
+                name,
+                props,
+                type_args,
+            }) => {
+                let props = props
+                    .iter()
+                    .map(|prop| TProp {
+                        ty: norm_type(&prop.ty, mapping, ctx),
+                        ..prop.to_owned()
+                    })
+                    .collect();
+                Type {
+                    variant: Variant::Class(ClassType {
+                        name: name.to_owned(),
+                        props,
+                        type_args: type_args
+                            .iter()
+                            .map(|t| norm_type(t, mapping, ctx))
+                            .collect(),
+                    }),
+                    ..ty.to_owned()
+                }
+            }
         }
     }
 
@@ -139,6 +222,365 @@ pub fn generalize(env: &Env, ty: &Type) -> Scheme {
 // TODO: handle optional properties correctly
 // Maybe we can have a function that will canonicalize objects by converting 
 // `x: T | undefined` to `x?: T`
+// Index signatures with `string` keys apply to all props while ones with
+// `number` keys only apply to props whose names are numbers.
+pub fn index_applies_to_prop(key: &Type, name: &str) -> bool {
+    match &key.variant {
+        Variant::Prim(Primitive::Num) => name.parse::<f64>().is_ok(),
+        _ => true,
+    }
+}
+
+pub fn is_type_op(t: &Type) -> bool {
+    matches!(
+        t.variant,
+        Variant::KeyOf(_)
+            | Variant::Query(_)
+            | Variant::Member(_)
+            | Variant::IndexedAccess(_)
+            | Variant::Conditional(_)
+            | Variant::Mapped(_)
+    )
+}
+
+// Evaluates type operators, e.g. `keyof T` and `T["x"]`, at the top level of
+// `t`.  Operators whose operands are still type variables can't be evaluated
+// yet and are returned as is.
+pub fn expand_type(t: &Type, ctx: &Context) -> Result<Type, String> {
+    match &t.variant {
+        Variant::KeyOf(obj) => match keys_of(&expand_operand(obj, ctx)?, ctx)? {
+            Some(keys) => Ok(keys),
+            None => Ok(t.to_owned()),
+        },
+        Variant::Query(name) => match ctx.values.get(name) {
+            Some(scheme) => expand_type(&ctx.instantiate(scheme), ctx),
+            None => Err(format!("Can't find value: {name}")),
+        },
+        Variant::Member(MemberType { obj, prop }) => {
+            let obj = expand_operand(obj, ctx)?;
+            match is_unknown(&obj) {
+                true => Ok(t.to_owned()),
+                false => expand_type(&prop_type(&obj, prop, ctx)?, ctx),
+            }
+        }
+        Variant::IndexedAccess(IndexedAccessType { obj, index }) => {
+            let index = expand_operand(index, ctx)?;
+            match &index.variant {
+                Variant::Lit(Lit::Str(name) | Lit::Num(name)) => {
+                    expand_type(&ctx.mem(obj.as_ref().to_owned(), name), ctx)
+                }
+                Variant::Union(types) => {
+                    let types: Result<Vec<_>, String> = types
+                        .iter()
+                        .map(|index| expand_type(&ctx.indexed_access(obj.as_ref().to_owned(), index.to_owned()), ctx))
+                        .collect();
+                    Ok(union_many_types(types?, ctx))
+                }
+                Variant::Prim(Primitive::Str | Primitive::Num) => {
+                    let obj = expand_operand(obj, ctx)?;
+                    match is_unknown(&obj) {
+                        true => Ok(t.to_owned()),
+                        false => expand_type(&index_type(&obj, &index, ctx)?, ctx),
+                    }
+                }
+                _ => Ok(t.to_owned()),
+            }
+        }
+        Variant::Conditional(cond) => {
+            let check = expand_operand(&cond.check, ctx)?;
+            if !check.ftv().is_empty() || is_unknown(&check) {
+                return Ok(t.to_owned());
+            }
+            match &check.variant {
+                // Conditional types distribute over unions.  Substituting a
+                // type param copies the type argument, including its id, so
+                // each occurrence of the type param in the branches can be
+                // replaced with the current member of the union.
+                Variant::Union(types) => {
+                    let types: Result<Vec<_>, String> = types
+                        .iter()
+                        .map(|member| {
+                            let s = Subst::from([(cond.check.id, member.to_owned())]);
+                            let cond = ctx.conditional(
+                                member.to_owned(),
+                                cond.extends.apply(&s),
+                                cond.true_type.apply(&s),
+                                cond.false_type.apply(&s),
+                            );
+                            expand_type(&cond, ctx)
+                        })
+                        .collect();
+                    Ok(union_many_types(types?, ctx))
+                }
+                // Matching `check` against `extends` binds the `infer` types
+                // in `extends` which are then replaced in `true_type`.
+                _ => match unify(&check, &cond.extends, ctx) {
+                    Ok(s) => expand_type(&cond.true_type.apply(&s), ctx),
+                    Err(_) => expand_type(&cond.false_type, ctx),
+                },
+            }
+        }
+        Variant::Mapped(mapped) => expand_mapped(t, mapped, ctx),
+        _ => Ok(t.to_owned()),
+    }
+}
+
+// Type operators can't be evaluated until their operands are known.
+fn is_unknown(t: &Type) -> bool {
+    matches!(t.variant, Variant::Var | Variant::Bound(_)) || is_type_op(t)
+}
+
+fn expand_mapped(t: &Type, mapped: &MappedType, ctx: &Context) -> Result<Type, String> {
+    let keys = expand_operand(&mapped.constraint, ctx)?;
+    let keys: Vec<Type> = match &keys.variant {
+        Variant::Lit(Lit::Str(_) | Lit::Num(_)) => vec![keys],
+        Variant::Union(types)
+            if types
+                .iter()
+                .all(|t| matches!(t.variant, Variant::Lit(Lit::Str(_) | Lit::Num(_)))) =>
+        {
+            types.to_owned()
+        }
+        Variant::Prim(Primitive::Never) => vec![],
+        // `{[K in string]: V}` is the same as `{[key: string]: V}`.
+        Variant::Prim(Primitive::Str | Primitive::Num) => {
+            let value = mapped.value.apply(&Subst::from([(mapped.key.id, keys.to_owned())]));
+            let readonly = mapped.readonly == Some(MappedModifier::Add);
+            return Ok(ctx.index(keys, expand_type(&value, ctx)?, readonly));
+        }
+        _ => return Ok(t.to_owned()),
+    };
+
+    // Mapped types over `keyof T` preserve the modifiers of the props in `T`.
+    let source_props = match &mapped.constraint.variant {
+        Variant::KeyOf(obj) => match expand_operand(obj, ctx)?.variant {
+            Variant::Object(props) => props,
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+
+    let mut props = vec![];
+    for key in keys {
+        let name = match &key.variant {
+            Variant::Lit(Lit::Str(name) | Lit::Num(name)) => name.to_owned(),
+            _ => unreachable!(),
+        };
+        let source_prop = source_props.iter().find(|prop| prop.name == name);
+        let value = mapped.value.apply(&Subst::from([(mapped.key.id, key)]));
+        let mut value = expand_type(&value, ctx)?;
+        // The type of optional props includes `undefined` when accessed.
+        if source_prop.is_some_and(|prop| prop.optional) {
+            if let Variant::Union(types) = &value.variant {
+                let types: Vec<_> = types
+                    .iter()
+                    .filter(|t| t.variant != Variant::Prim(Primitive::Undefined))
+                    .cloned()
+                    .collect();
+                value = union_many_types(types, ctx);
+            }
+        }
+        props.push(TProp {
+            name,
+            optional: match mapped.optional {
+                Some(MappedModifier::Add) => true,
+                Some(MappedModifier::Remove) => false,
+                None => source_prop.is_some_and(|prop| prop.optional),
+            },
+            readonly: match mapped.readonly {
+                Some(MappedModifier::Add) => true,
+                Some(MappedModifier::Remove) => false,
+                None => source_prop.is_some_and(|prop| prop.readonly),
+            },
+            ty: value,
+        });
+    }
+    Ok(ctx.object(props))
+}
+
+fn expand_operand(t: &Type, ctx: &Context) -> Result<Type, String> {
+    match &t.variant {
+        Variant::Alias(alias) => expand_operand(&lookup_alias(ctx, alias)?, ctx),
+        _ if is_type_op(t) => {
+            let expanded_t = expand_type(t, ctx)?;
+            match &expanded_t == t {
+                true => Ok(expanded_t),
+                false => expand_operand(&expanded_t, ctx),
+            }
+        }
+        _ => Ok(t.to_owned()),
+    }
+}
+
+fn keys_of(t: &Type, ctx: &Context) -> Result<Option<Type>, String> {
+    let keys = match &t.variant {
+        Variant::Var => return Ok(None),
+        Variant::Object(props) | Variant::Class(ClassType { props, .. }) => union_many_types(
+            props
+                .iter()
+                .map(|prop| ctx.lit_type(Lit::Str(prop.name.to_owned())))
+                .collect(),
+            ctx,
+        ),
+        Variant::Index(IndexType { key, .. }) => key.as_ref().to_owned(),
+        Variant::Intersection(types) => {
+            let mut keys = vec![];
+            for t in types {
+                match keys_of(&expand_operand(t, ctx)?, ctx)? {
+                    Some(t_keys) => keys.push(t_keys),
+                    None => return Ok(None),
+                }
+            }
+            union_many_types(keys, ctx)
+        }
+        Variant::Prim(Primitive::Any) => {
+            union_many_types(vec![ctx.prim(Primitive::Str), ctx.prim(Primitive::Num)], ctx)
+        }
+        _ => return Err(format!("Type '{t}' has no keys")),
+    };
+    Ok(Some(keys))
+}
+
+fn prop_type(t: &Type, prop: &str, ctx: &Context) -> Result<Type, String> {
+    match &t.variant {
+        Variant::Object(props) | Variant::Class(ClassType { props, .. }) => {
+            match props.iter().find(|p| p.name == prop) {
+                Some(p) => Ok(p.get_type(ctx)),
+                None => Err(format!("Property '{prop}' does not exist on type '{t}'")),
+            }
+        }
+        Variant::Index(IndexType { key, value, .. }) if index_applies_to_prop(key, prop) => {
+            Ok(value.as_ref().to_owned())
+        }
+        Variant::Intersection(types) => {
+            let types: Result<Vec<_>, String> =
+                types.iter().map(|t| expand_operand(t, ctx)).collect();
+            let types = types?;
+            let obj = types.iter().find(|t| match &t.variant {
+                Variant::Object(props) => props.iter().any(|p| p.name == prop),
+                _ => false,
+            });
+            let index = types.iter().find(|t| matches!(t.variant, Variant::Index(_)));
+            match obj.or(index) {
+                Some(t) => prop_type(t, prop, ctx),
+                None => Err(format!("Property '{prop}' does not exist on type '{t}'")),
+            }
+        }
+        Variant::Union(types) => {
+            let types: Result<Vec<_>, String> = types
+                .iter()
+                .map(|t| prop_type(&expand_operand(t, ctx)?, prop, ctx))
+                .collect();
+            Ok(union_many_types(types?, ctx))
+        }
+        Variant::Tuple(types) => match prop.parse::<usize>().ok().and_then(|i| types.get(i)) {
+            Some(t) => Ok(t.to_owned()),
+            None => Err(format!("Tuple type '{t}' has no element at index '{prop}'")),
+        },
+        Variant::Array(elem_t) if prop.parse::<usize>().is_ok() => Ok(elem_t.as_ref().to_owned()),
+        Variant::Readonly(t) => prop_type(t, prop, ctx),
+        Variant::Prim(Primitive::Any) => Ok(t.to_owned()),
+        _ => Err(format!("Property '{prop}' does not exist on type '{t}'")),
+    }
+}
+
+// Handles `T[string]` and `T[number]`.
+fn index_type(t: &Type, index: &Type, ctx: &Context) -> Result<Type, String> {
+    match (&t.variant, &index.variant) {
+        (Variant::Array(elem_t), Variant::Prim(Primitive::Num)) => Ok(elem_t.as_ref().to_owned()),
+        (Variant::Tuple(types), Variant::Prim(Primitive::Num)) => {
+            Ok(union_many_types(types.to_owned(), ctx))
+        }
+        (Variant::Readonly(t), _) => index_type(t, index, ctx),
+        (Variant::Index(IndexType { key, value, .. }), _) if key.as_ref() == index => {
+            Ok(value.as_ref().to_owned())
+        }
+        (Variant::Intersection(types), _) => {
+            match types.iter().find(|t| matches!(t.variant, Variant::Index(_))) {
+                Some(t) => index_type(t, index, ctx),
+                None => Err(format!("Type '{t}' has no matching index signature for type '{index}'")),
+            }
+        }
+        (Variant::Prim(Primitive::Any), _) => Ok(t.to_owned()),
+        _ => Err(format!("Type '{t}' has no matching index signature for type '{index}'")),
+    }
+}
+
+fn union_many_types(types: Vec<Type>, ctx: &Context) -> Type {
+    types
+        .iter()
+        .fold(ctx.prim(Primitive::Never), |accum, t| union_types(&accum, t, ctx))
+}
+
+// The string that a literal is converted to when it's used in a template
+// literal.
+pub fn lit_to_string(lit: &Lit) -> String {
+    match lit {
+        Lit::Num(n) => num_to_string(n),
+        Lit::Bool(b) => b.to_string(),
+        Lit::Str(s) => s.to_owned(),
+        Lit::Null => String::from("null"),
+        Lit::Undefined => String::from("undefined"),
+    }
+}
+
+// Number literals keep their source text, e.g. `1.50`, but are converted to
+// strings the way JavaScript converts them, e.g. "1.5".  JavaScript only uses
+// exponents for numbers >= 1e21 or < 1e-6, e.g. "1e+21" and "1e-7".
+fn num_to_string(n: &str) -> String {
+    match n.parse::<f64>() {
+        Ok(n) if n != 0.0 && (n.abs() >= 1e21 || n.abs() < 1e-6) => {
+            let s = format!("{n:e}");
+            match s.split_once('e') {
+                Some((mantissa, exp)) if !exp.starts_with('-') => format!("{mantissa}e+{exp}"),
+                _ => s,
+            }
+        }
+        Ok(n) => n.to_string(),
+        Err(_) => n.to_owned(),
+    }
+}
+
+// Creates a template literal type.  If all of the `types` are literals, or
+// unions of literals, the result is the string literal (or union of string
+// literals) for every combination of them instead, e.g. `` `${"a" | "b"}-x` ``
+// is `"a-x" | "b-x"`.
+pub fn template_literal_type(quasis: Vec<String>, types: Vec<Type>, ctx: &Context) -> Type {
+    fn literal_strings(t: &Type, ctx: &Context) -> Option<Vec<String>> {
+        match &t.variant {
+            Variant::Lit(lit) => Some(vec![lit_to_string(lit)]),
+            Variant::Union(types) => {
+                let strings: Option<Vec<_>> =
+                    types.iter().map(|t| literal_strings(t, ctx)).collect();
+                Some(strings?.concat())
+            }
+            Variant::Alias(alias) => literal_strings(&lookup_alias(ctx, alias).ok()?, ctx),
+            _ => None,
+        }
+    }
+
+    let mut results = vec![quasis[0].to_owned()];
+    for (t, quasi) in types.iter().zip(&quasis[1..]) {
+        let strings = match literal_strings(t, ctx) {
+            Some(strings) => strings,
+            None => return ctx.template_literal(quasis, types),
+        };
+        results = results
+            .iter()
+            .flat_map(|prefix| strings.iter().map(move |s| format!("{prefix}{s}{quasi}")))
+            .collect();
+    }
+
+    let mut types: Vec<_> = results
+        .into_iter()
+        .map(|s| ctx.lit_type(Lit::Str(s)))
+        .collect();
+    match types.len() {
+        1 => types.remove(0),
+        _ => ctx.union(types),
+    }
+}
+
 pub fn simplify_intersection(in_types: &[Type], ctx: &Context) -> Type {
     let obj_types: Vec<_> = in_types
         .iter()
@@ -150,11 +592,17 @@ pub fn simplify_intersection(in_types: &[Type], ctx: &Context) -> Type {
 
     // The use of HashSet<Type> here is to avoid duplicate types
     let mut props_map: DefaultHashMap<String, HashSet<Type>> = defaulthashmap!();
-    for props in obj_types {
-        for prop in props {
+    for props in &obj_types {
+        for prop in props.iter() {
             props_map[prop.name.clone()].insert(prop.ty.clone());
         }
     }
+    // A prop is readonly if it's readonly in any of the object types.
+    let is_readonly = |name: &str| {
+        obj_types
+            .iter()
+            .any(|props| props.iter().any(|prop| prop.name == name && prop.readonly))
+    };
 
     let mut props: Vec<TProp> = props_map
         .iter()
@@ -171,6 +619,7 @@ pub fn simplify_intersection(in_types: &[Type], ctx: &Context) -> Type {
                 // the same name.  This should only be optional if all of
                 // the TProps with the current name are optional.
                 optional: false,
+                readonly: is_readonly(name),
                 ty,
             }
         })
@@ -210,16 +659,27 @@ pub fn union_types(t1: &Type, t2: &Type, ctx: &Context) -> Type {
     types.extend(flatten_types(t1));
     types.extend(flatten_types(t2));
 
+    // `any` and `unknown` subsume all other types while `never` doesn't add
+    // anything to a union.
+    for prim in [Primitive::Any, Primitive::Unknown] {
+        if let Some(t) = types.iter().find(|t| t.variant == Variant::Prim(prim.clone())) {
+            return t.to_owned();
+        }
+    }
+    types.retain(|t| t.variant != Variant::Prim(Primitive::Never));
+    if types.is_empty() {
+        return ctx.prim(Primitive::Never);
+    }
+
     let types_set: HashSet<_> = types.iter().cloned().collect();
 
     let prim_types: HashSet<_> = types_set
         .iter()
-        .cloned()
         .filter(|ty| matches!(ty.variant, Variant::Prim(_)))
+        .cloned()
         .collect();
     let lit_types: HashSet<_> = types_set
         .iter()
-        .cloned()
         .filter(|ty| match &ty.variant {
             // Primitive types subsume corresponding literal types
             Variant::Lit(lit) => match lit {
@@ -231,11 +691,12 @@ pub fn union_types(t1: &Type, t2: &Type, ctx: &Context) -> Type {
             },
             _ => false,
         })
+        .cloned()
         .collect();
     let rest_types: HashSet<_> = types_set
         .iter()
-        .cloned()
         .filter(|ty| !matches!(ty.variant, Variant::Prim(_) | Variant::Lit(_)))
+        .cloned()
         .collect();
 
     let mut types: Vec<_> = prim_types
@@ -5,14 +5,14 @@ use crochet_ast::*;
 
 use super::expr::expr_parser;
 use super::pattern::pattern_parser;
-use super::type_ann::type_ann_parser;
+use super::type_ann::{type_ann_parser, type_elem_parser};
 use super::type_params::type_params;
 
-pub fn just_with_padding(inputs: &str) -> Padded<Just<char, &str, Simple<char>>> {
+pub fn just_with_padding(inputs: &str) -> Padded<Just<char, &str, Simple<char, Span>>> {
     just(inputs).padded()
 }
 
-pub fn decl_parser() -> impl Parser<char, Statement, Error = Simple<char>> {
+pub fn decl_parser() -> impl Parser<char, Statement, Error = Simple<char, Span>> {
     // We use `just` instead of `just_with_padding` here to ensure that
     // the span doesn't include leading whitespace.
     let pattern = pattern_parser();
@@ -71,10 +71,18 @@ pub fn decl_parser() -> impl Parser<char, Statement, Error = Simple<char>> {
             }
         });
 
-    choice((type_decl(), var_decl_with_init, var_decl))
+    choice((
+        type_decl(),
+        enum_decl(),
+        class_decl(),
+        interface_decl(),
+        namespace_decl(),
+        var_decl_with_init,
+        var_decl,
+    ))
 }
 
-fn type_decl() -> impl Parser<char, Statement, Error = Simple<char>> {
+fn type_decl() -> impl Parser<char, Statement, Error = Simple<char, Span>> {
     let type_ann = type_ann_parser();
     let ident = text::ident().map_with_span(|name, span: Span| Ident { name, span });
 
@@ -96,3 +104,157 @@ fn type_decl() -> impl Parser<char, Statement, Error = Simple<char>> {
 
     type_decl
 }
+
+fn enum_decl() -> impl Parser<char, Statement, Error = Simple<char, Span>> {
+    let type_ann = type_ann_parser();
+    let ident = text::ident().map_with_span(|name, span: Span| Ident { name, span });
+
+    let field = text::ident()
+        .then(just("?").or_not())
+        .then_ignore(just_with_padding(":"))
+        .then(type_ann.clone())
+        .map_with_span(|((name, optional), type_ann), span: Span| TProp {
+            span,
+            name,
+            optional: optional.is_some(),
+            readonly: false,
+            type_ann: Box::from(type_ann),
+        });
+
+    let fields = field
+        .separated_by(just_with_padding(","))
+        .allow_trailing()
+        .delimited_by(just_with_padding("("), just_with_padding(")"));
+
+    let variant = ident
+        .then(fields.or_not())
+        .map_with_span(|(name, fields), span: Span| EnumVariant { span, name, fields })
+        .padded();
+
+    just("enum")
+        .ignore_then(ident.padded())
+        .then(type_params(type_ann.boxed()).or_not())
+        .then(
+            variant
+                .separated_by(just_with_padding(","))
+                .allow_trailing()
+                .delimited_by(just_with_padding("{"), just_with_padding("}")),
+        )
+        .map_with_span(
+            |((id, type_params), variants), span| Statement::EnumDecl {
+                span,
+                id,
+                type_params,
+                variants,
+            },
+        )
+}
+
+fn interface_decl() -> impl Parser<char, Statement, Error = Simple<char, Span>> {
+    let type_ann = type_ann_parser();
+    let ident = text::ident().map_with_span(|name, span: Span| Ident { name, span });
+
+    let type_args = type_ann
+        .clone()
+        .separated_by(just_with_padding(","))
+        .allow_trailing()
+        .delimited_by(just_with_padding("<"), just_with_padding(">"));
+
+    let type_ref = text::ident()
+        .separated_by(just("."))
+        .at_least(1)
+        .map(|names| names.join("."))
+        .then(type_args.or_not())
+        .map_with_span(|(name, type_params), span| TypeRef {
+            span,
+            name,
+            type_params,
+        })
+        .padded();
+
+    let extends = text::keyword("extends")
+        .ignore_then(type_ref.separated_by(just_with_padding(",")).at_least(1))
+        .or_not()
+        .map(|extends| extends.unwrap_or_default());
+
+    // Members can be separated by commas, semicolons, or newlines.
+    let member = type_elem_parser()
+        .then_ignore(one_of(",;").padded().or_not())
+        .padded();
+
+    text::keyword("interface")
+        .ignore_then(ident.padded())
+        .then(type_params(type_ann.boxed()).or_not())
+        .then(extends)
+        .then(
+            member
+                .repeated()
+                .delimited_by(just_with_padding("{"), just_with_padding("}")),
+        )
+        .map_with_span(
+            |(((id, type_params), extends), members), span| Statement::InterfaceDecl {
+                span,
+                id,
+                type_params,
+                extends,
+                members,
+            },
+        )
+}
+
+fn namespace_decl() -> impl Parser<char, Statement, Error = Simple<char, Span>> {
+    let ident = text::ident().map_with_span(|name, span: Span| Ident { name, span });
+
+    text::keyword("namespace")
+        .ignore_then(ident.padded())
+        .then(
+            choice((type_decl(), interface_decl()))
+                .padded()
+                .repeated()
+                .delimited_by(just_with_padding("{"), just_with_padding("}")),
+        )
+        .map_with_span(|(id, body), span| Statement::NamespaceDecl { span, id, body })
+}
+
+fn class_decl() -> impl Parser<char, Statement, Error = Simple<char, Span>> {
+    let ident = text::ident().map_with_span(|name, span: Span| Ident { name, span });
+
+    let prop = ident
+        .then(just("?").or_not())
+        .then_ignore(just_with_padding(":"))
+        .then(type_ann_parser())
+        .map_with_span(|((key, optional), type_ann), span: Span| {
+            ClassMember::Prop(ClassProp {
+                span,
+                key,
+                optional: optional.is_some(),
+                type_ann,
+            })
+        });
+
+    // Methods are written as a name followed by a lambda, e.g.
+    // `area() => this.w * this.h`.
+    let method = ident
+        .then(expr_parser())
+        .try_map(|(key, expr), span: Span| match expr {
+            Expr::Lambda(lambda) => match key.name.as_str() {
+                "constructor" => Ok(ClassMember::Constructor(Constructor {
+                    span,
+                    params: lambda.params,
+                    body: lambda.body,
+                })),
+                _ => Ok(ClassMember::Method(ClassMethod { span, key, lambda })),
+            },
+            _ => Err(Simple::custom(span, "Expected a method")),
+        });
+
+    text::keyword("class")
+        .ignore_then(ident.padded())
+        .then(
+            choice((prop, method))
+                .padded()
+                .repeated()
+                .delimited_by(just_with_padding("{"), just_with_padding("}")),
+        )
+        .map_with_span(|(id, members), span| Statement::ClassDecl { span, id, members })
+}
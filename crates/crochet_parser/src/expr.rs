@@ -7,7 +7,7 @@ use crate::type_ann::type_ann_parser;
 use crate::type_params::type_params;
 use crate::util::just_with_padding;
 
-pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
+pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char, Span>> {
     let type_ann = type_ann_parser();
     let pattern = pattern_parser();
 
@@ -19,7 +19,14 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
         just_with_padding("false").map_with_span(|_, span| Expr::Lit(Lit::bool(false, span)));
     let r#bool = choice((r#true, r#false));
 
-    let int = text::int::<char, Simple<char>>(10)
+    let null = text::keyword("null")
+        .map_with_span(|_, span: Span| Expr::Lit(Lit::Null(Null { span })))
+        .padded();
+    let undefined = text::keyword("undefined")
+        .map_with_span(|_, span: Span| Expr::Lit(Lit::Undefined(Undefined { span })))
+        .padded();
+
+    let int = text::int::<char, Simple<char, Span>>(10)
         .map_with_span(|value, span| Expr::Lit(Lit::num(value, span)));
     let real = text::int(10)
         .chain(just('.'))
@@ -34,7 +41,7 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
         .collect::<String>()
         .map_with_span(|value, span| Expr::Lit(Lit::str(value, span)));
 
-    let parser = recursive(|expr: Recursive<'_, char, Expr, Simple<char>>| {
+    let parser = recursive(|expr: Recursive<'_, char, Expr, Simple<char, Span>>| {
         // TODO: support recursive functions to be declared within another function
         // let let_rec = ...
 
@@ -44,15 +51,19 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
             .or_not()
             .then(expr.clone())
             .separated_by(just_with_padding(";"))
-            .then(just_with_padding(";").or_not())
+            .then(
+                just_with_padding(";")
+                    .map_with_span(|_, span: Span| span)
+                    .or_not(),
+            )
             .delimited_by(just_with_padding("{"), just_with_padding("}"))
-            .map(|(lets, trailing_semi)| {
+            .map_with_span(|(lets, trailing_semi), span: Span| {
                 let mut iter = lets.iter().rev();
 
                 // TODO: if `lets` is empty then we should return the empty type
 
                 let last = match trailing_semi {
-                    Some(_) => Expr::Empty(Empty { span: 0..0 }),
+                    Some(span) => Expr::Empty(Empty { span }),
                     None => {
                         match iter.next() {
                             Some(term) => match term {
@@ -61,20 +72,19 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                                 (Some(_), _) => panic!("Didn't expect `let` here"),
                                 (_, expr) => expr.clone(),
                             },
-                            None => Expr::Empty(Empty { span: 0..0 }),
+                            None => Expr::Empty(Empty { span }),
                         }
                     }
                 };
 
                 let result: Expr = iter.fold(last, |body, (pattern, value)| {
                     let start = match pattern {
-                        Some(pattern) => pattern.span().start,
-                        None => value.span().start,
+                        Some(pattern) => pattern.span(),
+                        None => value.span(),
                     };
-                    let end = body.span().end;
 
                     Expr::Let(Let {
-                        span: start..end,
+                        span: start.to(&body.span()),
                         pattern: pattern.to_owned(),
                         init: Box::new(value.to_owned()),
                         body: Box::new(body),
@@ -165,12 +175,15 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
             .delimited_by(just_with_padding("["), just_with_padding("]"))
             .map_with_span(|elems, span: Span| Expr::Tuple(Tuple { span, elems }));
 
-        let template_str = ident
-            .or_not()
-            .then_ignore(just("`"))
-            .then(
-                take_until(just("${"))
-                    .map_with_span(|(chars, _), span: Span| {
+        let template_str = just("`")
+            .ignore_then(
+                // Quasis can't contain backticks otherwise we'd consume
+                // subsequent template literals looking for the next `${`.
+                none_of("`$")
+                    .or(just('$').then_ignore(none_of("{").rewind()))
+                    .repeated()
+                    .then_ignore(just("${"))
+                    .map_with_span(|chars, span: Span| {
                         let lit = Lit::str(chars.iter().collect(), span.clone());
                         TemplateElem {
                             span,
@@ -193,34 +206,50 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                         }),
                     ),
             )
-            .map_with_span(|(tag, (body, tail)), span: Span| {
+            .map_with_span(|(body, tail), span: Span| {
                 let (mut quasis, exprs): (Vec<_>, Vec<_>) = body.iter().cloned().unzip();
                 quasis.push(tail);
-                let template = TemplateLiteral {
-                    span: span.clone(),
+                TemplateLiteral {
+                    span,
                     exprs,
                     quasis,
-                };
-                match tag {
-                    Some(tag) => {
-                        Expr::TaggedTemplateLiteral(TaggedTemplateLiteral {
-                            span, // TODO: adjust this to include the tag
-                            tag,
-                            template,
-                        })
-                    }
-                    None => Expr::TemplateLiteral(template),
                 }
             });
 
+        let args = just_with_padding("...")
+            .map_with_span(|_, span: Span| span)
+            .or_not()
+            .then(expr.clone())
+            .map_with_span(|(spread, arg), _: Span| ExprOrSpread {
+                spread,
+                expr: Box::from(arg),
+            })
+            .separated_by(just_with_padding(","))
+            .allow_trailing()
+            .delimited_by(just_with_padding("("), just_with_padding(")"));
+
+        let new = text::keyword("new")
+            .ignore_then(ident.padded())
+            .then(args.clone())
+            .map_with_span(|(id, args), span: Span| {
+                Expr::New(New {
+                    span,
+                    expr: Box::from(Expr::Ident(id)),
+                    args,
+                })
+            });
+
         let atom = choice((
             // quarks (can't be broken down any further)
             r#bool,
+            null,
+            undefined,
             num,
             r#str,
             // can contain sub-expressions, but have the highest precedence
-            template_str,
+            template_str.clone().map(Expr::TemplateLiteral),
             if_else,
+            new,
             ident.map(Expr::Ident),
             obj,
             tuple,
@@ -232,6 +261,7 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
         enum Suffix {
             Member(MemberProp, Span),
             Call(Vec<ExprOrSpread>, Span),
+            TaggedTemplate(TemplateLiteral),
         }
 
         // NOTE: We use this approach of parsing suffixes instead of using a recursive
@@ -240,18 +270,9 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
             .clone()
             .then(
                 choice((
-                    just_with_padding("...")
-                        .map_with_span(|_, span: Span| span)
-                        .or_not()
-                        .then(expr.clone())
-                        .map_with_span(|(spread, arg), _: Span| ExprOrSpread {
-                            spread,
-                            expr: Box::from(arg),
-                        })
-                        .separated_by(just_with_padding(","))
-                        .allow_trailing()
-                        .delimited_by(just_with_padding("("), just_with_padding(")"))
-                        .map_with_span(Suffix::Call),
+                    args.map_with_span(Suffix::Call),
+                    // Any expression can be used as the tag, e.g. `styled.div`.
+                    template_str.padded().map(Suffix::TaggedTemplate),
                     just_with_padding(".")
                         .ignore_then(text::ident())
                         .map_with_span(|name, span: Span| {
@@ -261,6 +282,15 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                             });
                             Suffix::Member(prop, span)
                         }),
+                    expr.clone()
+                        .delimited_by(just_with_padding("["), just_with_padding("]"))
+                        .map_with_span(|expr, span: Span| {
+                            let prop = MemberProp::Computed(ComputedPropName {
+                                span: span.clone(),
+                                expr: Box::from(expr),
+                            });
+                            Suffix::Member(prop, span)
+                        }),
                 ))
                 .repeated(),
             )
@@ -270,25 +300,26 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                 // method on a literal doesn't make sense.
                 match suffix {
                     Suffix::Member(prop, span) => {
-                        let start = f.span().start;
-                        let end = span.end;
-
                         Expr::Member(Member {
-                            span: start..end,
+                            span: f.span().to(&span),
                             obj: Box::new(f),
                             prop,
                         })
                     }
                     Suffix::Call(args, span) => {
-                        let start = f.span().start;
-                        let end = span.end;
-
                         Expr::App(App {
-                            span: start..end,
+                            span: f.span().to(&span),
                             lam: Box::new(f),
                             args,
                         })
                     }
+                    Suffix::TaggedTemplate(template) => {
+                        Expr::TaggedTemplateLiteral(TaggedTemplateLiteral {
+                            span: f.span().to(&template.span),
+                            tag: Box::new(f),
+                            template,
+                        })
+                    }
                 }
             });
 
@@ -304,19 +335,36 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                 None => arg,
             });
 
-        let product = r#await
+        let unary = choice((
+            just("!").to(UnaryOp::Not),
+            text::keyword("typeof").to(UnaryOp::Typeof),
+        ))
+        .map_with_span(|op, span: Span| (op, span))
+        .padded()
+        .repeated()
+        .then(r#await)
+        .foldr(|(op, span), arg| {
+            let span = span.to(&arg.span());
+            Expr::Unary(Unary {
+                span,
+                op,
+                arg: Box::from(arg),
+            })
+        });
+
+        let product = unary
             .clone()
             .then(
                 choice((
                     just_with_padding("*").to(BinOp::Mul),
                     just_with_padding("/").to(BinOp::Div),
                 ))
-                .then(r#await.clone())
+                .then(unary.clone())
                 .repeated(),
             )
             .foldl(|left, (op, right)| {
                 // atoms are already using source spans since they're WithSpan<Expr>
-                let span = left.span().start..right.span().end;
+                let span = left.span().to(&right.span());
                 Expr::Op(Op {
                     span,
                     op,
@@ -337,7 +385,7 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
             )
             .foldl(|left, (op, right)| {
                 // products are already using source spans since they're WithSpan<Expr>
-                let span = left.span().start..right.span().end;
+                let span = left.span().to(&right.span());
                 Expr::Op(Op {
                     span,
                     op,
@@ -346,8 +394,47 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                 })
             });
 
+        enum Cast {
+            As(TypeAnn),
+            Satisfies(TypeAnn),
+        }
+
+        // `as` and `satisfies` bind tighter than comparisons so that
+        // `x as number == 5` compares the result of the assertion.
+        let cast = sum
+            .clone()
+            .then(
+                choice((
+                    text::keyword("as")
+                        .padded()
+                        .ignore_then(type_ann.clone())
+                        .map(Cast::As),
+                    text::keyword("satisfies")
+                        .padded()
+                        .ignore_then(type_ann.clone())
+                        .map(Cast::Satisfies),
+                ))
+                .map_with_span(|cast, span: Span| (cast, span))
+                .repeated(),
+            )
+            .foldl(|expr, (cast, span)| {
+                let span = expr.span().to(&span);
+                match cast {
+                    Cast::As(type_ann) => Expr::As(As {
+                        span,
+                        expr: Box::from(expr),
+                        type_ann,
+                    }),
+                    Cast::Satisfies(type_ann) => Expr::Satisfies(Satisfies {
+                        span,
+                        expr: Box::from(expr),
+                        type_ann,
+                    }),
+                }
+            });
+
         // TODO: logic operators
-        let comp = sum
+        let comp = cast
             .clone()
             .then(
                 choice((
@@ -360,12 +447,12 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                     just_with_padding("<=").to(BinOp::LtEq),
                     just_with_padding("<").to(BinOp::Lt),
                 ))
-                .then(sum.clone())
+                .then(cast.clone())
                 .repeated(),
             )
             .foldl(|left, (op, right)| {
-                // sums are already using source spans since they're WithSpan<Expr>
-                let span = left.span().start..right.span().end;
+                // casts are already using source spans since they're WithSpan<Expr>
+                let span = left.span().to(&right.span());
                 Expr::Op(Op {
                     span,
                     op,
@@ -374,6 +461,18 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                 })
             });
 
+        let assign = comp
+            .clone()
+            .then(just_with_padding("=").ignore_then(expr.clone()).or_not())
+            .map_with_span(|(left, right), span: Span| match right {
+                Some(right) => Expr::Assign(Assign {
+                    span,
+                    left: Box::from(left),
+                    right: Box::from(right),
+                }),
+                None => left,
+            });
+
         let param_list = pattern_parser()
             .separated_by(just_with_padding(","))
             .allow_trailing()
@@ -406,7 +505,7 @@ pub fn expr_parser() -> BoxedParser<'static, char, Expr, Simple<char>> {
                 },
             );
 
-        choice((lam, block, comp))
+        choice((lam, block, assign))
     });
 
     parser.boxed()
@@ -4,14 +4,39 @@ use crochet_ast::*;
 use crate::util::just_with_padding;
 
 pub fn jsx_parser(
-    expr: BoxedParser<'static, char, Expr, Simple<char>>,
-) -> BoxedParser<'static, char, Expr, Simple<char>> {
+    expr: BoxedParser<'static, char, Expr, Simple<char, Span>>,
+) -> BoxedParser<'static, char, Expr, Simple<char, Span>> {
     let str_lit = just("\"")
         .ignore_then(filter(|c| *c != '"').repeated().at_least(1))
         .then_ignore(just("\""))
         .collect::<String>()
         .map_with_span(Lit::str);
 
+    // JSX identifiers can contain dashes, e.g. `aria-label` or `data-foo`.
+    let jsx_ident = filter(|c: &char| c.is_ascii_alphabetic() || *c == '_' || *c == '$')
+        .chain(
+            filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_' || *c == '$' || *c == '-')
+                .repeated(),
+        )
+        .collect::<String>()
+        .map_with_span(|name, span| Ident { name, span });
+
+    let jsx_element_name = jsx_ident
+        .map(JSXElementName::Ident)
+        .then(
+            just(".")
+                .ignore_then(text::ident().map_with_span(|name, span| Ident { name, span }))
+                .repeated(),
+        )
+        .foldl(|obj, prop| {
+            let span = obj.span().to(&prop.span);
+            JSXElementName::Member(JSXMemberExpr {
+                span,
+                obj: Box::from(obj),
+                prop,
+            })
+        });
+
     let jsx_text = filter(|c| *c != '<' && *c != '{')
         .repeated()
         .at_least(1)
@@ -23,88 +48,101 @@ pub fn jsx_parser(
         .then_ignore(just_with_padding("}"))
         .map_with_span(|expr, span| JSXExprContainer { span, expr });
 
-    let jsx_attr = text::ident()
-        .map_with_span(|name, span| Ident { name, span })
-        .then_ignore(just_with_padding("="))
-        .then(choice((
-            str_lit.map(JSXAttrValue::Lit),
-            jsx_expr.clone().map(JSXAttrValue::JSXExprContainer),
-        )))
-        .map_with_span(|(name, value), span| JSXAttr {
-            span,
-            ident: name,
-            value,
+    let jsx_spread = just_with_padding("{")
+        .ignore_then(just_with_padding("..."))
+        .ignore_then(expr)
+        .then_ignore(just_with_padding("}"));
+
+    let jsx_attr = jsx_ident
+        .then(
+            just_with_padding("=")
+                .ignore_then(choice((
+                    str_lit.map(JSXAttrValue::Lit),
+                    jsx_expr.clone().map(JSXAttrValue::JSXExprContainer),
+                )))
+                .or_not(),
+        )
+        .map_with_span(|(name, value), span| {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span,
+                ident: name,
+                value,
+            })
         })
+        .or(jsx_spread.clone().map_with_span(|expr, span| {
+            JSXAttrOrSpread::JSXSpreadAttr(JSXSpreadAttr { span, expr })
+        }))
         .padded();
 
     let jsx_head = just_with_padding("<")
-        .ignore_then(text::ident().padded())
+        .ignore_then(jsx_element_name.padded())
         .then(jsx_attr.clone().repeated())
         .then_ignore(just_with_padding(">"));
 
     let jsx_tail = just("<")
         .ignore_then(just("/"))
-        .ignore_then(text::ident().padded())
+        .ignore_then(jsx_element_name.padded())
         .then_ignore(just_with_padding(">"));
 
     let jsx_element_self_closing = just_with_padding("<")
-        .ignore_then(text::ident().padded())
-        .then(jsx_attr.clone().repeated())
+        .ignore_then(jsx_element_name.padded())
+        .then(jsx_attr.repeated())
         .then_ignore(just_with_padding("/>"))
-        .map_with_span(|(head, attrs), span| JSXElement {
+        .map_with_span(|(name, attrs), span| JSXElement {
             span,
-            name: head,
+            name,
             attrs,
             children: vec![],
         });
 
-    let jsx_element_child = recursive(|jsx_element_child| {
-        // TODO: dedupe with `jsx_element` below
+    // Parses elements and fragments, both of which can appear as children
+    // of other elements and fragments.
+    let jsx_node = recursive(|jsx_node| {
+        let jsx_element_child = choice((
+            jsx_node,
+            jsx_spread.map_with_span(|expr, span| {
+                JSXElementChild::JSXSpreadChild(JSXSpreadChild { span, expr })
+            }),
+            jsx_expr.clone().map(JSXElementChild::JSXExprContainer),
+            jsx_text.map(JSXElementChild::JSXText),
+        ));
+
         let jsx_element = jsx_head
-            .clone()
-            .then(jsx_element_child.repeated())
+            .then(jsx_element_child.clone().repeated())
             .then(jsx_tail)
             .map_with_span(|(((head, attrs), children), tail), span| {
-                if head != tail {
+                if head.to_string() != tail.to_string() {
                     panic!("JSX head and tail elements must match")
                 }
 
-                JSXElementChild::JSXElement(Box::from(JSXElement {
+                JSXElement {
                     span,
                     name: head,
                     attrs,
                     children,
-                }))
+                }
             });
 
-        choice((
-            jsx_element,
-            jsx_element_self_closing
-                .clone()
-                .map(|elem| JSXElementChild::JSXElement(Box::from(elem))),
-            jsx_expr.clone().map(JSXElementChild::JSXExprContainer),
-            jsx_text.map(JSXElementChild::JSXText),
-        ))
-    });
-
-    // TODO: dedupe with `jsx_element` above
-    let jsx_element = jsx_head
-        .then(jsx_element_child.repeated())
-        .then(jsx_tail)
-        .map_with_span(|(((head, attrs), children), tail), span| {
-            if head != tail {
-                panic!("JSX head and tail elements must match")
-            }
+        let jsx_fragment = just_with_padding("<")
+            .ignore_then(just_with_padding(">"))
+            .ignore_then(jsx_element_child.repeated())
+            .then_ignore(
+                just("<")
+                    .ignore_then(just("/"))
+                    .ignore_then(just_with_padding(">")),
+            )
+            .map_with_span(|children, span| JSXFragment { span, children });
 
-            Expr::JSXElement(JSXElement {
-                span,
-                name: head,
-                attrs,
-                children,
-            })
-        });
+        choice((jsx_element, jsx_element_self_closing))
+            .map(|elem| JSXElementChild::JSXElement(Box::from(elem)))
+            .or(jsx_fragment.map(JSXElementChild::JSXFragment))
+    });
 
-    let parser = choice((jsx_element, jsx_element_self_closing.map(Expr::JSXElement)));
+    let parser = jsx_node.map(|node| match node {
+        JSXElementChild::JSXElement(elem) => Expr::JSXElement(*elem),
+        JSXElementChild::JSXFragment(frag) => Expr::JSXFragment(frag),
+        _ => unreachable!("jsx_node only parses elements and fragments"),
+    });
 
     parser.boxed()
 }
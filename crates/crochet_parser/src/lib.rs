@@ -12,14 +12,15 @@ pub use expr::expr_parser;
 use chumsky::prelude::*;
 use chumsky::primitive::*;
 use chumsky::text::Padded;
+use chumsky::Stream;
 
 use crochet_ast::*;
 
-pub fn just_with_padding(inputs: &str) -> Padded<Just<char, &str, Simple<char>>> {
+pub fn just_with_padding(inputs: &str) -> Padded<Just<char, &str, Simple<char, Span>>> {
     just(inputs).padded()
 }
 
-pub fn parser() -> impl Parser<char, Program, Error = Simple<char>> {
+pub fn parser() -> impl Parser<char, Program, Error = Simple<char, Span>> {
     let program = choice((
         decl_parser(),
         expr_parser().map_with_span(|expr, span: Span| Statement::Expr { expr, span }),
@@ -31,12 +32,42 @@ pub fn parser() -> impl Parser<char, Program, Error = Simple<char>> {
     program.then_ignore(end())
 }
 
+// Feeds the chars in `file` to a parser along with their byte spans so that
+// the spans in the resulting AST identify `file`.
+pub fn stream(
+    file: &SourceFile,
+) -> Stream<'_, char, Span, impl Iterator<Item = (char, Span)> + '_> {
+    let id = file.id();
+    let len = file.src().len() as u32;
+    let eoi = Span {
+        file: id,
+        start: len,
+        end: len,
+    };
+    let chars = file.src().char_indices().map(move |(start, c)| {
+        let start = start as u32;
+        let span = Span {
+            file: id,
+            start,
+            end: start + c.len_utf8() as u32,
+        };
+        (c, span)
+    });
+
+    Stream::from_iter(eoi, chars)
+}
+
+pub fn parse(file: &SourceFile) -> Result<Program, Vec<Simple<char, Span>>> {
+    parser().parse(stream(file))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn parse(input: &str) -> Program {
-        parser().parse(input).unwrap()
+        let file = SourceFile::new(FileId(0), "input.crochet", input);
+        super::parse(&file).unwrap()
     }
 
     #[test]
@@ -54,6 +85,7 @@ mod tests {
         insta::assert_debug_snapshot!(parse("`Hello, \"world\"`"));
         insta::assert_debug_snapshot!(parse("`foo ${`bar ${baz}`}`"));
         insta::assert_debug_snapshot!(parse("sql`SELECT * FROM ${table} WHERE id = ${id}`"));
+        insta::assert_debug_snapshot!(parse("styled.div`color: ${color};`"));
     }
 
     #[test]
@@ -144,12 +176,37 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn jsx_fragments_and_attributes() {
+        insta::assert_debug_snapshot!(parse("<>Hello, {world}</>"));
+        insta::assert_debug_snapshot!(parse("<Foo {...props} bar={baz} />"));
+        insta::assert_debug_snapshot!(parse("<input disabled />"));
+        insta::assert_debug_snapshot!(parse("<Ctx.Provider value={5}></Ctx.Provider>"));
+        insta::assert_debug_snapshot!(parse("<div data-foo=\"bar\" aria-label=\"baz\" />"));
+        insta::assert_debug_snapshot!(parse("<Foo>{...children}</Foo>"));
+        insta::assert_debug_snapshot!(parse("<Foo><><Bar /></></Foo>"));
+    }
+
     #[test]
     #[should_panic="JSX head and tail elements must match"]
     fn jsx_head_and_tail_must_match() {
         parse("<Foo>Hello</Bar>");
     }
 
+    #[test]
+    #[should_panic="JSX head and tail elements must match"]
+    fn jsx_member_head_and_tail_must_match() {
+        parse("<Ctx.Provider>Hello</Ctx.Consumer>");
+    }
+
+    #[test]
+    fn namespaces() {
+        insta::assert_debug_snapshot!(parse(
+            "namespace JSX { type Element = {} interface IntrinsicElements { div: {} } }"
+        ));
+        insta::assert_debug_snapshot!(parse("let render = (elem: JSX.Element) => elem"));
+    }
+
     #[test]
     fn type_annotations() {
         insta::assert_debug_snapshot!(parse("let x: number = 5"));
@@ -193,6 +250,69 @@ mod tests {
         insta::assert_debug_snapshot!(parse(r#"type Foo<T extends string = "foo"> = {bar: T}"#));
     }
 
+    #[test]
+    fn enum_decls() {
+        insta::assert_debug_snapshot!(parse("enum Shape { Circle(r: number), Square(s: number) }"));
+        insta::assert_debug_snapshot!(parse("enum Option<T> { Some(value: T), None }"));
+        insta::assert_debug_snapshot!(parse("enum Color {\n    Red,\n    Green,\n    Blue,\n}"));
+    }
+
+    #[test]
+    fn class_decls() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            class Point {
+                x: number
+                y: number
+                constructor(x: number, y: number) => {
+                    this.x = x;
+                    this.y = y;
+                }
+                norm_sq() => this.x * this.x + this.y * this.y
+            }
+            "#
+        ));
+        insta::assert_debug_snapshot!(parse("class Foo {}"));
+        insta::assert_debug_snapshot!(parse("let p = new Point(5, 10)"));
+        insta::assert_debug_snapshot!(parse("new Point(5, 10).norm_sq()"));
+    }
+
+    #[test]
+    fn interface_decls() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            interface Foo<T> extends Bar<T>, Baz {
+                x: T
+                y?: number;
+                concat(other: string): string,
+                <U>(U): T
+            }
+            "#
+        ));
+        insta::assert_debug_snapshot!(parse("interface Empty {}"));
+        insta::assert_debug_snapshot!(parse("interface Point {x: number, y: number}"));
+    }
+
+    #[test]
+    fn unary_ops() {
+        insta::assert_debug_snapshot!(parse("typeof x == \"string\""));
+        insta::assert_debug_snapshot!(parse("!x"));
+        insta::assert_debug_snapshot!(parse("x != null"));
+    }
+
+    #[test]
+    fn computed_member_access() {
+        insta::assert_debug_snapshot!(parse("scores[name]"));
+        insta::assert_debug_snapshot!(parse("foo.bar[\"baz\"].qux"));
+    }
+
+    #[test]
+    fn type_assertions() {
+        insta::assert_debug_snapshot!(parse("let x = JSON.parse(str) as {x: number}"));
+        insta::assert_debug_snapshot!(parse("let p = {x: 5, y: 10} satisfies Point"));
+        insta::assert_debug_snapshot!(parse("a + b as number == c"));
+    }
+
     #[test]
     fn blocks() {
         insta::assert_debug_snapshot!(parse("let foo = {let x = 5; x}"));
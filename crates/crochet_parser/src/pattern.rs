@@ -6,7 +6,7 @@ use crate::util::just_with_padding;
 
 // NOTE: Destructuring assignments admits different patterns from destructuring
 // function params.  We'll need to have different parsers for those.
-pub fn pattern_parser() -> BoxedParser<'static, char, Pattern, Simple<char>> {
+pub fn pattern_parser() -> BoxedParser<'static, char, Pattern, Simple<char, Span>> {
     let type_ann = type_ann_parser();
     let mut top_level = true;
 
@@ -14,7 +14,7 @@ pub fn pattern_parser() -> BoxedParser<'static, char, Pattern, Simple<char>> {
     let r#false = just_with_padding("false").map_with_span(|_, span| Lit::bool(false, span));
     let r#bool = choice((r#true, r#false));
 
-    let int = text::int::<char, Simple<char>>(10).map_with_span(Lit::num);
+    let int = text::int::<char, Simple<char, Span>>(10).map_with_span(Lit::num);
     let real = text::int(10)
         .chain(just('.'))
         .chain::<char, _, _>(text::digits(10))
@@ -6,13 +6,16 @@ use crate::util::just_with_padding;
 
 use super::type_params::type_params;
 
-pub fn type_ann_parser() -> BoxedParser<'static, char, TypeAnn, Simple<char>> {
+pub fn type_ann_parser() -> BoxedParser<'static, char, TypeAnn, Simple<char, Span>> {
     let prim = choice((
         just("number").to(Primitive::Num),
         just("string").to(Primitive::Str),
         just("boolean").to(Primitive::Bool),
         just("null").to(Primitive::Null),
         just("undefined").to(Primitive::Undefined),
+        text::keyword("never").to(Primitive::Never),
+        text::keyword("unknown").to(Primitive::Unknown),
+        text::keyword("any").to(Primitive::Any),
     ))
     .map_with_span(|prim, span| TypeAnn::Prim(PrimType { span, prim }))
     .padded();
@@ -31,7 +34,7 @@ pub fn type_ann_parser() -> BoxedParser<'static, char, TypeAnn, Simple<char>> {
     });
     let r#bool = choice((r#true, r#false));
 
-    let int = text::int::<char, Simple<char>>(10).map_with_span(|value, span: Span| {
+    let int = text::int::<char, Simple<char, Span>>(10).map_with_span(|value, span: Span| {
         TypeAnn::Lit(LitType {
             span: span.clone(),
             lit: Lit::num(value, span),
@@ -67,8 +70,12 @@ pub fn type_ann_parser() -> BoxedParser<'static, char, TypeAnn, Simple<char>> {
             .allow_trailing()
             .delimited_by(just_with_padding("<"), just_with_padding(">"));
 
+        // Type names can be qualified, e.g. `JSX.Element`.
         let type_ref =
             text::ident()
+                .separated_by(just("."))
+                .at_least(1)
+                .map(|names| names.join("."))
                 .then(type_args.or_not())
                 .map_with_span(|(name, type_params), span| {
                     TypeAnn::TypeRef(TypeRef {
@@ -78,58 +85,225 @@ pub fn type_ann_parser() -> BoxedParser<'static, char, TypeAnn, Simple<char>> {
                     })
                 });
 
-        let prop = text::ident()
+        let prop = prop_name()
             .then(just("?").or_not())
             .then_ignore(just_with_padding(":"))
             .then(type_ann.clone())
-            .map_with_span(|((name, optional), type_ann), span: Span| TProp {
+            .map_with_span(|(((readonly, name), optional), type_ann), span: Span| TProp {
                 span,
                 name,
                 optional: optional.is_some(),
+                readonly,
                 type_ann: Box::from(type_ann),
             });
 
-        let obj = prop
-            .separated_by(just_with_padding(","))
-            .allow_trailing()
-            .delimited_by(just_with_padding("{"), just_with_padding("}"))
-            .map_with_span(|props, span: Span| TypeAnn::Object(ObjectType { span, props }));
+        enum ObjElem {
+            Prop(TProp),
+            Index(TIndex),
+        }
+
+        let obj = choice((
+            index_signature(type_ann.clone()).map(ObjElem::Index),
+            prop.map(ObjElem::Prop),
+        ))
+        .separated_by(just_with_padding(","))
+        .allow_trailing()
+        .delimited_by(just_with_padding("{"), just_with_padding("}"))
+        .validate(|elems, span: Span, emit| {
+            let mut props = vec![];
+            let mut index = None;
+            for elem in elems {
+                match elem {
+                    ObjElem::Prop(prop) => props.push(prop),
+                    ObjElem::Index(elem) => {
+                        if index.is_some() {
+                            emit(Simple::custom(
+                                elem.span.clone(),
+                                "Only one index signature is allowed in an object type",
+                            ));
+                        }
+                        index = Some(Box::from(elem));
+                    }
+                }
+            }
+            TypeAnn::Object(ObjectType { span, props, index })
+        });
+
+        let modifier = choice((
+            just("+").to(MappedModifier::Add),
+            just("-").to(MappedModifier::Remove),
+        ))
+        .or_not();
 
-        let tuple = type_ann
+        // A missing `+` or `-` prefix is the same as `+`.
+        let mapped = modifier
             .clone()
+            .then_ignore(text::keyword("readonly"))
+            .padded()
+            .or_not()
+            .then(
+                text::ident()
+                    .map_with_span(|name, span| Ident { span, name })
+                    .padded()
+                    .then_ignore(text::keyword("in").padded())
+                    .then(type_ann.clone())
+                    .delimited_by(just_with_padding("["), just_with_padding("]")),
+            )
+            .then(modifier.then_ignore(just("?")).padded().or_not())
+            .then_ignore(just_with_padding(":"))
+            .then(type_ann.clone())
+            .then_ignore(just_with_padding(",").or_not())
+            .delimited_by(just_with_padding("{"), just_with_padding("}"))
+            .map_with_span(
+                |(((readonly, (type_param, constraint)), optional), type_ann), span: Span| {
+                    TypeAnn::Mapped(MappedType {
+                        span,
+                        type_param,
+                        constraint: Box::from(constraint),
+                        optional: optional.map(|m| m.unwrap_or(MappedModifier::Add)),
+                        readonly: readonly.map(|m| m.unwrap_or(MappedModifier::Add)),
+                        type_ann: Box::from(type_ann),
+                    })
+                },
+            );
+
+        let tuple = rest(type_ann.clone())
+            .or(type_ann.clone())
             .separated_by(just_with_padding(","))
             .allow_trailing()
             .delimited_by(just_with_padding("["), just_with_padding("]"))
             .map_with_span(|types, span: Span| TypeAnn::Tuple(TupleType { span, types }));
 
+        let query = text::keyword("typeof")
+            .padded()
+            .ignore_then(text::ident().map_with_span(|name, span| Ident { span, name }))
+            .map_with_span(|name, span: Span| TypeAnn::Query(QueryType { span, name }));
+
+        let infer = text::keyword("infer")
+            .padded()
+            .ignore_then(text::ident().map_with_span(|name, span| Ident { span, name }))
+            .map_with_span(|name, span: Span| TypeAnn::Infer(InferType { span, name }));
+
+        // Quasis end at the next placeholder or the closing backtick.
+        let quasi = none_of("`$")
+            .or(just('$').then_ignore(none_of("{").rewind()))
+            .repeated()
+            .collect::<String>()
+            .map_with_span(|value, span: Span| {
+                let lit = Lit::str(value, span.clone());
+                TemplateElem {
+                    span,
+                    raw: lit.clone(),
+                    cooked: lit,
+                }
+            });
+
+        let template = just("`")
+            .ignore_then(
+                quasi
+                    .clone()
+                    .then_ignore(just("${"))
+                    .then(type_ann.clone().then_ignore(just("}")))
+                    .repeated()
+                    .then(quasi),
+            )
+            .then_ignore(just("`"))
+            .map_with_span(|(body, tail), span: Span| {
+                let (mut quasis, types): (Vec<_>, Vec<_>) = body.into_iter().unzip();
+                quasis.push(tail);
+                TypeAnn::TemplateLiteral(TemplateLiteralType {
+                    span,
+                    quasis,
+                    types,
+                })
+            })
+            .padded();
+
         let atom = choice((
             r#bool,
             num,
             r#str,
+            template,
             prim,
+            mapped,
             obj,
             tuple,
+            query,
+            infer,
             type_ref,
             type_ann
                 .clone()
                 .delimited_by(just_with_padding("("), just_with_padding(")")),
         ));
 
+        #[derive(Clone)]
+        enum Suffix {
+            Array,
+            IndexedAccess(TypeAnn),
+        }
+
+        let suffix = choice((
+            just_with_padding("[]").to(Suffix::Array),
+            type_ann
+                .clone()
+                .delimited_by(just_with_padding("["), just_with_padding("]"))
+                .map(Suffix::IndexedAccess),
+        ))
+        .map_with_span(|suffix, span: Span| (suffix, span));
+
+        // `T[]` is an array type and `T["prop"]` is an indexed access type.
+        // Suffixes are applied from left to right so `T["prop"][]` is an
+        // array of `T["prop"]`.
         let atom_with_suffix = atom
             .clone()
-            .then(just_with_padding("[]").repeated())
-            .foldl(|accum, _| {
-                TypeAnn::Array(ArrayType {
-                    span: 0..0, // FIXME
-                    elem_type: Box::from(accum),
+            .map_with_span(|type_ann, span: Span| (type_ann, span))
+            .then(suffix.repeated())
+            .foldl(|(accum, accum_span), (suffix, suffix_span)| {
+                let span = accum_span.to(&suffix_span);
+                let type_ann = match suffix {
+                    Suffix::Array => TypeAnn::Array(ArrayType {
+                        span: span.clone(),
+                        elem_type: Box::from(accum),
+                    }),
+                    Suffix::IndexedAccess(index_type) => {
+                        TypeAnn::IndexedAccess(IndexedAccessType {
+                            span: span.clone(),
+                            obj_type: Box::from(accum),
+                            index_type: Box::from(index_type),
+                        })
+                    }
+                };
+                (type_ann, span)
+            })
+            .map(|(type_ann, _)| type_ann);
+
+        // `readonly` applies to the whole array type, e.g. `readonly string[]`
+        // is a readonly array of strings.
+        let readonly = text::keyword("readonly")
+            .padded()
+            .ignore_then(atom_with_suffix.clone())
+            .map_with_span(|type_ann, span: Span| {
+                TypeAnn::Readonly(ReadonlyType {
+                    span,
+                    type_ann: Box::from(type_ann),
+                })
+            });
+
+        let keyof = text::keyword("keyof")
+            .padded()
+            .ignore_then(atom_with_suffix.clone())
+            .map_with_span(|type_ann, span: Span| {
+                TypeAnn::KeyOf(KeyOfType {
+                    span,
+                    type_ann: Box::from(type_ann),
                 })
             });
 
         // We have to use `atom` here instead of `type_ann` to avoid a stack
         // overflow.
-        let intersection = atom_with_suffix
-            .clone()
+        let intersection = choice((readonly, keyof, atom_with_suffix.clone()))
             .separated_by(just_with_padding("&"))
+            .at_least(1)
             .map_with_span(|types, span| match types.len() {
                 1 => types[0].clone(),
                 _ => TypeAnn::Intersection(IntersectionType { span, types }),
@@ -137,14 +311,13 @@ pub fn type_ann_parser() -> BoxedParser<'static, char, TypeAnn, Simple<char>> {
 
         let union = intersection
             .separated_by(just_with_padding("|"))
+            .at_least(1)
             .map_with_span(|types, span| match types.len() {
                 1 => types[0].clone(),
                 _ => TypeAnn::Union(UnionType { span, types }),
             });
 
-        // TODO: support optional lambda param names
-        let lam_params = type_ann
-            .clone()
+        let lam_params = lam_param(type_ann.clone())
             .separated_by(just_with_padding(","))
             .allow_trailing()
             .delimited_by(just_with_padding("("), just_with_padding(")"));
@@ -163,23 +336,199 @@ pub fn type_ann_parser() -> BoxedParser<'static, char, TypeAnn, Simple<char>> {
                 })
             });
 
+        // Conditional types have lower precedence than union types so that
+        // `A | B extends C ? D : E` checks `A | B`.
+        let conditional = union
+            .clone()
+            .then(
+                text::keyword("extends")
+                    .padded()
+                    .ignore_then(choice((lam.clone(), union)))
+                    .then_ignore(just_with_padding("?"))
+                    .then(type_ann.clone())
+                    .then_ignore(just_with_padding(":"))
+                    .then(type_ann.clone())
+                    .or_not(),
+            )
+            .map_with_span(|(check_type, branches), span| match branches {
+                Some(((extends_type, true_type), false_type)) => {
+                    TypeAnn::Conditional(ConditionalType {
+                        span,
+                        check_type: Box::from(check_type),
+                        extends_type: Box::from(extends_type),
+                        true_type: Box::from(true_type),
+                        false_type: Box::from(false_type),
+                    })
+                }
+                None => check_type,
+            });
+
         choice((
             // lambda types have higher precedence than union types so that
             // `(A, B) => C | D` parse as lambda type with a return type that
             // happens to be a union.
-            lam, union, atom,
+            lam,
+            conditional,
+            atom,
         ))
     });
 
     parser.boxed()
 }
 
+// Parses the members of an interface: props, method signatures and call
+// signatures.  Param names in signatures are optional.
+pub fn type_elem_parser() -> BoxedParser<'static, char, TypeElem, Simple<char, Span>> {
+    let type_ann = type_ann_parser();
+
+    let params = lam_param(type_ann.clone())
+        .separated_by(just_with_padding(","))
+        .allow_trailing()
+        .delimited_by(just_with_padding("("), just_with_padding(")"));
+
+    let sig = type_params(type_ann.clone())
+        .or_not()
+        .then(params)
+        .then_ignore(just_with_padding(":"))
+        .then(type_ann.clone())
+        .map_with_span(|((type_params, params), ret), span| LamType {
+            span,
+            params,
+            ret: Box::from(ret),
+            type_params,
+        });
+
+    let prop = prop_name()
+        .then(just("?").or_not())
+        .then_ignore(just_with_padding(":"))
+        .then(type_ann)
+        .map_with_span(|(((readonly, name), optional), type_ann), span: Span| {
+            TypeElem::Prop(TProp {
+                span,
+                name,
+                optional: optional.is_some(),
+                readonly,
+                type_ann: Box::from(type_ann),
+            })
+        });
+
+    let method = text::ident()
+        .then(just("?").or_not())
+        .then(sig.clone())
+        .map_with_span(|((name, optional), lam), span: Span| {
+            TypeElem::Method(TMethod {
+                span,
+                name,
+                optional: optional.is_some(),
+                lam,
+            })
+        });
+
+    let call = sig.map(TypeElem::Call);
+
+    let index = index_signature(type_ann_parser()).map(TypeElem::Index);
+
+    choice((index, prop, method, call)).boxed()
+}
+
+// Parses `...T` in tuple types and lambda params.
+fn rest<P>(type_ann: P) -> impl Parser<char, TypeAnn, Error = Simple<char, Span>> + Clone
+where
+    P: Parser<char, TypeAnn, Error = Simple<char, Span>> + Clone,
+{
+    just_with_padding("...")
+        .ignore_then(type_ann)
+        .map_with_span(|type_ann, span: Span| {
+            TypeAnn::Rest(RestType {
+                span,
+                type_ann: Box::from(type_ann),
+            })
+        })
+}
+
+// Parses a param in a lambda type or signature, e.g. `number`, `x: number` or
+// `...args: number[]`.  Param names are optional.
+fn lam_param<P>(type_ann: P) -> impl Parser<char, TFnParam, Error = Simple<char, Span>> + Clone
+where
+    P: Parser<char, TypeAnn, Error = Simple<char, Span>> + Clone,
+{
+    let name = text::ident()
+        .map_with_span(|name, span| Ident { span, name })
+        .then_ignore(just_with_padding(":"))
+        .padded();
+
+    let rest_param = just_with_padding("...")
+        .ignore_then(name.or_not())
+        .then(type_ann.clone())
+        .map_with_span(|(name, type_ann), span: Span| TFnParam {
+            span: span.clone(),
+            name,
+            type_ann: Box::from(TypeAnn::Rest(RestType {
+                span,
+                type_ann: Box::from(type_ann),
+            })),
+        });
+
+    let param = name
+        .or_not()
+        .then(type_ann)
+        .map_with_span(|(name, type_ann), span| TFnParam {
+            span,
+            name,
+            type_ann: Box::from(type_ann),
+        });
+
+    rest_param.or(param)
+}
+
+// Parses an index signature, e.g. `[key: string]: number`.
+fn index_signature<P>(type_ann: P) -> impl Parser<char, TIndex, Error = Simple<char, Span>> + Clone
+where
+    P: Parser<char, TypeAnn, Error = Simple<char, Span>> + Clone,
+{
+    text::keyword("readonly")
+        .padded()
+        .or_not()
+        .then(
+            text::ident()
+                .map_with_span(|name, span| Ident { span, name })
+                .then_ignore(just_with_padding(":"))
+                .then(type_ann.clone())
+                .delimited_by(just_with_padding("["), just_with_padding("]")),
+        )
+        .then_ignore(just_with_padding(":"))
+        .then(type_ann)
+        .map_with_span(|((readonly, (key, key_type)), type_ann), span: Span| TIndex {
+            span,
+            key,
+            key_type: Box::from(key_type),
+            readonly: readonly.is_some(),
+            type_ann: Box::from(type_ann),
+        })
+}
+
+// Parses the name of a prop along with whether it's `readonly`.  Props can
+// also be named `readonly`, e.g. `{readonly: boolean}`.
+fn prop_name() -> impl Parser<char, (bool, String), Error = Simple<char, Span>> + Clone {
+    choice((
+        text::keyword("readonly")
+            .padded()
+            .ignore_then(text::ident())
+            .map(|name| (true, name)),
+        text::ident().map(|name| (false, name)),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn parse_type(input: &str) -> TypeAnn {
-        type_ann_parser().then_ignore(end()).parse(input).unwrap()
+        let file = SourceFile::new(FileId(0), "input.crochet", input);
+        type_ann_parser()
+            .then_ignore(end())
+            .parse(crate::stream(&file))
+            .unwrap()
     }
 
     #[test]
@@ -195,4 +544,66 @@ mod tests {
         insta::assert_debug_snapshot!(parse_type("(A | B) & (C | D)"));
         insta::assert_debug_snapshot!(parse_type("(A, B) => C & D"));
     }
+
+    #[test]
+    fn readonly_types() {
+        insta::assert_debug_snapshot!(parse_type("{readonly x: number, y?: string}"));
+        insta::assert_debug_snapshot!(parse_type("{readonly: boolean}"));
+        insta::assert_debug_snapshot!(parse_type("readonly string[]"));
+        insta::assert_debug_snapshot!(parse_type("readonly [number, string] | undefined"));
+    }
+
+    #[test]
+    fn index_signatures() {
+        insta::assert_debug_snapshot!(parse_type("{[key: string]: number}"));
+        insta::assert_debug_snapshot!(parse_type("{name: string, readonly [key: string]: string}"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn multiple_index_signatures() {
+        parse_type("{[key: string]: number, [index: number]: number}");
+    }
+
+    #[test]
+    fn type_operators() {
+        insta::assert_debug_snapshot!(parse_type("keyof Point"));
+        insta::assert_debug_snapshot!(parse_type("typeof point"));
+        insta::assert_debug_snapshot!(parse_type("Point[\"x\"]"));
+        insta::assert_debug_snapshot!(parse_type("keyof typeof point"));
+        insta::assert_debug_snapshot!(parse_type("Point[\"x\"][]"));
+    }
+
+    #[test]
+    fn conditional_types() {
+        insta::assert_debug_snapshot!(parse_type("T extends string ? \"str\" : \"other\""));
+        insta::assert_debug_snapshot!(parse_type("T extends (infer E)[] ? E : never"));
+        insta::assert_debug_snapshot!(parse_type("A | B extends C ? D : E | F"));
+    }
+
+    #[test]
+    fn mapped_types() {
+        insta::assert_debug_snapshot!(parse_type("{[K in keyof T]?: T[K]}"));
+        insta::assert_debug_snapshot!(parse_type("{-readonly [K in keyof T]-?: T[K]}"));
+        insta::assert_debug_snapshot!(parse_type("{readonly [K in \"a\" | \"b\"]: number}"));
+    }
+
+    #[test]
+    fn rest_types() {
+        insta::assert_debug_snapshot!(parse_type("[number, ...string[]]"));
+        insta::assert_debug_snapshot!(parse_type("(...args: number[]) => undefined"));
+        insta::assert_debug_snapshot!(parse_type("<T, U>([...T, U]) => U"));
+    }
+
+    #[test]
+    fn lam_param_names() {
+        insta::assert_debug_snapshot!(parse_type("(x: number, y: string) => boolean"));
+    }
+
+    #[test]
+    fn template_literal_types() {
+        insta::assert_debug_snapshot!(parse_type("`on${string}`"));
+        insta::assert_debug_snapshot!(parse_type("`/users/${number}/posts/${number}`"));
+        insta::assert_debug_snapshot!(parse_type("`${\"top\" | \"bottom\"}-${Side}`"));
+    }
 }
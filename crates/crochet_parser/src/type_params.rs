@@ -4,8 +4,8 @@ use crochet_ast::*;
 use crate::util::just_with_padding;
 
 pub fn type_params(
-    type_ann: BoxedParser<'static, char, TypeAnn, Simple<char>>,
-) -> BoxedParser<'static, char, Vec<TypeParam>, Simple<char>> {
+    type_ann: BoxedParser<'static, char, TypeAnn, Simple<char, Span>>,
+) -> BoxedParser<'static, char, Vec<TypeParam>, Simple<char, Span>> {
 
     let ident = text::ident().map_with_span(|name, span| Ident { span, name });
 
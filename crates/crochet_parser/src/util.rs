@@ -1,7 +1,8 @@
 use chumsky::prelude::*;
 use chumsky::primitive::*;
 use chumsky::text::Padded;
+use crochet_ast::Span;
 
-pub fn just_with_padding(inputs: &str) -> Padded<Just<char, &str, Simple<char>>> {
+pub fn just_with_padding(inputs: &str) -> Padded<Just<char, &str, Simple<char, Span>>> {
     just(inputs).padded()
 }